@@ -1,2 +1,34 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Models of the functions that programs use to get random bytes from the OS,
+// e.g. through `libc::getrandom`. They fill the buffer with arbitrary bytes.
+
+#include <stddef.h>
+#include <sys/types.h>
+
+unsigned char __nondet_uchar(void);
+
+static void __rmc_fill_nondet(void *buf, size_t len)
+{
+    unsigned char *bytes = buf;
+    for (size_t i = 0; i < len; i++) {
+        bytes[i] = __nondet_uchar();
+    }
+}
+
+ssize_t getrandom(void *buf, size_t buflen, unsigned int flags)
+{
+    __rmc_fill_nondet(buf, buflen);
+    return buflen;
+}
+
+int getentropy(void *buffer, size_t length)
+{
+    // `getentropy` fails if more than 256 bytes are requested.
+    if (length > 256) {
+        return -1;
+    }
+    __rmc_fill_nondet(buffer, length);
+    return 0;
+}
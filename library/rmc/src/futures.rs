@@ -0,0 +1,85 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module contains a minimal executor that allows verifying asynchronous code.
+use crate::any;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+/// Polls the given future until it completes and returns its output.
+///
+/// Since there is a single future and nothing else to run, the executor simply polls it again
+/// whenever it returns `Poll::Pending`. A future that never completes will make the
+/// verification loop forever, so an unwinding bound is needed in that case.
+///
+/// # Example:
+///
+/// ```rust
+/// async fn add(a: u32, b: u32) -> u32 {
+///     a + b
+/// }
+///
+/// let sum = rmc::block_on(add(1, 2));
+/// assert!(sum == 3);
+/// ```
+pub fn block_on<T>(mut fut: impl Future<Output = T>) -> T {
+    let waker = unsafe { Waker::from_raw(NOOP_RAW_WAKER) };
+    let cx = &mut Context::from_waker(&waker);
+    // SAFETY: we shadow the original binding, so it cannot be accessed again for the rest of the
+    // scope. This is the same as what the `pin_mut!` macro does.
+    let mut fut = unsafe { Pin::new_unchecked(&mut fut) };
+    loop {
+        if let Poll::Ready(res) = fut.as_mut().poll(cx) {
+            return res;
+        }
+    }
+}
+
+/// A future that may return `Poll::Pending` once before it completes.
+///
+/// Awaiting it models a suspension point whose resumption is out of the program's control, e.g.
+/// waiting for I/O to be ready. This allows verifying that a state machine behaves correctly
+/// whether or not it gets suspended there.
+///
+/// # Example:
+///
+/// ```rust
+/// rmc::block_on(async {
+///     rmc::futures::pending_nondet().await;
+/// });
+/// ```
+pub fn pending_nondet() -> PendingNondet {
+    PendingNondet { polled: false }
+}
+
+/// The future returned by [`pending_nondet`].
+pub struct PendingNondet {
+    polled: bool,
+}
+
+impl Future for PendingNondet {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.polled || any() {
+            Poll::Ready(())
+        } else {
+            self.polled = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// A waker that does nothing, since `block_on` polls its future in a loop anyway.
+const NOOP_RAW_WAKER: RawWaker = RawWaker::new(core::ptr::null(), &NOOP_WAKER_VTABLE);
+
+const NOOP_WAKER_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(noop_clone, noop_wake, noop_wake, noop_wake);
+
+unsafe fn noop_clone(_data: *const ()) -> RawWaker {
+    NOOP_RAW_WAKER
+}
+
+unsafe fn noop_wake(_data: *const ()) {}
@@ -3,10 +3,13 @@
 #![feature(rustc_attrs)] // Used for rustc_diagnostic_item.
 
 pub mod arbitrary;
+pub mod futures;
 pub mod invariant;
 pub mod slice;
+pub mod std_models;
 
 pub use arbitrary::Arbitrary;
+pub use futures::block_on;
 pub use invariant::Invariant;
 
 /// Creates an assumption that will be valid after this statement run. Note that the assumption
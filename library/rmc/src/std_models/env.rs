@@ -0,0 +1,100 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Models of `std::env`. Environment variables are arbitrary and may be unset, and the command
+//! line has up to `MAX_ARGS` arbitrary arguments, starting with the name of the program.
+use super::{any_string, MAX_ARGS};
+use crate::{any, assume};
+use std::env::{Args, ArgsOs, VarError};
+use std::ffi::{OsStr, OsString};
+use std::mem::transmute;
+use std::vec;
+
+/// Models `std::env::_var`, which implements `std::env::var`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelEnvVar"]
+pub fn var(_key: &OsStr) -> Result<String, VarError> {
+    if any() { Ok(any_string()) } else { Err(VarError::NotPresent) }
+}
+
+/// Models `std::env::_var_os`, which implements `std::env::var_os`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelEnvVarOs"]
+pub fn var_os(_key: &OsStr) -> Option<OsString> {
+    if any() { Some(OsString::from(any_string())) } else { None }
+}
+
+// `Args` wraps `ArgsOs`, which wraps a `vec::IntoIter<OsString>` on unix. The representation of
+// an `OsString` is a `Vec<u8>`, just like a `String`.
+#[inline]
+fn iter(args: &mut ArgsOs) -> &mut vec::IntoIter<OsString> {
+    unsafe { transmute(args) }
+}
+
+#[inline]
+fn iter_ref(args: &ArgsOs) -> &vec::IntoIter<OsString> {
+    unsafe { transmute(args) }
+}
+
+/// Models `std::env::args_os`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelEnvArgsOs"]
+pub fn args_os() -> ArgsOs {
+    let len: usize = any();
+    assume(len >= 1 && len <= MAX_ARGS);
+    let mut args = Vec::with_capacity(len);
+    for _ in 0..len {
+        args.push(OsString::from(any_string()));
+    }
+    unsafe { transmute(args.into_iter()) }
+}
+
+/// Models `std::env::args`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelEnvArgs"]
+pub fn args() -> Args {
+    unsafe { transmute(args_os()) }
+}
+
+/// Models `<ArgsOs as Iterator>::next`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelArgsOsNext"]
+pub fn args_os_next(args: &mut ArgsOs) -> Option<OsString> {
+    iter(args).next()
+}
+
+/// Models `<ArgsOs as Iterator>::size_hint`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelArgsOsSizeHint"]
+pub fn args_os_size_hint(args: &ArgsOs) -> (usize, Option<usize>) {
+    iter_ref(args).size_hint()
+}
+
+/// Models `<ArgsOs as ExactSizeIterator>::len`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelArgsOsLen"]
+pub fn args_os_len(args: &ArgsOs) -> usize {
+    iter_ref(args).len()
+}
+
+/// Models `<Args as Iterator>::next`. The arguments are ASCII, so they are valid strings.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelArgsNext"]
+pub fn args_next(args: &mut Args) -> Option<String> {
+    let args: &mut ArgsOs = unsafe { transmute(args) };
+    args_os_next(args).map(|arg| unsafe { transmute::<OsString, String>(arg) })
+}
+
+/// Models `<Args as Iterator>::size_hint`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelArgsSizeHint"]
+pub fn args_size_hint(args: &Args) -> (usize, Option<usize>) {
+    args_os_size_hint(unsafe { transmute(args) })
+}
+
+/// Models `<Args as ExactSizeIterator>::len`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelArgsLen"]
+pub fn args_len(args: &Args) -> usize {
+    args_os_len(unsafe { transmute(args) })
+}
@@ -0,0 +1,88 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Models of `std::fs`. Opening a file may fail, and files have arbitrary contents of up to
+//! `MAX_FILE_LEN` bytes.
+use super::{any_ascii, MAX_FILE_LEN};
+use crate::{any, assume};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::mem::transmute;
+use std::path::Path;
+
+#[inline]
+fn any_bytes(bytes: &mut Vec<u8>) -> usize {
+    let len: usize = any();
+    assume(len <= MAX_FILE_LEN);
+    for _ in 0..len {
+        bytes.push(any());
+    }
+    len
+}
+
+/// Models `std::fs::OpenOptions::_open`, which implements `File::open` and `File::create`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelFsOpen"]
+pub fn open(_options: &OpenOptions, _path: &Path) -> io::Result<File> {
+    if any() {
+        return Err(io::Error::from(io::ErrorKind::NotFound));
+    }
+    // A `File` wraps an `OwnedFd`, i.e. a non-negative `RawFd` on unix.
+    let fd: i32 = any();
+    assume(fd >= 0);
+    Ok(unsafe { transmute(fd) })
+}
+
+/// Models `<File as Read>::read`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelFileRead"]
+pub fn read(_file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+    let len: usize = any();
+    assume(len <= buf.len());
+    for byte in &mut buf[..len] {
+        *byte = any();
+    }
+    Ok(len)
+}
+
+/// Models `<File as Read>::read_to_end`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelFileReadToEnd"]
+pub fn read_to_end(_file: &mut File, buf: &mut Vec<u8>) -> io::Result<usize> {
+    Ok(any_bytes(buf))
+}
+
+/// Models `<File as Read>::read_to_string`. The contents of the file are ASCII.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelFileReadToString"]
+pub fn read_to_string(_file: &mut File, buf: &mut String) -> io::Result<usize> {
+    let contents = any_ascii(MAX_FILE_LEN);
+    buf.push_str(&contents);
+    Ok(contents.len())
+}
+
+/// Models `std::fs::read`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelFsRead"]
+pub fn read_file(path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = open(&any_options(), path)?;
+    let mut bytes = Vec::new();
+    read_to_end(&mut file, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Models `std::fs::read_to_string`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelFsReadToString"]
+pub fn read_file_to_string(path: &Path) -> io::Result<String> {
+    let mut file = open(&any_options(), path)?;
+    let mut string = String::new();
+    read_to_string(&mut file, &mut string)?;
+    Ok(string)
+}
+
+/// The models ignore the options, and `OpenOptions::new` is not available to RMC.
+#[inline]
+fn any_options() -> OpenOptions {
+    unsafe { crate::any_raw() }
+}
@@ -0,0 +1,50 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module contains models of the standard library functions that interact with the
+//! operating system, e.g. `std::env::var` or `std::time::Instant::now`.
+//!
+//! These functions are not generic, so their code is not available to RMC, and they end up in
+//! calls to foreign `libc` functions anyway. Instead, RMC replaces every call to one of them by a
+//! call to its model, which returns a nondeterministic but well-typed result: the command line
+//! arguments, environment variables and file contents are arbitrary (bounded) values, instants
+//! are monotonic, and opening a file may fail.
+//!
+//! The models are `#[inline]` so that their code is available in the crate under verification,
+//! and each of them is a diagnostic item that the compiler maps to the function that it models.
+//! They rely on the layout of the private types of the standard library that RMC is built with.
+
+pub mod env;
+pub mod fs;
+pub mod time;
+
+use crate::{any, assume};
+
+/// The maximum length of the strings returned by the models, e.g. environment variables.
+pub const MAX_STRING_LEN: usize = 8;
+
+/// The maximum number of command line arguments, including the name of the program.
+pub const MAX_ARGS: usize = 4;
+
+/// The maximum number of bytes that reading a whole file returns.
+pub const MAX_FILE_LEN: usize = 16;
+
+/// Returns an arbitrary ASCII string of length at most `MAX_STRING_LEN`.
+#[inline]
+pub fn any_string() -> String {
+    any_ascii(MAX_STRING_LEN)
+}
+
+#[inline]
+fn any_ascii(max_len: usize) -> String {
+    let len: usize = any();
+    assume(len <= max_len);
+    let mut bytes = Vec::with_capacity(len);
+    for _ in 0..len {
+        let byte: u8 = any();
+        assume(byte.is_ascii());
+        bytes.push(byte);
+    }
+    // SAFETY: ASCII strings are valid UTF-8.
+    unsafe { String::from_utf8_unchecked(bytes) }
+}
@@ -0,0 +1,123 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Models of `std::time`. The system time is arbitrary, and instants are arbitrary but monotonic.
+use crate::{any, assume};
+use std::mem::transmute;
+use std::time::{Duration, Instant, SystemTime, SystemTimeError};
+
+const NANOS_PER_SEC: i64 = 1_000_000_000;
+
+/// The representation of a point in time by the standard library.
+trait RawTime: Copy + Ord {
+    fn any() -> Self;
+
+    /// `self - earlier`, or `Err(earlier - self)` if `earlier` is later.
+    fn sub(self, earlier: Self) -> Result<Duration, Duration>;
+}
+
+/// The representation of `SystemTime`, and of `Instant` except on macOS, i.e. a
+/// `libc::timespec`.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Timespec {
+    tv_sec: i64,
+    tv_nsec: i64,
+}
+
+impl RawTime for Timespec {
+    #[inline]
+    fn any() -> Timespec {
+        let t = Timespec { tv_sec: any(), tv_nsec: any() };
+        assume(t.tv_nsec >= 0 && t.tv_nsec < NANOS_PER_SEC);
+        t
+    }
+
+    #[inline]
+    fn sub(self, earlier: Timespec) -> Result<Duration, Duration> {
+        if self < earlier {
+            return Err(earlier.sub(self).unwrap_or_default());
+        }
+        // The difference of the seconds fits in a `u64`.
+        let secs = self.tv_sec.wrapping_sub(earlier.tv_sec) as u64;
+        if self.tv_nsec >= earlier.tv_nsec {
+            Ok(Duration::new(secs, (self.tv_nsec - earlier.tv_nsec) as u32))
+        } else {
+            Ok(Duration::new(secs - 1, (self.tv_nsec + NANOS_PER_SEC - earlier.tv_nsec) as u32))
+        }
+    }
+}
+
+/// On macOS, an `Instant` is a number of ticks, which we treat as nanoseconds.
+impl RawTime for u64 {
+    #[inline]
+    fn any() -> u64 {
+        any()
+    }
+
+    #[inline]
+    fn sub(self, earlier: u64) -> Result<Duration, Duration> {
+        if self < earlier {
+            Err(Duration::from_nanos(earlier - self))
+        } else {
+            Ok(Duration::from_nanos(self - earlier))
+        }
+    }
+}
+
+#[cfg(not(target_vendor = "apple"))]
+type RawInstant = Timespec;
+#[cfg(target_vendor = "apple")]
+type RawInstant = u64;
+
+/// The last instant returned by `Instant::now`.
+static mut LAST_INSTANT: Option<RawInstant> = None;
+
+/// Models `std::time::Instant::now`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelInstantNow"]
+pub fn instant_now() -> Instant {
+    let now = RawInstant::any();
+    unsafe {
+        if let Some(last) = LAST_INSTANT {
+            assume(now >= last);
+        }
+        LAST_INSTANT = Some(now);
+        transmute(now)
+    }
+}
+
+/// Models `std::time::Instant::duration_since`, which saturates to zero.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelInstantDurationSince"]
+pub fn instant_duration_since(instant: &Instant, earlier: Instant) -> Duration {
+    let (instant, earlier): (RawInstant, RawInstant) =
+        unsafe { (transmute(*instant), transmute(earlier)) };
+    instant.sub(earlier).unwrap_or_default()
+}
+
+/// Models `std::time::Instant::elapsed`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelInstantElapsed"]
+pub fn instant_elapsed(instant: &Instant) -> Duration {
+    instant_duration_since(&instant_now(), *instant)
+}
+
+/// Models `std::time::SystemTime::now`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelSystemTimeNow"]
+pub fn system_time_now() -> SystemTime {
+    unsafe { transmute(Timespec::any()) }
+}
+
+/// Models `std::time::SystemTime::duration_since`.
+#[inline]
+#[rustc_diagnostic_item = "RmcModelSystemTimeDurationSince"]
+pub fn system_time_duration_since(
+    time: &SystemTime,
+    earlier: SystemTime,
+) -> Result<Duration, SystemTimeError> {
+    let (time, earlier): (Timespec, Timespec) = unsafe { (transmute(*time), transmute(earlier)) };
+    // A `SystemTimeError` wraps the `Duration` by which `earlier` is later.
+    time.sub(earlier).map_err(|duration| unsafe { transmute(duration) })
+}
@@ -49,3 +49,20 @@ pub fn proof(_attr: TokenStream, item: TokenStream) -> TokenStream {
     //     $item
     // )
 }
+
+/// Check that the annotated harness frees all the heap memory it allocates.
+#[cfg(not(rmc))]
+#[proc_macro_attribute]
+pub fn check_leaks(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[cfg(rmc)]
+#[proc_macro_attribute]
+pub fn check_leaks(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+
+    result.extend("#[rmctool::check_leaks]".parse::<TokenStream>().unwrap());
+    result.extend(item);
+    result
+}
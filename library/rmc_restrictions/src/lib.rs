@@ -1,7 +1,10 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-// Data structures to represent vtable trait function pointer restrictions
+// Data structures to represent vtable trait function pointer restrictions.
+// Calls through plain function pointers use the same structures: the "trait" is
+// either the goto type of the function pointer or the name of the call's wrapper,
+// and the vtable index is 0.
 use cbmc::InternedString;
 use serde::{Deserialize, Serialize};
 
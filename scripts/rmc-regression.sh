@@ -30,6 +30,9 @@ check-cbmc-viewer-version.py --major 2 --minor 5
 # Build tool for linking RMC pointer restrictions
 cargo build --release --manifest-path src/tools/rmc-link-restrictions/Cargo.toml 
 
+# Build tool for linking symbol tables
+cargo build --release --manifest-path src/tools/rmc-link-goto/Cargo.toml
+
 # Standalone rmc tests, expected tests, and cargo tests
 ./x.py build -i src/tools/compiletest --stage 0
 export COMPILETEST_FORCE_STAGE0=1  # We don't care about the stage anymore. Remove this once we replace ./x.py test
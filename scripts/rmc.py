@@ -15,8 +15,10 @@ RMC_CFG = "rmc"
 RMC_RUSTC_EXE = "rmc-rustc"
 MY_PATH = pathlib.Path(__file__).parent.parent.absolute()
 GEN_C_LIB = MY_PATH / "library" / "rmc" / "gen_c_lib.c"
+RMC_LINK_GOTO_EXE = MY_PATH / "target" / "release" / "rmc-link-goto"
 EXIT_CODE_SUCCESS = 0
 CBMC_VERIFICATION_FAILURE_EXIT_CODE = 10
+RESTRICTION_WARNING = "warning: Call site "
 
 MEMORY_SAFETY_CHECKS = ["--bounds-check",
                         "--pointer-check",
@@ -211,14 +213,67 @@ def run_cmd(
     return process.returncode
 
 
-def compiler_flags(mangler, symbol_table_passes, restrict_vtable):
+# Reads the passes of a pipeline file: one per line, where `#` starts a comment
+def read_passes_file(passes_file):
+    if passes_file is None:
+        return []
+    ensure(os.path.isfile(passes_file), f"Invalid path to symbol table passes file: {passes_file}")
+    with open(passes_file) as f:
+        lines = [line.split("#")[0].strip() for line in f]
+    return [line for line in lines if line]
+
+
+def compiler_flags(mangler, symbol_table_passes, restrict_vtable, restrict_fn_ptrs, panic_unwind,
+                   havoc_inline_asm, inline_asm_models, simple_hashing, alloc_may_fail, heap_bound,
+                   check_leaks, prune_unreachable, function, extra_passes, passes_file,
+                   dump_after, dump_goto):
     rmc_flags = ["--goto-c"]
+    # The user's passes run in the order given, those of the file after those of the flag. We
+    # read the file here to put our passes around them: pruning runs first, so that the user's
+    # passes only see the reachable symbols, and ours (i.e. `gen-c`) run last, since they rename
+    # the symbols that the user's passes refer to.
+    user_passes = extra_passes + read_passes_file(passes_file)
+    symbol_table_passes = user_passes + symbol_table_passes
+    if prune_unreachable:
+        symbol_table_passes = ["reachability"] + symbol_table_passes
+        rmc_flags.append(f"--entry-point={function}")
+
     if symbol_table_passes:
         rmc_flags.append(f"--symbol-table-passes={','.join(symbol_table_passes)}")
 
+    if dump_after:
+        rmc_flags.append(f"--dump-after={','.join(dump_after)}")
+
+    for dumped_fn in dump_goto:
+        rmc_flags.append(f"--dump-goto={dumped_fn}")
+
     if restrict_vtable:
         rmc_flags.append("--restrict-vtable-fn-ptrs")
 
+    if restrict_fn_ptrs:
+        rmc_flags.append("--restrict-fn-ptrs")
+
+    if panic_unwind:
+        rmc_flags.append("--panic-unwind")
+
+    if havoc_inline_asm:
+        rmc_flags.append("--havoc-inline-asm")
+
+    for model in inline_asm_models:
+        rmc_flags.append(f"--inline-asm-model={model}")
+
+    if simple_hashing:
+        rmc_flags.append("--simple-hashing")
+
+    if alloc_may_fail:
+        rmc_flags.append("--alloc-may-fail")
+
+    if heap_bound is not None:
+        rmc_flags.append(f"--heap-bound={heap_bound}")
+
+    if check_leaks:
+        rmc_flags.append("--check-leaks")
+
     rustc_flags = ["-Z", f"symbol-mangling-version={mangler}"]
 
     if "RUSTFLAGS" in os.environ:
@@ -239,7 +294,21 @@ def compile_single_rust_file(
         atexit.register(delete_file, base + ".rmc-metadata.json")
 
     build_cmd = [RMC_RUSTC_EXE] + compiler_flags(extra_args.mangler, symbol_table_passes,
-                                                 extra_args.restrict_vtable)
+                                                 extra_args.restrict_vtable,
+                                                 extra_args.restrict_fn_ptrs,
+                                                 extra_args.panic_unwind,
+                                                 extra_args.havoc_inline_asm,
+                                                 extra_args.inline_asm_model,
+                                                 extra_args.simple_hashing,
+                                                 extra_args.alloc_may_fail,
+                                                 extra_args.heap_bound,
+                                                 extra_args.check_leaks,
+                                                 extra_args.prune_unreachable,
+                                                 extra_args.function,
+                                                 extra_args.symbol_table_passes,
+                                                 extra_args.symbol_table_passes_file,
+                                                 extra_args.dump_after,
+                                                 extra_args.dump_goto)
 
     if extra_args.use_abs:
         build_cmd += ["-Z", "force-unstable-if-unmarked=yes",
@@ -249,6 +318,9 @@ def compile_single_rust_file(
     if extra_args.tests and "--test" not in build_cmd:
         build_cmd += ["--test"]
 
+    if extra_args.build_target:
+        build_cmd += ["--target", extra_args.build_target]
+
     build_cmd += ["-o", base + ".o", input_filename]
 
     build_env = os.environ
@@ -272,11 +344,25 @@ def cargo_build(
     ensure(os.path.isdir(crate), f"Invalid path to crate: {crate}")
 
     rustflags = compiler_flags(extra_args.mangler, symbol_table_passes,
-                               extra_args.restrict_vtable)
+                               extra_args.restrict_vtable, extra_args.restrict_fn_ptrs,
+                               extra_args.panic_unwind,
+                               extra_args.havoc_inline_asm, extra_args.inline_asm_model,
+                               extra_args.simple_hashing, extra_args.alloc_may_fail,
+                               extra_args.heap_bound, extra_args.check_leaks,
+                               extra_args.prune_unreachable, extra_args.function,
+                               extra_args.symbol_table_passes,
+                               extra_args.symbol_table_passes_file, extra_args.dump_after,
+                               extra_args.dump_goto)
     cargo_cmd = ["cargo", "build"] if not extra_args.tests else ["cargo", "test", "--no-run"]
     build_cmd = cargo_cmd + ["--target-dir", str(target_dir)]
     if extra_args.build_target:
         build_cmd += ["--target", str(extra_args.build_target)]
+    if extra_args.build_std:
+        # The standard library must be built in the same invocation as the crate, or its
+        # symbols get different names than the ones the crate refers to.
+        ensure(extra_args.build_target, "--build-std requires --build-target")
+        ensure(not extra_args.prune_unreachable, "Incompatible flags: --build-std, --prune-unreachable")
+        build_cmd += ["-Z", "build-std"]
     build_env = os.environ
     # rmc-compiler expects the rmc flags to precede rustc flags but cargo is unpredictable. Use this to allow us to
     # separate them programmatically.
@@ -302,6 +388,13 @@ def append_unwind_tip(text):
                   "[RMC] tip: Consider increasing the unwinding value or disabling `--unwinding-assertions`.\n")
     return text + unwind_tip
 
+# Prints the warnings of rmc-link-restrictions about call sites, and returns the output as is
+def print_restriction_warnings(text):
+    for line in text.splitlines():
+        if line.startswith(RESTRICTION_WARNING):
+            print(line)
+    return text
+
 # Generates a goto program from a symbol table
 def symbol_table_to_gotoc(json_files, verbose=False, keep_temps=False, dry_run=False):
     out_files = []
@@ -317,9 +410,37 @@ def symbol_table_to_gotoc(json_files, verbose=False, keep_temps=False, dry_run=F
 
     return out_files
 
+# Links the symbol tables of several crates into a single one
+def link_symbol_tables(json_files, out_file, verbose=False, keep_temps=False, dry_run=False):
+    ensure(dry_run or os.path.isfile(RMC_LINK_GOTO_EXE),
+           f"Could not find {RMC_LINK_GOTO_EXE}. Build it with "
+           "`cargo build --release --manifest-path src/tools/rmc-link-goto/Cargo.toml`")
+    cmd = [str(RMC_LINK_GOTO_EXE), out_file] + json_files
+    if not keep_temps:
+        atexit.register(delete_file, out_file)
+    if run_cmd(cmd, label="rmc-link-goto", verbose=verbose, dry_run=dry_run) != EXIT_CODE_SUCCESS:
+        raise Exception("Failed to run command: {}".format(" ".join(cmd)))
+    return out_file
+
+# Returns the goto-cc flags that compile C code for the pointer width and endianness of the target
+def target_c_flags(build_target):
+    if build_target is None:
+        return []
+    cmd = [RMC_RUSTC_EXE, "--print", "cfg", "--target", build_target]
+    cfg = subprocess.run(cmd, stdout=subprocess.PIPE, universal_newlines=True, check=True).stdout
+    flags = []
+    if 'target_pointer_width="32"' in cfg:
+        flags.append("-m32")
+    elif 'target_pointer_width="16"' in cfg:
+        flags.append("-m16")
+    if 'target_endian="big"' in cfg:
+        flags.append("-mbig-endian")
+    return flags
+
 # Links in external C programs into a goto program
-def link_c_lib(srcs, dst, c_lib, verbose=False, quiet=False, function="main", dry_run=False, keep_temps=False):
-    cmd = ["goto-cc"] + ["--function", function] + srcs + c_lib + ["-o", dst]
+def link_c_lib(srcs, dst, c_lib, verbose=False, quiet=False, function="main", dry_run=False, keep_temps=False,
+               build_target=None):
+    cmd = ["goto-cc"] + target_c_flags(build_target) + ["--function", function] + srcs + c_lib + ["-o", dst]
     if not keep_temps:
         atexit.register(delete_file, dst)
     if run_cmd(cmd, label="goto-cc", verbose=verbose, quiet=quiet, dry_run=dry_run) != EXIT_CODE_SUCCESS:
@@ -435,7 +556,12 @@ def process_vtable_restrictions(restrictions_filename, verbose=False, dry_run=Fa
     cmd = ["cargo", "run", "--release", "--manifest-path", "src/tools/rmc-link-restrictions/Cargo.toml"]
     outname = os.path.join(os.path.dirname(os.path.abspath(restrictions_filename)), "linked-restrictions.json")
     cmd += ["--", restrictions_filename, outname]
-    if (run_cmd(cmd, label="rmc-link-restrictions", verbose=verbose, dry_run=dry_run) != EXIT_CODE_SUCCESS):
+    # The output is only shown on failure or with --verbose, so show the warnings about call sites
+    # without any possible target on their own: CBMC cuts off every execution through them.
+    # The output also has the warnings of cargo, which we leave out.
+    scanners = [] if verbose else [Scanner(f"(?m)^{RESTRICTION_WARNING}", print_restriction_warnings)]
+    if (run_cmd(cmd, label="rmc-link-restrictions", verbose=verbose, scanners=scanners,
+                dry_run=dry_run) != EXIT_CODE_SUCCESS):
         raise Exception("Failed to run command: {}".format(" ".join(cmd)))
     return outname
 
@@ -254,6 +254,22 @@ def add_other_flags(make_group, add_flag, config):
              help="Do not produce error return code on CBMC verification failure")
     add_flag(group, "--dry-run", default=False, action=BooleanOptionalAction,
              help="Print commands instead of running them")
+    add_flag(group, "--panic-unwind", default=False, action=BooleanOptionalAction,
+             help="Model panics as unwinding, which allows verifying code that catches panics")
+    add_flag(group, "--havoc-inline-asm", default=False, action=BooleanOptionalAction,
+             help="Model inline assembly as assigning arbitrary values to its outputs")
+    add_flag(group, "--inline-asm-model", action="append", default=[], metavar="FUNCTION=MODEL",
+             help="Replace the inline assembly in FUNCTION by a call to the function MODEL")
+    add_flag(group, "--simple-hashing", default=False, action=BooleanOptionalAction,
+             help="Replace the SipHash hasher of HashMap and HashSet by a cheap hasher")
+    add_flag(group, "--alloc-may-fail", default=False, action=BooleanOptionalAction,
+             help="Model heap allocations as nondeterministically failing")
+    add_flag(group, "--heap-bound", type=int, metavar="BYTES",
+             help="Make heap allocations fail once BYTES bytes are allocated")
+    add_flag(group, "--check-leaks", default=False, action=BooleanOptionalAction,
+             help="Check that harnesses free all the heap memory they allocate")
+    add_flag(group, "--prune-unreachable", default=False, action=BooleanOptionalAction,
+             help="Remove the symbols that are unreachable from the entry point before running CBMC")
 
 # Add flags we don't expect end-users to use.
 def add_developer_flags(make_group, add_flag, config):
@@ -269,6 +285,16 @@ def add_developer_flags(make_group, add_flag, config):
              help="Choose abstraction for modules of standard library if available")
     add_flag(group, "--restrict-vtable", default=False, action=BooleanOptionalAction,
              help="Restrict the targets of virtual table function pointer calls")
+    add_flag(group, "--restrict-fn-ptrs", default=False, action=BooleanOptionalAction,
+             help="Restrict the targets of calls through function pointers")
+    add_flag(group, "--symbol-table-passes", action="append", default=[], metavar="PASS",
+             help="Transform the symbol table with PASS, written NAME or NAME:OPTION=VALUE; may be repeated")
+    add_flag(group, "--symbol-table-passes-file", type=pl.Path, metavar="FILE",
+             help="Transform the symbol table with the passes listed in FILE, one per line")
+    add_flag(group, "--dump-after", action="append", default=[], metavar="PASS",
+             help="Write the symbol table after each run of PASS, next to the symbol table; may be repeated")
+    add_flag(group, "--dump-goto", action="append", default=[], metavar="FUNCTION",
+             help="Write the MIR and the goto program of FUNCTION next to the symbol table; may be repeated")
 
 # Adds the flags common to both rmc and cargo-rmc.
 # Allows you to specify flags/groups of flags to not add.
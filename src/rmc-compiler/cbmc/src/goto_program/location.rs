@@ -14,6 +14,17 @@ pub enum Location {
     /// Location in user code.
     /// `function` is `None` for global, `Some(function_name)` for function local.
     Loc { file: InternedString, function: Option<InternedString>, line: u64, col: Option<u64> },
+    /// Location of a property (e.g. an assertion) in user code.
+    /// `comment` describes the property and `property_class` names the kind of check,
+    /// which CBMC uses to group and name the resulting verification conditions.
+    Property {
+        file: InternedString,
+        function: Option<InternedString>,
+        line: u64,
+        col: Option<u64>,
+        comment: InternedString,
+        property_class: InternedString,
+    },
 }
 
 /// Getters and predicates
@@ -34,14 +45,14 @@ pub fn is_builtin(&self) -> bool {
 
     pub fn filename(&self) -> Option<String> {
         match self {
-            Location::Loc { file, .. } => Some(file.to_string()),
+            Location::Loc { file, .. } | Location::Property { file, .. } => Some(file.to_string()),
             _ => None,
         }
     }
 
     pub fn line(&self) -> Option<u64> {
         match self {
-            Location::Loc { line, .. } => Some(*line),
+            Location::Loc { line, .. } | Location::Property { line, .. } => Some(*line),
             _ => None,
         }
     }
@@ -57,7 +68,9 @@ pub fn short_string(&self) -> String {
             Location::BuiltinFunction { function_name, line: None } => {
                 format!("<{}>", function_name)
             }
-            Location::Loc { file, line, .. } => format!("{}:{}", file, line),
+            Location::Loc { file, line, .. } | Location::Property { file, line, .. } => {
+                format!("{}:{}", file, line)
+            }
         }
     }
 }
@@ -89,4 +102,25 @@ pub fn builtin_function<T: Into<InternedString>>(name: T, line: Option<u64>) ->
         let function_name = name.into();
         Location::BuiltinFunction { line, function_name }
     }
+
+    /// Attach a property description and class to an existing source location.
+    /// Locations without file information cannot carry a property and are returned unchanged.
+    pub fn create_location_with_property<T: Into<InternedString>, U: Into<InternedString>>(
+        comment: T,
+        property_class: U,
+        location: Location,
+    ) -> Location {
+        match location {
+            Location::Loc { file, function, line, col }
+            | Location::Property { file, function, line, col, .. } => Location::Property {
+                file,
+                function,
+                line,
+                col,
+                comment: comment.into(),
+                property_class: property_class.into(),
+            },
+            Location::None | Location::BuiltinFunction { .. } => location,
+        }
+    }
 }
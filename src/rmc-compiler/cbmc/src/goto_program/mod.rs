@@ -5,11 +5,13 @@
 mod builtin;
 mod expr;
 mod location;
+mod printer;
 mod stmt;
 mod symbol;
 mod symbol_table;
 pub mod symtab_transformer;
 mod typ;
+mod typ_interner;
 
 pub use builtin::BuiltinFn;
 pub use expr::{
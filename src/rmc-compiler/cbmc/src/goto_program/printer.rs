@@ -0,0 +1,672 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This module prints goto programs as a compact, C-like listing, for debugging.
+//!
+//! Unlike `--gen-c`, the listing keeps the symbol names of the symbol table, and annotates
+//! statements with their source location (e.g. `// main.rs:12`) whenever it changes.
+//! Operators that have no C equivalent are printed as calls, e.g. `overflow-plus(a, b)`.
+
+use super::{
+    BinaryOperand, CIntType, DatatypeComponent, Expr, ExprValue, Location, Parameter, SelfOperand,
+    Stmt, StmtBody, Symbol, SymbolTable, SymbolValues, Type, UnaryOperand,
+};
+use std::fmt::{self, Display, Formatter, Write};
+
+const INDENT: &str = "    ";
+
+/// Writes statements and expressions, keeping track of the indentation and of the last
+/// location that was printed.
+struct Printer<'a, W: Write> {
+    out: &'a mut W,
+    indent: usize,
+    /// Whether to annotate statements with their location.
+    locations: bool,
+    last_location: Option<String>,
+}
+
+impl<'a, W: Write> Printer<'a, W> {
+    fn new(out: &'a mut W) -> Self {
+        Printer { out, indent: 0, locations: true, last_location: None }
+    }
+
+    fn start_line(&mut self) -> fmt::Result {
+        for _ in 0..self.indent {
+            self.out.write_str(INDENT)?;
+        }
+        Ok(())
+    }
+
+    /// Ends the line, with a comment giving `loc` if it differs from the last one printed.
+    fn end_line(&mut self, loc: &Location) -> fmt::Result {
+        if self.locations && !loc.is_none() {
+            let loc = loc.to_string();
+            if self.last_location.as_ref() != Some(&loc) {
+                write!(self.out, "  // {}", loc)?;
+                self.last_location = Some(loc);
+            }
+        }
+        self.out.write_char('\n')
+    }
+
+    fn list(&mut self, exprs: &[Expr]) -> fmt::Result {
+        for (i, e) in exprs.iter().enumerate() {
+            if i > 0 {
+                self.out.write_str(", ")?;
+            }
+            self.expr(e)?;
+        }
+        Ok(())
+    }
+
+    fn parenthesized(&mut self, e: &Expr) -> fmt::Result {
+        self.out.write_char('(')?;
+        self.expr(e)?;
+        self.out.write_char(')')
+    }
+
+    /// Prints `e` as the operand of a prefix or binary operator, in parentheses unless it binds
+    /// tighter than those.
+    fn operand(&mut self, e: &Expr) -> fmt::Result {
+        match e.value() {
+            ExprValue::Assign { .. } | ExprValue::BinOp { .. } | ExprValue::If { .. } => {
+                self.parenthesized(e)
+            }
+            _ => self.expr(e),
+        }
+    }
+
+    /// Prints `e` as the operand of a postfix operator, e.g. a member access or a call.
+    fn postfix_operand(&mut self, e: &Expr) -> fmt::Result {
+        match e.value() {
+            ExprValue::AddressOf(_)
+            | ExprValue::Dereference(_)
+            | ExprValue::Typecast(_)
+            | ExprValue::UnOp { op: UnaryOperand::Bitnot, .. }
+            | ExprValue::UnOp { op: UnaryOperand::Not, .. }
+            | ExprValue::UnOp { op: UnaryOperand::UnaryMinus, .. } => self.parenthesized(e),
+            ExprValue::SelfOp { op: SelfOperand::Predecrement, .. }
+            | ExprValue::SelfOp { op: SelfOperand::Preincrement, .. } => self.parenthesized(e),
+            _ => self.operand(e),
+        }
+    }
+
+    fn expr(&mut self, e: &Expr) -> fmt::Result {
+        match e.value() {
+            ExprValue::AddressOf(e) => {
+                self.out.write_char('&')?;
+                self.operand(e)
+            }
+            ExprValue::Array { elems } | ExprValue::Struct { values: elems } => {
+                self.out.write_char('{')?;
+                self.list(elems)?;
+                self.out.write_char('}')
+            }
+            ExprValue::ArrayOf { elem } => {
+                self.out.write_str("ARRAY_OF(")?;
+                self.expr(elem)?;
+                self.out.write_char(')')
+            }
+            ExprValue::Assign { left, right } => {
+                self.expr(left)?;
+                self.out.write_str(" = ")?;
+                self.expr(right)
+            }
+            ExprValue::BinOp { op, lhs, rhs } => match binop_symbol(*op) {
+                Some(symbol) => {
+                    self.operand(lhs)?;
+                    write!(self.out, " {} ", symbol)?;
+                    self.operand(rhs)
+                }
+                None => {
+                    write!(self.out, "{}(", binop_name(*op))?;
+                    self.expr(lhs)?;
+                    self.out.write_str(", ")?;
+                    self.expr(rhs)?;
+                    self.out.write_char(')')
+                }
+            },
+            ExprValue::BoolConstant(b) => self.out.write_str(if *b { "TRUE" } else { "FALSE" }),
+            ExprValue::ByteExtract { e: inner, offset } => {
+                write!(self.out, "BYTE_EXTRACT<{}>(", e.typ())?;
+                self.expr(inner)?;
+                write!(self.out, ", {})", offset)
+            }
+            ExprValue::CBoolConstant(b) => self.out.write_str(if *b { "true" } else { "false" }),
+            ExprValue::Dereference(e) => {
+                self.out.write_char('*')?;
+                self.operand(e)
+            }
+            ExprValue::DoubleConstant(d) => write!(self.out, "{:?}", d),
+            ExprValue::FloatConstant(f) => write!(self.out, "{:?}f", f),
+            ExprValue::FunctionCall { function, arguments } => {
+                self.postfix_operand(function)?;
+                self.out.write_char('(')?;
+                self.list(arguments)?;
+                self.out.write_char(')')
+            }
+            ExprValue::If { c, t, e } => {
+                self.operand(c)?;
+                self.out.write_str(" ? ")?;
+                self.operand(t)?;
+                self.out.write_str(" : ")?;
+                self.operand(e)
+            }
+            ExprValue::Index { array, index } => {
+                self.postfix_operand(array)?;
+                self.out.write_char('[')?;
+                self.expr(index)?;
+                self.out.write_char(']')
+            }
+            ExprValue::IntConstant(i) => write!(self.out, "{}", i),
+            ExprValue::Member { lhs, field } => {
+                self.postfix_operand(lhs)?;
+                write!(self.out, ".{}", field)
+            }
+            ExprValue::Nondet => write!(self.out, "NONDET({})", e.typ()),
+            ExprValue::PointerConstant(0) => self.out.write_str("NULL"),
+            ExprValue::PointerConstant(p) => write!(self.out, "({}){:#x}", e.typ(), p),
+            ExprValue::SelfOp { op, e } => match op {
+                SelfOperand::Postdecrement => {
+                    self.postfix_operand(e)?;
+                    self.out.write_str("--")
+                }
+                SelfOperand::Postincrement => {
+                    self.postfix_operand(e)?;
+                    self.out.write_str("++")
+                }
+                SelfOperand::Predecrement => {
+                    self.out.write_str("--")?;
+                    self.operand(e)
+                }
+                SelfOperand::Preincrement => {
+                    self.out.write_str("++")?;
+                    self.operand(e)
+                }
+            },
+            ExprValue::StatementExpression { statements } => {
+                self.out.write_str("({\n")?;
+                self.indent += 1;
+                for s in statements {
+                    self.stmt(s)?;
+                }
+                self.indent -= 1;
+                self.start_line()?;
+                self.out.write_str("})")
+            }
+            ExprValue::StringConstant { s } => write!(self.out, "{:?}", s.to_string()),
+            ExprValue::Symbol { identifier } => write!(self.out, "{}", identifier),
+            ExprValue::Typecast(inner) => {
+                write!(self.out, "({})", e.typ())?;
+                self.operand(inner)
+            }
+            ExprValue::Union { value, field } => {
+                write!(self.out, "{{.{} = ", field)?;
+                self.expr(value)?;
+                self.out.write_char('}')
+            }
+            ExprValue::UnOp { op, e } => {
+                match op {
+                    UnaryOperand::Bitnot => self.out.write_char('~')?,
+                    UnaryOperand::Not => self.out.write_char('!')?,
+                    UnaryOperand::UnaryMinus => self.out.write_char('-')?,
+                    _ => {
+                        write!(self.out, "{}(", unop_name(*op))?;
+                        self.expr(e)?;
+                        return self.out.write_char(')');
+                    }
+                }
+                self.operand(e)
+            }
+            ExprValue::Vector { elems } => {
+                write!(self.out, "({}){{", e.typ())?;
+                self.list(elems)?;
+                self.out.write_char('}')
+            }
+        }
+    }
+
+    /// Prints `{ stmts }`, starting on the current line.
+    fn block(&mut self, stmts: &[Stmt], loc: &Location) -> fmt::Result {
+        self.out.write_char('{')?;
+        self.end_line(loc)?;
+        self.indent += 1;
+        for s in stmts {
+            self.stmt(s)?;
+        }
+        self.indent -= 1;
+        self.start_line()?;
+        self.out.write_char('}')
+    }
+
+    /// Prints `s` as the body of a compound statement, i.e. as a block.
+    fn body(&mut self, s: &Stmt) -> fmt::Result {
+        match s.body() {
+            StmtBody::Block(stmts) => self.block(stmts, s.location()),
+            _ => self.block(std::slice::from_ref(s), s.location()),
+        }
+    }
+
+    /// Prints a simple statement without its trailing `;`, e.g. the initialization of a loop.
+    fn inline_stmt(&mut self, s: &Stmt) -> fmt::Result {
+        let mut out = String::new();
+        Printer { out: &mut out, indent: 0, locations: false, last_location: None }.stmt(s)?;
+        self.out.write_str(out.trim_end().trim_end_matches(';'))
+    }
+
+    fn stmt(&mut self, s: &Stmt) -> fmt::Result {
+        let loc = s.location();
+        if let StmtBody::Label { label, body } = s.body() {
+            // Labels are outdented, like in C listings.
+            let indent = self.indent;
+            self.indent = indent.saturating_sub(1);
+            self.start_line()?;
+            self.indent = indent;
+            write!(self.out, "{}:", label)?;
+            self.end_line(loc)?;
+            return self.stmt(body);
+        }
+        self.start_line()?;
+        match s.body() {
+            StmtBody::Assign { lhs, rhs } => {
+                self.expr(lhs)?;
+                self.out.write_str(" = ")?;
+                self.expr(rhs)?;
+                self.out.write_char(';')?;
+            }
+            StmtBody::Assume { cond } => {
+                self.out.write_str("__CPROVER_assume(")?;
+                self.expr(cond)?;
+                self.out.write_str(");")?;
+            }
+            StmtBody::AtomicBlock(stmts) => {
+                self.out.write_str("ATOMIC ")?;
+                self.block(stmts, loc)?;
+            }
+            StmtBody::Block(stmts) => self.block(stmts, loc)?,
+            StmtBody::Break => self.out.write_str("break;")?,
+            StmtBody::Continue => self.out.write_str("continue;")?,
+            StmtBody::Decl { lhs, value } => {
+                write!(self.out, "{} ", lhs.typ())?;
+                self.expr(lhs)?;
+                if let Some(value) = value {
+                    self.out.write_str(" = ")?;
+                    self.expr(value)?;
+                }
+                self.out.write_char(';')?;
+            }
+            StmtBody::Expression(e) => {
+                self.expr(e)?;
+                self.out.write_char(';')?;
+            }
+            StmtBody::For { init, cond, update, body } => {
+                self.out.write_str("for (")?;
+                self.inline_stmt(init)?;
+                self.out.write_str("; ")?;
+                self.expr(cond)?;
+                self.out.write_str("; ")?;
+                self.inline_stmt(update)?;
+                self.out.write_str(") ")?;
+                self.body(body)?;
+            }
+            StmtBody::FunctionCall { lhs, function, arguments } => {
+                if let Some(lhs) = lhs {
+                    self.expr(lhs)?;
+                    self.out.write_str(" = ")?;
+                }
+                self.postfix_operand(function)?;
+                self.out.write_char('(')?;
+                self.list(arguments)?;
+                self.out.write_str(");")?;
+            }
+            StmtBody::Goto(label) => write!(self.out, "goto {};", label)?,
+            StmtBody::Ifthenelse { i, t, e } => {
+                self.out.write_str("if (")?;
+                self.expr(i)?;
+                self.out.write_str(") ")?;
+                self.body(t)?;
+                if let Some(e) = e {
+                    self.out.write_str(" else ")?;
+                    self.body(e)?;
+                }
+            }
+            StmtBody::Label { .. } => unreachable!(),
+            StmtBody::Return(None) => self.out.write_str("return;")?,
+            StmtBody::Return(Some(e)) => {
+                self.out.write_str("return ")?;
+                self.expr(e)?;
+                self.out.write_char(';')?;
+            }
+            StmtBody::Skip => self.out.write_char(';')?,
+            StmtBody::StartThread(body) => {
+                self.out.write_str("__CPROVER_ASYNC_1: ")?;
+                self.body(body)?;
+            }
+            StmtBody::Switch { control, cases, default } => {
+                self.out.write_str("switch (")?;
+                self.expr(control)?;
+                self.out.write_str(") {")?;
+                self.end_line(loc)?;
+                for case in cases {
+                    self.start_line()?;
+                    self.out.write_str("case ")?;
+                    self.expr(case.case())?;
+                    self.out.write_str(": ")?;
+                    self.body(case.body())?;
+                    self.out.write_char('\n')?;
+                }
+                if let Some(default) = default {
+                    self.start_line()?;
+                    self.out.write_str("default: ")?;
+                    self.body(default)?;
+                    self.out.write_char('\n')?;
+                }
+                self.start_line()?;
+                self.out.write_char('}')?;
+            }
+            StmtBody::While { cond, body } => {
+                self.out.write_str("while (")?;
+                self.expr(cond)?;
+                self.out.write_str(") ")?;
+                self.body(body)?;
+            }
+        }
+        self.end_line(loc)
+    }
+
+    fn parameters(&mut self, parameters: &[Parameter], variadic: bool) -> fmt::Result {
+        self.out.write_char('(')?;
+        for (i, p) in parameters.iter().enumerate() {
+            if i > 0 {
+                self.out.write_str(", ")?;
+            }
+            write!(self.out, "{}", p.typ())?;
+            if let Some(name) = p.identifier().or_else(|| p.base_name()) {
+                write!(self.out, " {}", name)?;
+            }
+        }
+        if variadic {
+            self.out.write_str(if parameters.is_empty() { "..." } else { ", ..." })?;
+        }
+        self.out.write_char(')')
+    }
+
+    fn symbol(&mut self, symbol: &Symbol) -> fmt::Result {
+        if let Some(pretty_name) = symbol.pretty_name {
+            if pretty_name != symbol.name {
+                write!(self.out, "// {}", pretty_name)?;
+                self.end_line(&symbol.location)?;
+            }
+        }
+        match &symbol.typ {
+            Type::Code { parameters, return_type }
+            | Type::VariadicCode { parameters, return_type } => {
+                write!(self.out, "{} {}", return_type, symbol.name)?;
+                self.parameters(parameters, symbol.typ.is_variadic_code())?;
+                match &symbol.value {
+                    SymbolValues::Stmt(body) => {
+                        self.out.write_char(' ')?;
+                        self.body(body)?;
+                        self.out.write_char('\n')
+                    }
+                    _ => {
+                        self.out.write_char(';')?;
+                        self.end_line(&symbol.location)
+                    }
+                }
+            }
+            Type::Struct { tag, components } | Type::Union { tag, components }
+                if symbol.is_type =>
+            {
+                let kind = if symbol.typ.is_struct() { "struct" } else { "union" };
+                write!(self.out, "{} {} {{", kind, tag)?;
+                self.end_line(&symbol.location)?;
+                for component in components.iter() {
+                    match component {
+                        DatatypeComponent::Field { name, typ } => {
+                            writeln!(self.out, "{}{} {};", INDENT, typ, name)?
+                        }
+                        DatatypeComponent::Padding { name, bits } => {
+                            writeln!(self.out, "{}/* padding */ uint{}_t {};", INDENT, bits, name)?
+                        }
+                    }
+                }
+                self.out.write_str("};\n")
+            }
+            typ => {
+                if symbol.is_type {
+                    write!(self.out, "typedef {} {};", typ, symbol.name)?;
+                    return self.end_line(&symbol.location);
+                }
+                if symbol.is_static_lifetime {
+                    self.out.write_str("static ")?;
+                }
+                write!(self.out, "{} {}", typ, symbol.name)?;
+                match &symbol.value {
+                    SymbolValues::Expr(e) => {
+                        self.out.write_str(" = ")?;
+                        self.expr(e)?;
+                    }
+                    SymbolValues::Stmt(s) => {
+                        self.out.write_str(" = ")?;
+                        self.inline_stmt(s)?;
+                    }
+                    SymbolValues::None => {}
+                }
+                self.out.write_char(';')?;
+                self.end_line(&symbol.location)
+            }
+        }
+    }
+}
+
+/// The C operator for `op`, if there is one.
+fn binop_symbol(op: BinaryOperand) -> Option<&'static str> {
+    Some(match op {
+        BinaryOperand::And => "&&",
+        BinaryOperand::Ashr => ">>",
+        BinaryOperand::Bitand => "&",
+        BinaryOperand::Bitor => "|",
+        BinaryOperand::Bitxor => "^",
+        BinaryOperand::Div => "/",
+        BinaryOperand::Equal | BinaryOperand::IeeeFloatEqual => "==",
+        BinaryOperand::Ge => ">=",
+        BinaryOperand::Gt => ">",
+        BinaryOperand::IeeeFloatNotequal | BinaryOperand::Notequal => "!=",
+        BinaryOperand::Implies => "==>",
+        BinaryOperand::Le => "<=",
+        BinaryOperand::Lt => "<",
+        BinaryOperand::Minus => "-",
+        BinaryOperand::Mod => "%",
+        BinaryOperand::Mult => "*",
+        BinaryOperand::Or => "||",
+        BinaryOperand::Plus => "+",
+        BinaryOperand::Shl => "<<",
+        BinaryOperand::Lshr
+        | BinaryOperand::OverflowMinus
+        | BinaryOperand::OverflowMult
+        | BinaryOperand::OverflowPlus
+        | BinaryOperand::Rol
+        | BinaryOperand::Ror
+        | BinaryOperand::Xor => return None,
+    })
+}
+
+/// The name of the operators that `binop_symbol` does not cover, after their irep id.
+fn binop_name(op: BinaryOperand) -> &'static str {
+    match op {
+        BinaryOperand::Lshr => "lshr",
+        BinaryOperand::OverflowMinus => "overflow-minus",
+        BinaryOperand::OverflowMult => "overflow-mult",
+        BinaryOperand::OverflowPlus => "overflow-plus",
+        BinaryOperand::Rol => "rol",
+        BinaryOperand::Ror => "ror",
+        BinaryOperand::Xor => "xor",
+        _ => unreachable!("{:?} is printed as a C operator", op),
+    }
+}
+
+fn unop_name(op: UnaryOperand) -> &'static str {
+    match op {
+        UnaryOperand::Bitnot | UnaryOperand::Not | UnaryOperand::UnaryMinus => {
+            unreachable!("{:?} is printed as a C operator", op)
+        }
+        UnaryOperand::Bswap => "__builtin_bswap",
+        UnaryOperand::CountLeadingZeros { .. } => "__builtin_clz",
+        UnaryOperand::CountTrailingZeros { .. } => "__builtin_ctz",
+        UnaryOperand::IsDynamicObject => "__CPROVER_DYNAMIC_OBJECT",
+        UnaryOperand::ObjectSize => "__CPROVER_OBJECT_SIZE",
+        UnaryOperand::PointerObject => "__CPROVER_POINTER_OBJECT",
+        UnaryOperand::PointerOffset => "__CPROVER_POINTER_OFFSET",
+        UnaryOperand::Popcount => "__builtin_popcount",
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////
+/// Display implementations
+///////////////////////////////////////////////////////////////////////////////////////////////
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Loc { col: Some(col), .. } | Location::Property { col: Some(col), .. } => {
+                write!(f, "{}:{}", self.short_string(), col)
+            }
+            _ => f.write_str(&self.short_string()),
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Array { typ, size } => write!(f, "{}[{}]", typ, size),
+            Type::Bool => f.write_str("__CPROVER_bool"),
+            Type::CBitField { typ, width } => write!(f, "{} : {}", typ, width),
+            Type::CInteger(CIntType::Bool) => f.write_str("bool"),
+            Type::CInteger(CIntType::Char) => f.write_str("char"),
+            Type::CInteger(CIntType::Int) => f.write_str("int"),
+            Type::CInteger(CIntType::SizeT) => f.write_str("size_t"),
+            Type::CInteger(CIntType::SSizeT) => f.write_str("ssize_t"),
+            Type::Code { parameters, return_type }
+            | Type::VariadicCode { parameters, return_type } => {
+                write!(f, "{} (", return_type)?;
+                for (i, p) in parameters.iter().enumerate() {
+                    write!(f, "{}{}", if i > 0 { ", " } else { "" }, p.typ())?;
+                }
+                if self.is_variadic_code() {
+                    f.write_str(if parameters.is_empty() { "..." } else { ", ..." })?;
+                }
+                f.write_char(')')
+            }
+            Type::Constructor => f.write_str("__attribute__((constructor))"),
+            Type::Double => f.write_str("double"),
+            Type::Empty => f.write_str("void"),
+            Type::FlexibleArray { typ } => write!(f, "{}[]", typ),
+            Type::Float => f.write_str("float"),
+            Type::IncompleteStruct { tag } | Type::Struct { tag, .. } => {
+                write!(f, "struct {}", tag)
+            }
+            Type::IncompleteUnion { tag } | Type::Union { tag, .. } => write!(f, "union {}", tag),
+            Type::InfiniteArray { typ } => write!(f, "{}[__CPROVER_infinity()]", typ),
+            Type::Pointer { typ } => write!(f, "{}*", typ),
+            Type::Signedbv { width } => write!(f, "int{}_t", width),
+            Type::StructTag(tag) => {
+                write!(f, "struct {}", tag.to_string().trim_start_matches("tag-"))
+            }
+            Type::UnionTag(tag) => {
+                write!(f, "union {}", tag.to_string().trim_start_matches("tag-"))
+            }
+            Type::Unsignedbv { width } => write!(f, "uint{}_t", width),
+            Type::Vector { typ, size } => write!(f, "{} __attribute__((vector({})))", typ, size),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Printer::new(f).expr(self)
+    }
+}
+
+impl Display for Stmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Printer::new(f).stmt(self)
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Printer::new(f).symbol(self)
+    }
+}
+
+impl Display for SymbolTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, (_, symbol)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_char('\n')?;
+            }
+            Printer::new(f).symbol(symbol)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Expr, Location, Stmt, Symbol, Type};
+    use crate::NO_PRETTY_NAME;
+
+    #[test]
+    fn test_types() {
+        assert_eq!(Type::signed_int(32).to_pointer().to_string(), "int32_t*");
+        assert_eq!(Type::struct_tag("Foo").array_of(3).to_string(), "struct Foo[3]");
+        assert_eq!(
+            Type::code_with_unnamed_parameters(vec![Type::c_int()], Type::empty()).to_string(),
+            "void (int)"
+        );
+    }
+
+    #[test]
+    fn test_exprs() {
+        let x = Expr::symbol_expression("x", Type::signed_int(32));
+        let y = Expr::symbol_expression("y", Type::signed_int(32));
+        assert_eq!(x.clone().plus(y.clone()).mul(x.clone()).to_string(), "(x + y) * x");
+        assert_eq!(x.clone().mul_overflow_p(y.clone()).to_string(), "overflow-mult(x, y)");
+        assert_eq!(x.clone().address_of().dereference().to_string(), "*&x");
+        let (x, y) = (x.cast_to(Type::signed_int(64)), y.cast_to(Type::signed_int(64)));
+        assert_eq!(x.clone().lt(y).not().to_string(), "!((int64_t)x < (int64_t)y)");
+        let f_typ = Type::code_with_unnamed_parameters(vec![Type::signed_int(64)], Type::empty());
+        let f = Expr::symbol_expression("f", f_typ.to_pointer());
+        assert_eq!(f.dereference().call(vec![x]).to_string(), "(*f)((int64_t)x)");
+    }
+
+    #[test]
+    fn test_function() {
+        let x = Expr::symbol_expression("f::x", Type::c_int());
+        let loc = |line| Location::new("main.rs", Some("f"), line, None);
+        let body = Stmt::block(
+            vec![
+                Stmt::decl(x.clone(), Some(Type::c_int().zero()), loc(2)),
+                Stmt::goto("bb1", loc(2)),
+                Stmt::ret(Some(x), loc(3)).with_label("bb1"),
+            ],
+            loc(1),
+        );
+        let f = Symbol::function(
+            "f",
+            Type::code_with_unnamed_parameters(vec![], Type::c_int()),
+            Some(body),
+            NO_PRETTY_NAME,
+            loc(1),
+        );
+        let expected = "\
+int f() {  // main.rs:1
+    int f::x = 0;  // main.rs:2
+    goto bb1;
+bb1:  // main.rs:3
+    return f::x;
+}
+";
+        assert_eq!(f.to_string(), expected);
+    }
+}
@@ -3,6 +3,7 @@
 use self::StmtBody::*;
 use super::{BuiltinFn, Expr, Location};
 use crate::InternedString;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use tracing::debug;
 
@@ -89,6 +90,9 @@ pub enum StmtBody {
     Return(Option<Expr>),
     /// `;`
     Skip,
+    /// `__CPROVER_ASYNC_1: body;` runs `body` in a new thread, which exits at the end of `body`.
+    /// The new thread starts with its own copy of the local variables of the current function.
+    StartThread(Stmt),
     /// `switch (control) { case1.case: cast1.body; case2.case: case2.body; ... }`
     Switch {
         control: Expr,
@@ -293,6 +297,11 @@ pub fn skip(loc: Location) -> Self {
         stmt!(Skip, loc)
     }
 
+    /// `__CPROVER_ASYNC_1: body;`
+    pub fn start_thread(body: Stmt, loc: Location) -> Self {
+        stmt!(StartThread(body), loc)
+    }
+
     /// `switch (control) { case1.case: cast1.body; case2.case: case2.body; ... }`
     pub fn switch(
         control: Expr,
@@ -328,6 +337,64 @@ pub fn is_expression(&self) -> bool {
     }
 }
 
+/// Loops
+impl Stmt {
+    /// The loops that CBMC finds in this function body, in the order of CBMC's loop numbers,
+    /// i.e. `function.n` in `--unwindset` is the `n`th loop. CBMC numbers the backward jumps in
+    /// the order of the instructions that it converts the body to. Each loop is given by the
+    /// label that its backward `goto` jumps to, or `None` for a `while` or `for` loop, whose
+    /// backward jump follows its body, and by the location of its backward jump, i.e. of the
+    /// `goto` or of the `while` or `for` loop.
+    ///
+    /// Statement expressions are not searched, since the codegen does not put loops in them.
+    pub fn loop_labels(&self) -> Vec<(Option<InternedString>, Location)> {
+        fn visit(
+            stmt: &Stmt,
+            seen: &mut HashSet<InternedString>,
+            loops: &mut Vec<(Option<InternedString>, Location)>,
+        ) {
+            match stmt.body() {
+                AtomicBlock(block) | Block(block) => {
+                    block.iter().for_each(|stmt| visit(stmt, seen, loops))
+                }
+                For { init, update, body, .. } => {
+                    visit(init, seen, loops);
+                    visit(body, seen, loops);
+                    visit(update, seen, loops);
+                    loops.push((None, *stmt.location()));
+                }
+                Goto(label) => {
+                    if seen.contains(label) {
+                        loops.push((Some(*label), *stmt.location()));
+                    }
+                }
+                Ifthenelse { t, e, .. } => {
+                    visit(t, seen, loops);
+                    e.iter().for_each(|e| visit(e, seen, loops));
+                }
+                Label { label, body } => {
+                    seen.insert(*label);
+                    visit(body, seen, loops);
+                }
+                StartThread(body) => visit(body, seen, loops),
+                Switch { cases, default, .. } => {
+                    cases.iter().for_each(|case| visit(case.body(), seen, loops));
+                    default.iter().for_each(|default| visit(default, seen, loops));
+                }
+                While { body, .. } => {
+                    visit(body, seen, loops);
+                    loops.push((None, *stmt.location()));
+                }
+                _ => {}
+            }
+        }
+
+        let mut loops = vec![];
+        visit(self, &mut HashSet::new(), &mut loops);
+        loops
+    }
+}
+
 /// Setters
 impl StmtBody {
     #[deprecated(
@@ -360,3 +427,37 @@ pub fn body(&self) -> &Stmt {
         &self.body
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Expr, Location};
+    use super::Stmt;
+
+    #[test]
+    fn test_loop_labels() {
+        let loc = Location::none();
+        let line = |line: u64| Location::new("main.rs", None::<String>, line, None);
+        let inner = Stmt::while_loop(Expr::bool_true(), Stmt::skip(loc), line(3));
+        let body = Stmt::block(
+            vec![
+                Stmt::goto("bb1", loc).with_label("bb0"),
+                Stmt::block(vec![inner, Stmt::goto("bb0", line(4))], loc).with_label("bb1"),
+                Stmt::goto("bb1", line(5)).with_label("bb2"),
+            ],
+            loc,
+        );
+        let loops: Vec<_> = body
+            .loop_labels()
+            .into_iter()
+            .map(|(label, location)| (label.map(|l| l.to_string()), location.line()))
+            .collect();
+        assert_eq!(
+            loops,
+            vec![
+                (None, Some(3)),
+                (Some("bb0".to_string()), Some(4)),
+                (Some("bb1".to_string()), Some(5))
+            ]
+        );
+    }
+}
@@ -258,6 +258,18 @@ pub fn update_fn_declaration_with_definition(&mut self, body: Stmt) {
         self.value = SymbolValues::Stmt(body);
     }
 
+    /// Sets the initial value of a static variable.
+    /// Most static variables are initialized by an initializer function instead, but CBMC uses
+    /// the value of the symbol to initialize the copy of a thread-local variable in a new thread.
+    pub fn update_static_variable_initial_value(&mut self, value: Expr) {
+        assert!(
+            self.is_static_lifetime && self.is_lvalue,
+            "Expected static variable, got {:?}",
+            self
+        );
+        self.value = SymbolValues::Expr(value);
+    }
+
     pub fn with_is_extern(mut self, v: bool) -> Symbol {
         self.is_extern = v;
         self
@@ -1,7 +1,7 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 use super::super::{env, MachineModel};
-use super::{BuiltinFn, DatatypeComponent, Stmt, Symbol, Type};
+use super::{BuiltinFn, DatatypeComponent, Expr, Stmt, Symbol, Type};
 use crate::InternedString;
 use std::collections::BTreeMap;
 /// This is a typesafe implementation of the CBMC symbol table, based on the CBMC code at:
@@ -79,6 +79,15 @@ pub fn update_fn_declaration_with_definition<T: Into<InternedString>>(
         let name = name.into();
         self.symbol_table.get_mut(&name).unwrap().update_fn_declaration_with_definition(body);
     }
+
+    pub fn update_static_variable_initial_value<T: Into<InternedString>>(
+        &mut self,
+        name: T,
+        value: Expr,
+    ) {
+        let name = name.into();
+        self.symbol_table.get_mut(&name).unwrap().update_static_variable_initial_value(value);
+    }
 }
 
 /// Getters
@@ -96,11 +105,11 @@ pub fn lookup<T: Into<InternedString>>(&self, name: T) -> Option<&Symbol> {
         self.symbol_table.get(&name)
     }
 
-    pub fn lookup_components(&self, aggr_name: InternedString) -> Option<&Vec<DatatypeComponent>> {
+    pub fn lookup_components(&self, aggr_name: InternedString) -> Option<&[DatatypeComponent]> {
         self.lookup(aggr_name).and_then(|x| x.typ.components())
     }
 
-    pub fn lookup_components_in_type(&self, base_type: &Type) -> Option<&Vec<DatatypeComponent>> {
+    pub fn lookup_components_in_type(&self, base_type: &Type) -> Option<&[DatatypeComponent]> {
         base_type.type_name().and_then(|aggr_name| self.lookup_components(aggr_name))
     }
 
@@ -127,7 +136,7 @@ pub fn lookup_field_type_in_type<T: Into<InternedString>>(
         base_type.type_name().and_then(|aggr_name| self.lookup_field_type(aggr_name, field_name))
     }
 
-    pub fn lookup_fields_in_type(&self, base_type: &Type) -> Option<&Vec<DatatypeComponent>> {
+    pub fn lookup_fields_in_type(&self, base_type: &Type) -> Option<&[DatatypeComponent]> {
         base_type
             .type_name()
             .and_then(|aggr_name| self.lookup(aggr_name))
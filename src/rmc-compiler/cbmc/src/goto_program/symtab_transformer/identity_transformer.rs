@@ -38,39 +38,13 @@ fn extract_symbol_table(self) -> SymbolTable {
 mod tests {
     use super::{
         super::super::{
-            super::{MachineModel, RoundingMode},
-            DatatypeComponent, Expr, Location, Stmt, SwitchCase, Symbol, SymbolTable, Type,
+            super::MachineModel, DatatypeComponent, Expr, Location, Stmt, SwitchCase, Symbol,
+            SymbolTable, Type,
         },
         IdentityTransformer,
     };
-    fn default_machine_model() -> MachineModel {
-        MachineModel::new(
-            1,
-            "x86_64",
-            8,
-            false,
-            8,
-            64,
-            32,
-            32,
-            false,
-            128,
-            64,
-            64,
-            4,
-            true,
-            64,
-            RoundingMode::ToNearest,
-            16,
-            32,
-            false,
-            32,
-            32,
-        )
-    }
-
     fn empty_symtab() -> SymbolTable {
-        SymbolTable::new(default_machine_model())
+        SymbolTable::new(MachineModel::test_default())
     }
 
     fn assert_transform_eq(original: SymbolTable) {
@@ -414,6 +388,7 @@ fn stmts() {
             ));
             add_sym(Stmt::ret(Some(Expr::bool_true()), Location::none()));
             add_sym(Stmt::skip(Location::none()));
+            add_sym(Stmt::start_thread(Stmt::assert_false("", Location::none()), Location::none()));
             add_sym(Stmt::switch(
                 Expr::int_constant(3, Type::c_int()),
                 vec![
@@ -5,7 +5,10 @@
 mod gen_c_transformer;
 mod identity_transformer;
 mod passes;
+mod reachability;
+mod simplify_transformer;
 mod transformer;
 
-pub use passes::do_passes;
+pub use passes::{read_pipeline_file, Pass, PassContext, PassOptions, PassRegistry, Pipeline};
+pub use reachability::retain_reachable;
 use transformer::Transformer;
@@ -1,28 +1,297 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+//! This module contains the pass manager, which runs pipelines of symbol table passes.
+//!
+//! A pipeline is a list of pass invocations, each written `name` or `name:option=value:...`,
+//! e.g. `reachability:entry=harness`. Pipelines come from the `--symbol-table-passes` flag, or
+//! from a file with one invocation per line, where `#` starts a comment.
+//!
+//! To add a pass, implement it as a function from a symbol table to a symbol table, and
+//! register it in `PassRegistry::default` (or `PassRegistry::register` for passes defined
+//! outside of this crate).
+
 use super::gen_c_transformer::{ExprTransformer, NameTransformer, NondetTransformer};
 use super::identity_transformer::IdentityTransformer;
+use super::reachability::retain_reachable;
+use super::simplify_transformer::SimplifyTransformer;
 use crate::goto_program::SymbolTable;
+use crate::InternedString;
+use std::path::Path;
+use std::time::Instant;
+use tracing::info;
+
+/// The options of a pass invocation, e.g. `entry=harness` in `reachability:entry=harness`.
+#[derive(Debug, Default)]
+pub struct PassOptions {
+    options: Vec<(String, Option<String>)>,
+}
 
-/// Performs each pass provided on the given symbol table.
-pub fn do_passes(mut symtab: SymbolTable, pass_names: &[String]) -> SymbolTable {
-    for pass_name in pass_names {
-        symtab = match &pass_name[..] {
-            "gen-c" => {
+impl PassOptions {
+    /// Whether the option `key` was given, with or without a value.
+    pub fn contains(&self, key: &str) -> bool {
+        self.options.iter().any(|(k, _)| k == key)
+    }
+
+    /// The values of the option `key`, which may be given several times.
+    pub fn values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.options.iter().filter(move |(k, _)| k == key).filter_map(|(_, v)| v.as_deref())
+    }
+}
+
+/// What passes know about the program, besides its symbol table.
+pub struct PassContext<'a> {
+    /// The functions the program may start from, e.g. `main` and the proof harnesses.
+    pub entry_points: &'a [InternedString],
+}
+
+/// A named symbol table transformation.
+pub struct Pass {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The options the pass accepts.
+    pub options: &'static [&'static str],
+    pub run: fn(&SymbolTable, &PassOptions, &PassContext<'_>) -> SymbolTable,
+}
+
+/// The passes that pipelines can use.
+pub struct PassRegistry {
+    passes: Vec<Pass>,
+}
+
+impl Default for PassRegistry {
+    fn default() -> Self {
+        let mut registry = PassRegistry { passes: vec![] };
+        registry.register(Pass {
+            name: "gen-c",
+            description: "Make the symbol table valid C, for --gen-c-runnable",
+            options: &[],
+            run: |symtab, _, _| {
                 // Note: the order of these DOES matter;
                 // ExprTransformer expects the NondetTransformer to happen after, and
                 // NameTransformer should clean up any identifiers introduced by
                 // the other two identifiers
-                let symtab = ExprTransformer::transform(&symtab);
+                let symtab = ExprTransformer::transform(symtab);
                 let symtab = NondetTransformer::transform(&symtab);
-                let symtab = NameTransformer::transform(&symtab);
-                symtab
+                NameTransformer::transform(&symtab)
+            },
+        });
+        registry.register(Pass {
+            name: "identity",
+            description: "Rebuild the symbol table unchanged",
+            options: &[],
+            run: |symtab, _, _| IdentityTransformer::transform(symtab),
+        });
+        registry.register(Pass {
+            name: "reachability",
+            description: "Remove the symbols that are unreachable from the entry points, \
+                and from the functions given with `entry=FUNCTION`",
+            options: &["entry"],
+            run: |symtab, options, context| {
+                let entry_points: Vec<InternedString> = context
+                    .entry_points
+                    .iter()
+                    .copied()
+                    .chain(options.values("entry").map(InternedString::from))
+                    .collect();
+                retain_reachable(symtab, &entry_points)
+            },
+        });
+        registry.register(Pass {
+            name: "simplify",
+            description: "Fold constants, and remove no-op statements and redundant gotos",
+            options: &[],
+            run: |symtab, _, _| SimplifyTransformer::transform(symtab),
+        });
+        registry
+    }
+}
+
+impl PassRegistry {
+    /// Adds a pass to the registry. Panics if a pass with the same name already exists.
+    pub fn register(&mut self, pass: Pass) {
+        assert!(self.lookup(pass.name).is_none(), "Pass {} is already registered", pass.name);
+        self.passes.push(pass);
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<&Pass> {
+        self.passes.iter().find(|pass| pass.name == name)
+    }
+
+    /// A description of the registered passes and their options, one per line.
+    pub fn describe(&self) -> String {
+        self.passes
+            .iter()
+            .map(|pass| {
+                let options: String =
+                    pass.options.iter().map(|option| format!(":{}=...", option)).collect();
+                format!("  {}{}: {}", pass.name, options, pass.description)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Parses the pipeline made of the given pass invocations, which runs them in the order
+    /// given and dumps the symbol table after each run of the passes in `dump_after`. Since
+    /// `gen-c` renames symbols, no pass may run after it.
+    pub fn parse_pipeline(
+        &self,
+        invocations: &[String],
+        dump_after: &[String],
+    ) -> Result<Pipeline<'_>, String> {
+        let invocations = invocations
+            .iter()
+            .map(|invocation| self.parse_invocation(invocation))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(i) = invocations.iter().position(|(pass, _)| pass.name == "gen-c") {
+            if i + 1 < invocations.len() {
+                return Err(format!(
+                    "Invalid symbol table transformation after gen-c: {}\n\
+                    gen-c must run last, since it renames the symbols that other transformations \
+                    refer to",
+                    invocations[i + 1].0.name
+                ));
+            }
+        }
+        for name in dump_after {
+            if !invocations.iter().any(|(pass, _)| pass.name == name) {
+                return Err(format!("Cannot dump after {}, which is not in the pipeline", name));
             }
-            "identity" => IdentityTransformer::transform(&symtab),
-            _ => panic!("Invalid symbol table transformation: {}", pass_name),
         }
+        Ok(Pipeline { invocations, dump_after: dump_after.to_vec() })
     }
 
-    symtab
+    fn parse_invocation(&self, invocation: &str) -> Result<(&Pass, PassOptions), String> {
+        let mut parts = invocation.trim().split(':');
+        let name = parts.next().unwrap();
+        let pass = self.lookup(name).ok_or_else(|| {
+            format!(
+                "Invalid symbol table transformation: {}\nAvailable transformations:\n{}",
+                name,
+                self.describe()
+            )
+        })?;
+        let mut options = PassOptions::default();
+        for option in parts {
+            let (key, value) = match option.split_once('=') {
+                Some((key, value)) => (key, Some(value.to_string())),
+                None => (option, None),
+            };
+            if !pass.options.contains(&key) {
+                return Err(format!("Invalid option {} for transformation {}", key, name));
+            }
+            options.options.push((key.to_string(), value));
+        }
+        Ok((pass, options))
+    }
+}
+
+/// Reads the pass invocations of a pipeline file: one per line, where `#` starts a comment.
+pub fn read_pipeline_file(path: &Path) -> Result<Vec<String>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read symbol table passes from {}: {}", path.display(), e))?;
+    Ok(parse_pipeline_file(&contents))
+}
+
+fn parse_pipeline_file(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// A sequence of pass invocations.
+pub struct Pipeline<'a> {
+    invocations: Vec<(&'a Pass, PassOptions)>,
+    dump_after: Vec<String>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Runs the passes in order, and logs the time each of them takes.
+    /// `dump` is called with the symbol table after each run of the passes to dump, and a name
+    /// for it: the name of the pass, followed by the number of the run if it is not the first
+    /// (e.g. `simplify` and `simplify.2`).
+    pub fn run<F: FnMut(&str, &SymbolTable)>(
+        &self,
+        mut symtab: SymbolTable,
+        context: &PassContext<'_>,
+        mut dump: F,
+    ) -> SymbolTable {
+        for (i, (pass, options)) in self.invocations.iter().enumerate() {
+            let start = Instant::now();
+            symtab = (pass.run)(&symtab, options, context);
+            info!("symbol table pass {}: {:.2?}", pass.name, start.elapsed());
+            if self.dump_after.iter().any(|name| name == pass.name) {
+                let run =
+                    self.invocations[..=i].iter().filter(|(p, _)| p.name == pass.name).count();
+                let dump_name =
+                    if run == 1 { pass.name.to_string() } else { format!("{}.{}", pass.name, run) };
+                dump(&dump_name, &symtab);
+            }
+        }
+        symtab
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{super::MachineModel, Location, Stmt, Symbol, SymbolTable, Type};
+    use super::{parse_pipeline_file, PassContext, PassRegistry};
+    use crate::NO_PRETTY_NAME;
+
+    fn strings(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let registry = PassRegistry::default();
+        assert!(registry.parse_pipeline(&strings(&["identity", "simplify"]), &[]).is_ok());
+        assert!(registry.parse_pipeline(&strings(&["unknown"]), &[]).is_err());
+        assert!(registry.parse_pipeline(&strings(&["identity:entry=f"]), &[]).is_err());
+        assert!(registry.parse_pipeline(&strings(&["identity"]), &strings(&["simplify"])).is_err());
+        assert!(registry.parse_pipeline(&strings(&["reachability", "gen-c"]), &[]).is_ok());
+        assert!(registry.parse_pipeline(&strings(&["gen-c", "reachability"]), &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let contents = "# Comment\nreachability:entry=harness # Keep the harness\n\n  simplify\n";
+        assert_eq!(
+            parse_pipeline_file(contents),
+            strings(&["reachability:entry=harness", "simplify"])
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        let mut symtab = SymbolTable::new(MachineModel::test_default());
+        for name in ["main", "harness", "unused"] {
+            symtab.insert(Symbol::function(
+                name,
+                Type::code(vec![], Type::empty()),
+                Some(Stmt::block(vec![], Location::none())),
+                NO_PRETTY_NAME,
+                Location::none(),
+            ));
+        }
+        let registry = PassRegistry::default();
+        let pipeline = registry
+            .parse_pipeline(
+                &strings(&["identity", "reachability:entry=harness", "identity"]),
+                &strings(&["identity"]),
+            )
+            .unwrap();
+        let mut dumps = vec![];
+        let symtab =
+            pipeline.run(symtab, &PassContext { entry_points: &["main".into()] }, |name, _| {
+                dumps.push(name.to_string())
+            });
+        assert_eq!(dumps, strings(&["identity", "identity.2"]));
+        assert!(symtab.contains("main".into()));
+        assert!(symtab.contains("harness".into()));
+        assert!(!symtab.contains("unused".into()));
+    }
 }
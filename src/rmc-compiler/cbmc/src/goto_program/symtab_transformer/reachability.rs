@@ -0,0 +1,333 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module removes the symbols that are not transitively referenced from a set of entry
+//! functions, e.g. the unused monomorphizations of the standard library.
+//!
+//! A symbol is reachable if it is an entry function, or if it is referenced from the type or the
+//! value of a reachable symbol. References are symbol expressions (which include taking the
+//! address of a function or a static), struct and union tags, and function parameters.
+//!
+//! We do not try to resolve function pointers: a function can only be called through a pointer
+//! if its address was taken, which is a symbol expression in the body of a reachable function
+//! or in the initial value of a reachable static (e.g. a vtable). So following every symbol
+//! expression is conservative.
+
+use crate::goto_program::{
+    DatatypeComponent, Expr, ExprValue, Stmt, StmtBody, Symbol, SymbolTable, SymbolValues, Type,
+};
+use crate::InternedString;
+use std::collections::HashSet;
+
+/// Returns a symbol table with the symbols of `symtab` that are reachable from `entry_points`.
+/// Like the `--function` option of CBMC, an entry point may be either the name or the pretty
+/// name of a function. Entry points that match no symbol are ignored.
+pub fn retain_reachable(symtab: &SymbolTable, entry_points: &[InternedString]) -> SymbolTable {
+    let mut reachability = Reachability { symtab, reached: HashSet::new(), worklist: vec![] };
+    for (name, symbol) in symtab.iter() {
+        let is_entry_point = entry_points.iter().any(|entry_point| {
+            *entry_point == *name
+                || (symbol.is_function() && symbol.pretty_name == Some(*entry_point))
+        });
+        if is_entry_point {
+            reachability.reach(*name);
+        }
+    }
+    while let Some(name) = reachability.worklist.pop() {
+        let symbol = symtab.lookup(name).unwrap();
+        reachability.visit_symbol(symbol);
+    }
+
+    let mut new_symtab = SymbolTable::new(symtab.machine_model().clone());
+    for (name, symbol) in symtab.iter() {
+        if reachability.reached.contains(name) && !new_symtab.contains(*name) {
+            new_symtab.insert(symbol.clone());
+        }
+    }
+    new_symtab
+}
+
+struct Reachability<'a> {
+    symtab: &'a SymbolTable,
+    reached: HashSet<InternedString>,
+    /// Reached symbols whose type and value have not been visited yet.
+    worklist: Vec<InternedString>,
+}
+
+impl<'a> Reachability<'a> {
+    fn reach(&mut self, name: InternedString) {
+        if self.symtab.contains(name) && self.reached.insert(name) {
+            self.worklist.push(name);
+        }
+    }
+
+    fn visit_symbol(&mut self, symbol: &Symbol) {
+        self.visit_type(&symbol.typ);
+        match &symbol.value {
+            SymbolValues::Expr(e) => self.visit_expr(e),
+            SymbolValues::Stmt(s) => self.visit_stmt(s),
+            SymbolValues::None => {}
+        }
+    }
+
+    fn visit_type(&mut self, typ: &Type) {
+        if let Some(name) = typ.type_name() {
+            self.reach(name);
+        }
+        match typ {
+            Type::Array { typ, .. }
+            | Type::CBitField { typ, .. }
+            | Type::FlexibleArray { typ }
+            | Type::InfiniteArray { typ }
+            | Type::Pointer { typ }
+            | Type::Vector { typ, .. } => self.visit_type(typ),
+            Type::Code { parameters, return_type }
+            | Type::VariadicCode { parameters, return_type } => {
+                for parameter in parameters.iter() {
+                    if let Some(identifier) = parameter.identifier() {
+                        self.reach(identifier);
+                    }
+                    self.visit_type(parameter.typ());
+                }
+                self.visit_type(return_type);
+            }
+            Type::Struct { components, .. } | Type::Union { components, .. } => {
+                for component in components.iter() {
+                    if let DatatypeComponent::Field { typ, .. } = component {
+                        self.visit_type(typ);
+                    }
+                }
+            }
+            Type::Bool
+            | Type::CInteger(_)
+            | Type::Constructor
+            | Type::Double
+            | Type::Empty
+            | Type::Float
+            | Type::IncompleteStruct { .. }
+            | Type::IncompleteUnion { .. }
+            | Type::Signedbv { .. }
+            | Type::StructTag(_)
+            | Type::UnionTag(_)
+            | Type::Unsignedbv { .. } => {}
+        }
+    }
+
+    fn visit_expr(&mut self, e: &Expr) {
+        self.visit_type(e.typ());
+        match e.value() {
+            ExprValue::Symbol { identifier } => self.reach(*identifier),
+            ExprValue::AddressOf(e)
+            | ExprValue::ArrayOf { elem: e }
+            | ExprValue::ByteExtract { e, .. }
+            | ExprValue::Dereference(e)
+            | ExprValue::Member { lhs: e, .. }
+            | ExprValue::SelfOp { e, .. }
+            | ExprValue::Typecast(e)
+            | ExprValue::Union { value: e, .. }
+            | ExprValue::UnOp { e, .. } => self.visit_expr(e),
+            ExprValue::Assign { left, right } => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            }
+            ExprValue::BinOp { lhs, rhs, .. } => {
+                self.visit_expr(lhs);
+                self.visit_expr(rhs);
+            }
+            ExprValue::Index { array, index } => {
+                self.visit_expr(array);
+                self.visit_expr(index);
+            }
+            ExprValue::If { c, t, e } => {
+                self.visit_expr(c);
+                self.visit_expr(t);
+                self.visit_expr(e);
+            }
+            ExprValue::FunctionCall { function, arguments } => {
+                self.visit_expr(function);
+                arguments.iter().for_each(|argument| self.visit_expr(argument));
+            }
+            ExprValue::Array { elems } | ExprValue::Vector { elems } => {
+                elems.iter().for_each(|elem| self.visit_expr(elem));
+            }
+            ExprValue::Struct { values } => values.iter().for_each(|value| self.visit_expr(value)),
+            ExprValue::StatementExpression { statements } => {
+                statements.iter().for_each(|statement| self.visit_stmt(statement));
+            }
+            ExprValue::BoolConstant(_)
+            | ExprValue::CBoolConstant(_)
+            | ExprValue::DoubleConstant(_)
+            | ExprValue::FloatConstant(_)
+            | ExprValue::IntConstant(_)
+            | ExprValue::Nondet
+            | ExprValue::PointerConstant(_)
+            | ExprValue::StringConstant { .. } => {}
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match stmt.body() {
+            StmtBody::Assign { lhs, rhs } => {
+                self.visit_expr(lhs);
+                self.visit_expr(rhs);
+            }
+            StmtBody::Assume { cond } => self.visit_expr(cond),
+            StmtBody::AtomicBlock(block) | StmtBody::Block(block) => {
+                block.iter().for_each(|stmt| self.visit_stmt(stmt));
+            }
+            StmtBody::Decl { lhs, value } => {
+                self.visit_expr(lhs);
+                value.iter().for_each(|value| self.visit_expr(value));
+            }
+            StmtBody::Expression(e) => self.visit_expr(e),
+            StmtBody::For { init, cond, update, body } => {
+                self.visit_stmt(init);
+                self.visit_expr(cond);
+                self.visit_stmt(update);
+                self.visit_stmt(body);
+            }
+            StmtBody::FunctionCall { lhs, function, arguments } => {
+                lhs.iter().for_each(|lhs| self.visit_expr(lhs));
+                self.visit_expr(function);
+                arguments.iter().for_each(|argument| self.visit_expr(argument));
+            }
+            StmtBody::Ifthenelse { i, t, e } => {
+                self.visit_expr(i);
+                self.visit_stmt(t);
+                e.iter().for_each(|e| self.visit_stmt(e));
+            }
+            StmtBody::Label { body, .. } | StmtBody::StartThread(body) => self.visit_stmt(body),
+            StmtBody::Return(value) => value.iter().for_each(|value| self.visit_expr(value)),
+            StmtBody::Switch { control, cases, default } => {
+                self.visit_expr(control);
+                for case in cases {
+                    self.visit_expr(case.case());
+                    self.visit_stmt(case.body());
+                }
+                default.iter().for_each(|default| self.visit_stmt(default));
+            }
+            StmtBody::While { cond, body } => {
+                self.visit_expr(cond);
+                self.visit_stmt(body);
+            }
+            StmtBody::Break | StmtBody::Continue | StmtBody::Goto(_) | StmtBody::Skip => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{
+        super::MachineModel, DatatypeComponent, Expr, Location, Stmt, Symbol, SymbolTable, Type,
+    };
+    use super::retain_reachable;
+    use crate::NO_PRETTY_NAME;
+
+    fn function(name: &str, typ: Type, body: Vec<Stmt>) -> Symbol {
+        Symbol::function(
+            name,
+            typ,
+            Some(Stmt::block(body, Location::none())),
+            NO_PRETTY_NAME,
+            Location::none(),
+        )
+    }
+
+    fn call(name: &str) -> Stmt {
+        let typ = Type::code(vec![], Type::empty());
+        Expr::symbol_expression(name, typ).call(vec![]).as_stmt(Location::none())
+    }
+
+    fn names(symtab: &SymbolTable) -> Vec<String> {
+        let default = SymbolTable::new(symtab.machine_model().clone());
+        let mut names: Vec<_> = symtab
+            .iter()
+            .filter(|(name, _)| !default.contains(**name))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_calls() {
+        let mut symtab = SymbolTable::new(MachineModel::test_default());
+        let unit = Type::code(vec![], Type::empty());
+        symtab.insert(function("main", unit.clone(), vec![call("foo")]));
+        symtab.insert(function("foo", unit.clone(), vec![call("bar")]));
+        symtab.insert(function("bar", unit.clone(), vec![call("foo")]));
+        symtab.insert(function("unused", unit.clone(), vec![call("bar")]));
+        symtab.insert(Symbol::function("extern_fn", unit, None, NO_PRETTY_NAME, Location::none()));
+
+        let reduced = retain_reachable(&symtab, &["main".into()]);
+        assert_eq!(names(&reduced), vec!["bar", "foo", "main"]);
+    }
+
+    #[test]
+    fn test_statics_and_types() {
+        let mut symtab = SymbolTable::new(MachineModel::test_default());
+        let unit = Type::code(vec![], Type::empty());
+        let fn_ptr = unit.clone().to_pointer();
+        symtab.insert(Symbol::struct_type(
+            "vtable",
+            NO_PRETTY_NAME,
+            vec![DatatypeComponent::field("method", fn_ptr.clone())],
+        ));
+        symtab.insert(Symbol::struct_type(
+            "unused_struct",
+            NO_PRETTY_NAME,
+            vec![DatatypeComponent::field("x", Type::c_int())],
+        ));
+        symtab.insert(function("method", unit.clone(), vec![]));
+        symtab.insert(function("unused", unit.clone(), vec![]));
+        let vtable = Symbol::static_variable(
+            "vtable_instance",
+            "vtable_instance",
+            Type::struct_tag("vtable"),
+            Location::none(),
+        );
+        symtab.insert(vtable.clone());
+        let method = Expr::symbol_expression("method", unit.clone()).address_of();
+        symtab.update_static_variable_initial_value(
+            "vtable_instance",
+            Expr::struct_expr_from_values(Type::struct_tag("vtable"), vec![method], &symtab),
+        );
+        // Calls `method` through the function pointer in the vtable.
+        let call = vtable.to_expr().member("method", &symtab).dereference().call(vec![]);
+        symtab.insert(function("main", unit, vec![call.as_stmt(Location::none())]));
+
+        let reduced = retain_reachable(&symtab, &["main".into()]);
+        assert_eq!(names(&reduced), vec!["main", "method", "tag-vtable", "vtable_instance"]);
+    }
+
+    #[test]
+    fn test_pretty_name_entry_point() {
+        let mut symtab = SymbolTable::new(MachineModel::test_default());
+        let unit = Type::code(vec![], Type::empty());
+        symtab.insert(function("main", unit.clone(), vec![]));
+        symtab.insert(Symbol::function(
+            "_RNvCs1234_4test5check",
+            unit,
+            Some(Stmt::block(vec![], Location::none())),
+            Some("check"),
+            Location::none(),
+        ));
+
+        let reduced = retain_reachable(&symtab, &["check".into()]);
+        assert_eq!(names(&reduced), vec!["_RNvCs1234_4test5check"]);
+    }
+
+    #[test]
+    fn test_parameters_and_missing_entry_points() {
+        let mut symtab = SymbolTable::new(MachineModel::test_default());
+        let param = Symbol::variable("foo::x", "x", Type::c_int(), Location::none());
+        let typ = Type::code(vec![param.to_function_parameter()], Type::empty());
+        symtab.insert(param);
+        symtab.insert(function("foo", typ, vec![]));
+
+        let reduced = retain_reachable(&symtab, &["foo".into(), "missing".into()]);
+        assert_eq!(names(&reduced), vec!["foo", "foo::x"]);
+        let reduced = retain_reachable(&symtab, &["missing".into()]);
+        assert!(names(&reduced).is_empty());
+    }
+}
@@ -0,0 +1,710 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module simplifies the redundant constructs that the codegen produces:
+//!  - constant expressions are folded, e.g. `1 + 2`, `true && e`, `!false`, casts of constants
+//!    and overflow checks of constants.
+//!  - no-op statements are removed: `skip`s (e.g. from `StorageLive`), `assume(true)`,
+//!    branches of an `if` on a constant, and nondeterministic assignments to zero-sized
+//!    structs (e.g. the unit returned by `rmc::any`).
+//!  - nested blocks without declarations are flattened, including the labeled blocks of basic
+//!    blocks, which does not change the scope of any variable.
+//!  - gotos to a label whose statement is just another goto are redirected to the final label,
+//!    gotos to the immediately following statement are removed, and so are labels that are no
+//!    longer the target of any goto and the unreachable code after a goto or a return.
+//!
+//! Removing a goto or a label can expose further simplifications, so we simplify each function
+//! until its number of instructions stops decreasing.
+
+use super::Transformer;
+use crate::goto_program::{
+    BinaryOperand, Expr, ExprValue, Location, Stmt, StmtBody, SymbolTable, SymbolValues, Type,
+    UnaryOperand,
+};
+use crate::InternedString;
+use num::bigint::BigInt;
+use num::Zero;
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+
+/// Struct for simplifying the statements and expressions of a symbol table.
+pub struct SimplifyTransformer {
+    new_symbol_table: SymbolTable,
+    /// Labels whose statement is just a goto, mapped to the final label of the goto chain.
+    forwarded_labels: HashMap<InternedString, InternedString>,
+    /// The labels that are the target of some goto in the current function, once known.
+    used_labels: Option<HashSet<InternedString>>,
+    /// How many statement expressions we are in. Gotos cannot jump into or out of a statement
+    /// expression, so we do not track the labels inside of them and leave them alone.
+    statement_expression_depth: usize,
+}
+
+impl SimplifyTransformer {
+    /// Simplify the given symbol table, and log the number of instructions it saved.
+    pub fn transform(original_symbol_table: &SymbolTable) -> SymbolTable {
+        let new_symbol_table = SymbolTable::new(original_symbol_table.machine_model().clone());
+        let symtab = SimplifyTransformer {
+            new_symbol_table,
+            forwarded_labels: HashMap::default(),
+            used_labels: None,
+            statement_expression_depth: 0,
+        }
+        .transform_symbol_table(original_symbol_table);
+        info!(
+            "simplify: {} instructions before, {} instructions after",
+            count_instructions(original_symbol_table),
+            count_instructions(&symtab)
+        );
+        symtab
+    }
+
+    /// Simplifies the body of a function until its number of instructions stops decreasing.
+    fn simplify_function_body(&mut self, body: &Stmt) -> Stmt {
+        self.forwarded_labels.clear();
+        self.used_labels = None;
+        let mut body = self.transform_stmt(body);
+        let mut instructions = stmt_instructions(&body);
+        loop {
+            self.forwarded_labels = forwarded_labels(&body);
+            self.used_labels = Some(goto_targets(&body, &self.forwarded_labels));
+            body = self.transform_stmt(&body);
+            let new_instructions = stmt_instructions(&body);
+            if new_instructions >= instructions {
+                break;
+            }
+            instructions = new_instructions;
+        }
+        self.forwarded_labels.clear();
+        self.used_labels = None;
+        body
+    }
+
+    /// The smallest and largest values of an integer type, if it has a known width.
+    fn int_range(&self, typ: &Type) -> Option<(BigInt, BigInt)> {
+        if !typ.is_integer() || typ.is_c_bool() {
+            return None;
+        }
+        let mm = self.symbol_table().machine_model();
+        let width = typ.native_width(mm)?;
+        let signed = typ.is_signed(mm);
+        Some((crate::utils::min_int(width, signed), crate::utils::max_int(width, signed)))
+    }
+
+    /// Folds `lhs op rhs` if its value is known.
+    fn fold_bin_op(&self, op: BinaryOperand, lhs: &Expr, rhs: &Expr) -> Option<Expr> {
+        use BinaryOperand::*;
+        match (lhs.value(), rhs.value()) {
+            (ExprValue::BoolConstant(l), ExprValue::BoolConstant(r)) => {
+                let value = match op {
+                    And => *l && *r,
+                    Equal => l == r,
+                    Implies => !*l || *r,
+                    Notequal | Xor => l != r,
+                    Or => *l || *r,
+                    _ => return None,
+                };
+                Some(Expr::bool_constant(value))
+            }
+            // `&&`, `||` and `==>` do not evaluate their right operand if the left one
+            // determines the result.
+            (ExprValue::BoolConstant(l), _) if rhs.typ().is_bool() => match (op, *l) {
+                (And, true) | (Implies, true) | (Or, false) => Some(rhs.clone()),
+                (And, false) => Some(Expr::bool_false()),
+                (Implies, false) | (Or, true) => Some(Expr::bool_true()),
+                _ => None,
+            },
+            (ExprValue::IntConstant(l), ExprValue::IntConstant(r)) if lhs.typ() == rhs.typ() => {
+                self.fold_int_bin_op(op, lhs.typ(), l, r)
+            }
+            _ => None,
+        }
+    }
+
+    /// Folds `l op r`, where `l` and `r` are constants of type `typ`, if the result is defined.
+    fn fold_int_bin_op(
+        &self,
+        op: BinaryOperand,
+        typ: &Type,
+        l: &BigInt,
+        r: &BigInt,
+    ) -> Option<Expr> {
+        use BinaryOperand::*;
+        let (min, max) = self.int_range(typ)?;
+        let in_range = |value: &BigInt| min <= *value && *value <= max;
+        let value = match op {
+            Bitand => l & r,
+            Bitor => l | r,
+            Bitxor => l ^ r,
+            Div if !r.is_zero() => l / r,
+            Minus => l - r,
+            Mod if !r.is_zero() => l % r,
+            Mult => l * r,
+            Plus => l + r,
+            Equal => return Some(Expr::bool_constant(l == r)),
+            Ge => return Some(Expr::bool_constant(l >= r)),
+            Gt => return Some(Expr::bool_constant(l > r)),
+            Le => return Some(Expr::bool_constant(l <= r)),
+            Lt => return Some(Expr::bool_constant(l < r)),
+            Notequal => return Some(Expr::bool_constant(l != r)),
+            OverflowMinus => return Some(Expr::bool_constant(!in_range(&(l - r)))),
+            OverflowMult => return Some(Expr::bool_constant(!in_range(&(l * r)))),
+            OverflowPlus => return Some(Expr::bool_constant(!in_range(&(l + r)))),
+            _ => return None,
+        };
+        if in_range(&value) { Some(Expr::int_constant(value, typ.clone())) } else { None }
+    }
+
+    /// Whether `typ` is a struct without any field or padding, e.g. `()`.
+    fn is_zero_sized_struct(&self, typ: &Type) -> bool {
+        typ.is_struct_tag()
+            && self
+                .symbol_table()
+                .lookup_components_in_type(typ)
+                .map_or(false, |components| components.is_empty())
+    }
+}
+
+impl Transformer for SimplifyTransformer {
+    /// Get reference to symbol table.
+    fn symbol_table(&self) -> &SymbolTable {
+        &self.new_symbol_table
+    }
+
+    /// Get mutable reference to symbol table.
+    fn mut_symbol_table(&mut self) -> &mut SymbolTable {
+        &mut self.new_symbol_table
+    }
+
+    /// Get owned symbol table.
+    fn extract_symbol_table(self) -> SymbolTable {
+        self.new_symbol_table
+    }
+
+    /// Folds binary operations on constants.
+    fn transform_expr_bin_op(
+        &mut self,
+        _typ: &Type,
+        op: &BinaryOperand,
+        lhs: &Expr,
+        rhs: &Expr,
+    ) -> Expr {
+        let lhs = self.transform_expr(lhs);
+        let rhs = self.transform_expr(rhs);
+        self.fold_bin_op(*op, &lhs, &rhs).unwrap_or_else(|| lhs.binop(*op, rhs))
+    }
+
+    /// Replaces `c ? t : e` by `t` or `e` if `c` is a constant.
+    fn transform_expr_if(&mut self, _typ: &Type, c: &Expr, t: &Expr, e: &Expr) -> Expr {
+        let c = self.transform_expr(c);
+        match c.value() {
+            ExprValue::BoolConstant(true) => self.transform_expr(t),
+            ExprValue::BoolConstant(false) => self.transform_expr(e),
+            _ => {
+                let t = self.transform_expr(t);
+                let e = self.transform_expr(e);
+                c.ternary(t, e)
+            }
+        }
+    }
+
+    /// Folds casts of integer constants to integer types that can represent them.
+    fn transform_expr_typecast(&mut self, typ: &Type, child: &Expr) -> Expr {
+        let typ = self.transform_type(typ);
+        let child = self.transform_expr(child);
+        match (child.int_constant_value(), self.int_range(&typ)) {
+            (Some(value), Some((min, max))) if min <= value && value <= max => {
+                Expr::int_constant(value, typ)
+            }
+            _ => child.cast_to(typ),
+        }
+    }
+
+    /// Folds the negation of boolean constants.
+    fn transform_expr_un_op(&mut self, _typ: &Type, op: &UnaryOperand, e: &Expr) -> Expr {
+        let e = self.transform_expr(e);
+        match op {
+            UnaryOperand::Bitnot => e.bitnot(),
+            UnaryOperand::Bswap => e.bswap(),
+            UnaryOperand::IsDynamicObject => e.dynamic_object(),
+            UnaryOperand::Not => match e.value() {
+                ExprValue::BoolConstant(value) => Expr::bool_constant(!*value),
+                _ => e.not(),
+            },
+            UnaryOperand::ObjectSize => e.object_size(),
+            UnaryOperand::PointerObject => e.pointer_object(),
+            UnaryOperand::PointerOffset => e.pointer_offset(),
+            UnaryOperand::Popcount => e.popcount(),
+            UnaryOperand::CountTrailingZeros { allow_zero } => e.cttz(*allow_zero),
+            UnaryOperand::CountLeadingZeros { allow_zero } => e.ctlz(*allow_zero),
+            UnaryOperand::UnaryMinus => e.neg(),
+        }
+    }
+
+    /// Leaves the labels of statement expressions alone.
+    fn transform_expr_statement_expression(&mut self, typ: &Type, statements: &[Stmt]) -> Expr {
+        let typ = self.transform_type(typ);
+        self.statement_expression_depth += 1;
+        let statements = statements.iter().map(|stmt| self.transform_stmt(stmt)).collect();
+        self.statement_expression_depth -= 1;
+        Expr::statement_expression(statements, typ)
+    }
+
+    /// Removes nondeterministic assignments to zero-sized structs.
+    fn transform_stmt_assign(&mut self, lhs: &Expr, rhs: &Expr) -> Stmt {
+        let lhs = self.transform_expr(lhs);
+        let rhs = self.transform_expr(rhs);
+        if matches!(rhs.value(), ExprValue::Nondet) && self.is_zero_sized_struct(lhs.typ()) {
+            Stmt::skip(Location::none())
+        } else {
+            lhs.assign(rhs, Location::none())
+        }
+    }
+
+    /// Removes `assume(true)`.
+    fn transform_stmt_assume(&mut self, cond: &Expr) -> Stmt {
+        let cond = self.transform_expr(cond);
+        match cond.value() {
+            ExprValue::BoolConstant(true) => Stmt::skip(Location::none()),
+            _ => Stmt::assume(cond, Location::none()),
+        }
+    }
+
+    /// Flattens nested blocks, and removes `skip`s, unreachable statements and gotos to the
+    /// following statement.
+    fn transform_stmt_block(&mut self, block: &[Stmt]) -> Stmt {
+        let mut stmts = vec![];
+        for stmt in block {
+            let stmt = self.transform_stmt(stmt);
+            match stmt.body() {
+                StmtBody::Skip => {}
+                StmtBody::Block(inner) if !inner.iter().any(is_decl) => {
+                    stmts.extend(inner.iter().cloned())
+                }
+                // `label: { s1; s2; }` becomes `label: s1; s2;`
+                StmtBody::Label { label, body } => match body.body() {
+                    StmtBody::Block(inner) if !inner.is_empty() && !inner.iter().any(is_decl) => {
+                        stmts.push(inner[0].clone().with_label(*label));
+                        stmts.extend(inner[1..].iter().cloned());
+                    }
+                    _ => stmts.push(stmt),
+                },
+                _ => stmts.push(stmt),
+            }
+        }
+        Stmt::block(simplify_jumps(stmts), Location::none())
+    }
+
+    /// Redirects gotos to the end of goto chains.
+    fn transform_stmt_goto(&mut self, label: InternedString) -> Stmt {
+        let label = self.forwarded_labels.get(&label).copied().unwrap_or(label);
+        Stmt::goto(label, Location::none())
+    }
+
+    /// Replaces an `if` on a constant by the branch it takes, unless the other branch contains
+    /// a label, which a goto may jump to. The branch keeps its own location, e.g. the property
+    /// of an assertion.
+    fn transform_stmt_ifthenelse(&mut self, i: &Expr, t: &Stmt, e: &Option<Stmt>) -> Stmt {
+        let i = self.transform_expr(i);
+        match i.value() {
+            ExprValue::BoolConstant(true) if !e.as_ref().map_or(false, contains_label) => {
+                self.transform_stmt(t)
+            }
+            ExprValue::BoolConstant(false) if !contains_label(t) => match e {
+                Some(e) => self.transform_stmt(e),
+                None => Stmt::skip(Location::none()),
+            },
+            _ => {
+                let t = self.transform_stmt(t);
+                let e = e.as_ref().map(|e| self.transform_stmt(e));
+                Stmt::if_then_else(i, t, e, Location::none())
+            }
+        }
+    }
+
+    /// Removes the labels that are not the target of any goto.
+    fn transform_stmt_label(&mut self, label: InternedString, body: &Stmt) -> Stmt {
+        let body = self.transform_stmt(body);
+        let is_unused = self.statement_expression_depth == 0
+            && self.used_labels.as_ref().map_or(false, |used| !used.contains(&label));
+        if is_unused { body } else { body.with_label(label) }
+    }
+
+    /// Simplifies function bodies as a whole, since gotos may jump anywhere in them.
+    fn transform_value(&mut self, value: &SymbolValues) -> SymbolValues {
+        match value {
+            SymbolValues::None => SymbolValues::None,
+            SymbolValues::Expr(expr) => SymbolValues::Expr(self.transform_expr(expr)),
+            SymbolValues::Stmt(stmt) => SymbolValues::Stmt(self.simplify_function_body(stmt)),
+        }
+    }
+}
+
+/// The number of instructions in the function bodies of `symtab`, where blocks and labels are
+/// not instructions.
+pub fn count_instructions(symtab: &SymbolTable) -> usize {
+    symtab
+        .iter()
+        .map(|(_, symbol)| match &symbol.value {
+            SymbolValues::Stmt(stmt) => stmt_instructions(stmt),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn stmt_instructions(stmt: &Stmt) -> usize {
+    match stmt.body() {
+        StmtBody::AtomicBlock(block) | StmtBody::Block(block) => {
+            block.iter().map(stmt_instructions).sum()
+        }
+        StmtBody::Label { body, .. } => stmt_instructions(body),
+        StmtBody::For { init, update, body, .. } => {
+            1 + stmt_instructions(init) + stmt_instructions(update) + stmt_instructions(body)
+        }
+        StmtBody::Ifthenelse { t, e, .. } => {
+            1 + stmt_instructions(t) + e.as_ref().map_or(0, stmt_instructions)
+        }
+        StmtBody::StartThread(body) | StmtBody::While { body, .. } => 1 + stmt_instructions(body),
+        StmtBody::Switch { cases, default, .. } => {
+            1 + cases.iter().map(|case| stmt_instructions(case.body())).sum::<usize>()
+                + default.as_ref().map_or(0, stmt_instructions)
+        }
+        _ => 1,
+    }
+}
+
+/// Calls `f` on `stmt` and all of its sub-statements, except those in statement expressions.
+fn for_each_stmt<F: FnMut(&Stmt)>(stmt: &Stmt, f: &mut F) {
+    f(stmt);
+    match stmt.body() {
+        StmtBody::AtomicBlock(block) | StmtBody::Block(block) => {
+            block.iter().for_each(|stmt| for_each_stmt(stmt, f))
+        }
+        StmtBody::For { init, update, body, .. } => {
+            for_each_stmt(init, f);
+            for_each_stmt(update, f);
+            for_each_stmt(body, f);
+        }
+        StmtBody::Ifthenelse { t, e, .. } => {
+            for_each_stmt(t, f);
+            e.iter().for_each(|e| for_each_stmt(e, f));
+        }
+        StmtBody::Label { body, .. }
+        | StmtBody::StartThread(body)
+        | StmtBody::While { body, .. } => for_each_stmt(body, f),
+        StmtBody::Switch { cases, default, .. } => {
+            cases.iter().for_each(|case| for_each_stmt(case.body(), f));
+            default.iter().for_each(|default| for_each_stmt(default, f));
+        }
+        _ => {}
+    }
+}
+
+fn is_decl(stmt: &Stmt) -> bool {
+    matches!(stmt.body(), StmtBody::Decl { .. })
+}
+
+fn contains_label(stmt: &Stmt) -> bool {
+    let mut found = false;
+    for_each_stmt(stmt, &mut |stmt| found |= matches!(stmt.body(), StmtBody::Label { .. }));
+    found
+}
+
+/// If `stmt` is just `goto label`, possibly labeled or in a block, returns `label`.
+fn jump_target(stmt: &Stmt) -> Option<InternedString> {
+    match stmt.body() {
+        StmtBody::Goto(label) => Some(*label),
+        StmtBody::Label { body, .. } => jump_target(body),
+        StmtBody::Block(block) if block.len() == 1 => jump_target(&block[0]),
+        _ => None,
+    }
+}
+
+/// Maps the labels whose statement is just a goto to the end of their goto chain.
+/// Labels in a cycle of gotos are not forwarded.
+fn forwarded_labels(body: &Stmt) -> HashMap<InternedString, InternedString> {
+    let mut jumps = HashMap::new();
+    for_each_stmt(body, &mut |stmt| {
+        if let (StmtBody::Label { label, body }, Some(target)) = (stmt.body(), jump_target(stmt)) {
+            if jump_target(body) == Some(target) {
+                jumps.insert(*label, target);
+            }
+        }
+    });
+    jumps
+        .keys()
+        .filter_map(|label| {
+            let mut visited = HashSet::new();
+            let mut target = *label;
+            while let Some(next) = jumps.get(&target) {
+                if !visited.insert(target) {
+                    return None;
+                }
+                target = *next;
+            }
+            Some((*label, target))
+        })
+        .collect()
+}
+
+/// The labels that gotos in `body` jump to, once they are forwarded.
+fn goto_targets(
+    body: &Stmt,
+    forwarded_labels: &HashMap<InternedString, InternedString>,
+) -> HashSet<InternedString> {
+    let mut targets = HashSet::new();
+    for_each_stmt(body, &mut |stmt| {
+        if let StmtBody::Goto(label) = stmt.body() {
+            targets.insert(forwarded_labels.get(label).copied().unwrap_or(*label));
+        }
+    });
+    targets
+}
+
+/// The label of the first statement that `stmt` executes, if any.
+fn first_label(stmt: &Stmt) -> Option<InternedString> {
+    match stmt.body() {
+        StmtBody::Label { label, .. } => Some(*label),
+        StmtBody::Block(block) => block.first().and_then(first_label),
+        _ => None,
+    }
+}
+
+/// Whether the last statement that `stmt` executes is a goto or a return.
+fn ends_with_jump(stmt: &Stmt) -> bool {
+    match stmt.body() {
+        StmtBody::Goto(_) | StmtBody::Return(_) => true,
+        StmtBody::Block(block) => block.last().map_or(false, ends_with_jump),
+        StmtBody::Label { body, .. } => ends_with_jump(body),
+        _ => false,
+    }
+}
+
+/// If the last statement that `stmt` executes is `goto label`, returns `stmt` without it.
+fn strip_trailing_goto(stmt: &Stmt, label: InternedString) -> Option<Stmt> {
+    let loc = stmt.location().clone();
+    match stmt.body() {
+        StmtBody::Goto(target) if *target == label => Some(Stmt::skip(loc)),
+        StmtBody::Block(block) => {
+            let (last, init) = block.split_last()?;
+            let last = strip_trailing_goto(last, label)?;
+            let mut block = init.to_vec();
+            block.push(last);
+            Some(Stmt::block(block, loc))
+        }
+        StmtBody::Label { label: stmt_label, body } => {
+            Some(strip_trailing_goto(body, label)?.with_label(*stmt_label))
+        }
+        _ => None,
+    }
+}
+
+/// Removes the statements of a block that follow a goto or a return and cannot be jumped to,
+/// and the gotos to the following statement of the block.
+fn simplify_jumps(stmts: Vec<Stmt>) -> Vec<Stmt> {
+    let mut reachable = vec![];
+    let mut is_unreachable = false;
+    for stmt in stmts {
+        if is_unreachable && !contains_label(&stmt) {
+            continue;
+        }
+        is_unreachable = ends_with_jump(&stmt);
+        reachable.push(stmt);
+    }
+
+    let mut simplified = vec![];
+    for (i, stmt) in reachable.iter().enumerate() {
+        let next_label = reachable.get(i + 1).and_then(first_label);
+        match next_label.and_then(|label| strip_trailing_goto(stmt, label)) {
+            Some(stripped) if matches!(stripped.body(), StmtBody::Skip) => {}
+            Some(stripped) => simplified.push(stripped),
+            None => simplified.push(stmt.clone()),
+        }
+    }
+    simplified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::{
+        super::MachineModel, Expr, Location, Stmt, StmtBody, Symbol, SymbolTable, SymbolValues,
+        Type,
+    };
+    use super::{count_instructions, SimplifyTransformer};
+    use crate::NO_PRETTY_NAME;
+
+    /// Simplifies a symbol table with a function `f` with the given body, and returns the
+    /// simplified body.
+    fn simplify(body: Vec<Stmt>) -> Stmt {
+        let mut symtab = SymbolTable::new(MachineModel::test_default());
+        symtab.insert(Symbol::variable("x", "x", Type::signed_int(32), Location::none()));
+        symtab.insert(Symbol::struct_type("Unit", NO_PRETTY_NAME, vec![]));
+        symtab.insert(Symbol::variable("u", "u", Type::struct_tag("Unit"), Location::none()));
+        symtab.insert(Symbol::function(
+            "f",
+            Type::code(vec![], Type::empty()),
+            Some(Stmt::block(body, Location::none())),
+            NO_PRETTY_NAME,
+            Location::none(),
+        ));
+        let simplified = SimplifyTransformer::transform(&symtab);
+        assert!(count_instructions(&simplified) <= count_instructions(&symtab));
+        match &simplified.lookup("f").unwrap().value {
+            SymbolValues::Stmt(stmt) => stmt.clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn x() -> Expr {
+        Expr::symbol_expression("x", Type::signed_int(32))
+    }
+
+    fn int(i: i64) -> Expr {
+        Expr::int_constant(i, Type::signed_int(32))
+    }
+
+    fn assign_x(value: Expr) -> Stmt {
+        x().assign(value, Location::none())
+    }
+
+    fn goto(label: &str) -> Stmt {
+        Stmt::goto(label, Location::none())
+    }
+
+    fn skip() -> Stmt {
+        Stmt::skip(Location::none())
+    }
+
+    fn block(stmts: Vec<Stmt>) -> Stmt {
+        Stmt::block(stmts, Location::none())
+    }
+
+    fn stmts(body: &Stmt) -> &Vec<Stmt> {
+        match body.body() {
+            StmtBody::Block(stmts) => stmts,
+            _ => unreachable!("Expected a block, got {:?}", body),
+        }
+    }
+
+    fn assigned_constant(stmt: &Stmt) -> Option<i64> {
+        match stmt.body() {
+            StmtBody::Assign { rhs, .. } => {
+                rhs.int_constant_value().map(|i| i.to_string().parse().unwrap())
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        let cond = int(1).lt(int(2)).and(Expr::bool_true());
+        let body = simplify(vec![
+            assign_x(int(2).plus(int(3)).mul(int(4))),
+            assign_x(Expr::int_constant(7, Type::unsigned_int(8)).cast_to(Type::signed_int(32))),
+            assign_x(cond.ternary(int(1), int(0))),
+            assign_x(int(i32::MAX as i64).plus(int(1))),
+            assign_x(int(1).div(int(0))),
+        ]);
+        let stmts = stmts(&body);
+        assert_eq!(stmts.len(), 5);
+        assert_eq!(assigned_constant(&stmts[0]), Some(20));
+        assert_eq!(assigned_constant(&stmts[1]), Some(7));
+        assert_eq!(assigned_constant(&stmts[2]), Some(1));
+        // Overflowing and undefined operations are not folded.
+        assert_eq!(assigned_constant(&stmts[3]), None);
+        assert_eq!(assigned_constant(&stmts[4]), None);
+    }
+
+    #[test]
+    fn test_remove_no_ops() {
+        let unit = Expr::symbol_expression("u", Type::struct_tag("Unit"));
+        let body = simplify(vec![
+            skip(),
+            block(vec![skip(), skip()]),
+            Stmt::assume(int(1).add_overflow_p(int(2)).not(), Location::none()),
+            unit.assign(Type::struct_tag("Unit").nondet(), Location::none()),
+            Stmt::if_then_else(Expr::bool_false(), assign_x(int(1)), None, Location::none()),
+            Stmt::if_then_else(
+                Expr::bool_true(),
+                assign_x(int(2)),
+                Some(assign_x(int(3))),
+                Location::none(),
+            ),
+        ]);
+        let stmts = stmts(&body);
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(assigned_constant(&stmts[0]), Some(2));
+    }
+
+    #[test]
+    fn test_keep_branch_location() {
+        let if_loc = Location::new("main.rs", Some("f"), 3, None);
+        let assert_loc = Location::create_location_with_property(
+            "x is one",
+            "assertion",
+            Location::new("main.rs", Some("f"), 4, None),
+        );
+        let assert = Stmt::assert(x().eq(int(1)), "x is one", assert_loc);
+        let body = simplify(vec![Stmt::if_then_else(Expr::bool_true(), assert, None, if_loc)]);
+        let stmts = stmts(&body);
+        assert_eq!(stmts.len(), 1);
+        match stmts[0].location() {
+            Location::Property { line, comment, property_class, .. } => {
+                assert_eq!(*line, 4);
+                assert_eq!(comment.to_string(), "x is one");
+                assert_eq!(property_class.to_string(), "assertion");
+            }
+            loc => panic!("Expected the location of the assertion, got {:?}", loc),
+        }
+    }
+
+    #[test]
+    fn test_keep_scopes() {
+        let decl = Stmt::decl(x(), None, Location::none());
+        let body = simplify(vec![block(vec![decl, assign_x(int(1))]), assign_x(int(2))]);
+        let stmts = stmts(&body);
+        assert_eq!(stmts.len(), 2);
+        assert!(matches!(stmts[0].body(), StmtBody::Block(_)));
+    }
+
+    #[test]
+    fn test_collapse_gotos() {
+        // bb0: x = 0; goto bb1;
+        // bb1: goto bb2;
+        // bb2: goto bb3;
+        // bb3: x = 1; goto bb4;
+        // bb4: if (x == 1) goto bb3; return;
+        let body = simplify(vec![
+            block(vec![assign_x(int(0)), goto("bb1")]).with_label("bb0"),
+            block(vec![skip(), goto("bb2")]).with_label("bb1"),
+            goto("bb3").with_label("bb2"),
+            block(vec![assign_x(int(1)), goto("bb4")]).with_label("bb3"),
+            block(vec![
+                Stmt::if_then_else(x().eq(int(1)), goto("bb3"), None, Location::none()),
+                Stmt::ret(None, Location::none()),
+                assign_x(int(2)),
+            ])
+            .with_label("bb4"),
+        ]);
+        // x = 0;
+        // bb3: x = 1;
+        // if (x == 1) goto bb3; return;
+        let stmts = stmts(&body);
+        assert_eq!(stmts.len(), 4, "{:?}", stmts);
+        assert_eq!(assigned_constant(&stmts[0]), Some(0));
+        match stmts[1].body() {
+            StmtBody::Label { label, body } => {
+                assert_eq!(label.to_string(), "bb3");
+                assert_eq!(assigned_constant(body), Some(1));
+            }
+            _ => panic!("Expected a label, got {:?}", stmts[1]),
+        }
+        assert!(matches!(stmts[2].body(), StmtBody::Ifthenelse { .. }));
+        assert!(matches!(stmts[3].body(), StmtBody::Return(None)));
+    }
+
+    #[test]
+    fn test_goto_cycle() {
+        let body = simplify(vec![goto("a").with_label("b"), goto("b").with_label("a")]);
+        assert_eq!(stmts(&body).len(), 2);
+    }
+}
@@ -69,8 +69,8 @@ fn transform_type(&mut self, typ: &Type) -> Type {
     }
 
     /// Transforms an array type (`typ x[size]`)
-    fn transform_type_array(&mut self, typ: &Box<Type>, size: &u64) -> Type {
-        let transformed_typ = self.transform_type(typ.as_ref());
+    fn transform_type_array(&mut self, typ: &Type, size: &u64) -> Type {
+        let transformed_typ = self.transform_type(typ);
         transformed_typ.array_of(*size)
     }
 
@@ -80,8 +80,8 @@ fn transform_type_bool(&mut self) -> Type {
     }
 
     /// Transforms a c bit field type (`typ x : width`)
-    fn transform_type_c_bit_field(&mut self, typ: &Box<Type>, width: &u64) -> Type {
-        let transformed_typ = self.transform_type(typ.as_ref());
+    fn transform_type_c_bit_field(&mut self, typ: &Type, width: &u64) -> Type {
+        let transformed_typ = self.transform_type(typ);
         transformed_typ.as_bitfield(*width)
     }
 
@@ -103,7 +103,7 @@ fn transform_type_parameter(&mut self, parameter: &Parameter) -> Parameter {
     }
 
     /// Transforms a function type (`return_type x(parameters)`)
-    fn transform_type_code(&mut self, parameters: &[Parameter], return_type: &Box<Type>) -> Type {
+    fn transform_type_code(&mut self, parameters: &[Parameter], return_type: &Type) -> Type {
         let transformed_parameters =
             parameters.iter().map(|parameter| self.transform_type_parameter(parameter)).collect();
         let transformed_return_type = self.transform_type(return_type);
@@ -126,7 +126,7 @@ fn transform_type_empty(&mut self) -> Type {
     }
 
     /// Transforms a flexible array type (`typ x[]`)
-    fn transform_type_flexible_array(&mut self, typ: &Box<Type>) -> Type {
+    fn transform_type_flexible_array(&mut self, typ: &Type) -> Type {
         let transformed_typ = self.transform_type(typ);
         Type::flexible_array_of(transformed_typ)
     }
@@ -147,14 +147,14 @@ fn transform_type_incomplete_union(&mut self, tag: InternedString) -> Type {
     }
 
     /// Transforms an infinite array type (`typ x[__CPROVER_infinity()]`)
-    fn transform_type_infinite_array(&mut self, typ: &Box<Type>) -> Type {
-        let transformed_typ = self.transform_type(typ.as_ref());
+    fn transform_type_infinite_array(&mut self, typ: &Type) -> Type {
+        let transformed_typ = self.transform_type(typ);
         transformed_typ.infinite_array_of()
     }
 
     /// Transforms a pointer type (`typ*`)
-    fn transform_type_pointer(&mut self, typ: &Box<Type>) -> Type {
-        let transformed_typ = self.transform_type(typ.as_ref());
+    fn transform_type_pointer(&mut self, typ: &Type) -> Type {
+        let transformed_typ = self.transform_type(typ);
         transformed_typ.to_pointer()
     }
 
@@ -218,17 +218,17 @@ fn transform_type_unsignedbv(&mut self, width: &u64) -> Type {
     fn transform_type_variadic_code(
         &mut self,
         parameters: &[Parameter],
-        return_type: &Box<Type>,
+        return_type: &Type,
     ) -> Type {
         let transformed_parameters =
             parameters.iter().map(|parameter| self.transform_type_parameter(parameter)).collect();
-        let transformed_return_type = self.transform_type(return_type.as_ref());
+        let transformed_return_type = self.transform_type(return_type);
         Type::variadic_code(transformed_parameters, transformed_return_type)
     }
 
     /// Transforms a vector type (`typ __attribute__((vector_size (size * sizeof(typ)))) var;`)
-    fn transform_type_vector(&mut self, typ: &Box<Type>, size: &u64) -> Type {
-        let transformed_typ = self.transform_type(typ.as_ref());
+    fn transform_type_vector(&mut self, typ: &Type, size: &u64) -> Type {
+        let transformed_typ = self.transform_type(typ);
         Type::vector(transformed_typ, *size)
     }
 
@@ -492,8 +492,10 @@ fn transform_expr_un_op(&mut self, _typ: &Type, op: &UnaryOperand, e: &Expr) ->
     /// Perform recursive descent on a `Stmt` data structure.
     /// Extracts the variant's field data, and passes them into
     /// the corresponding stmt transformer method.
+    /// The result gets the location of `stmt`, unless the stmt transformer method gave it one,
+    /// e.g. the location of the branch that replaces an `if`.
     fn transform_stmt(&mut self, stmt: &Stmt) -> Stmt {
-        match stmt.body() {
+        let transformed = match stmt.body() {
             StmtBody::Assign { lhs, rhs } => self.transform_stmt_assign(lhs, rhs),
             StmtBody::Assume { cond } => self.transform_stmt_assume(cond),
             StmtBody::AtomicBlock(block) => self.transform_stmt_atomic_block(block),
@@ -513,12 +515,17 @@ fn transform_stmt(&mut self, stmt: &Stmt) -> Stmt {
             StmtBody::Label { label, body } => self.transform_stmt_label(*label, body),
             StmtBody::Return(value) => self.transform_stmt_return(value),
             StmtBody::Skip => self.transform_stmt_skip(),
+            StmtBody::StartThread(body) => self.transform_stmt_start_thread(body),
             StmtBody::Switch { control, cases, default } => {
                 self.transform_stmt_switch(control, cases, default)
             }
             StmtBody::While { cond, body } => self.transform_stmt_while(cond, body),
+        };
+        if transformed.location().is_none() {
+            transformed.with_location(stmt.location().clone())
+        } else {
+            transformed
         }
-        .with_location(stmt.location().clone())
     }
 
     /// Transforms an assign stmt (`lhs = rhs;`)
@@ -635,6 +642,12 @@ fn transform_stmt_skip(&mut self) -> Stmt {
         Stmt::skip(Location::none())
     }
 
+    /// Transforms a start thread stmt (`__CPROVER_ASYNC_1: body;`)
+    fn transform_stmt_start_thread(&mut self, body: &Stmt) -> Stmt {
+        let transformed_body = self.transform_stmt(body);
+        Stmt::start_thread(transformed_body, Location::none())
+    }
+
     /// Transforms a switch stmt (`switch (control) { case1.case: cast1.body; case2.case: case2.body; ... }`)
     fn transform_stmt_switch(
         &mut self,
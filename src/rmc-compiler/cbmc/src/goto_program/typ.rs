@@ -4,11 +4,13 @@
 use self::Type::*;
 use super::super::utils::{aggr_tag, max_int, min_int};
 use super::super::MachineModel;
+use super::typ_interner::{intern_components, intern_parameters, intern_type};
 use super::{Expr, SymbolTable};
 use crate::cbmc_string::InternedString;
 use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::fmt::Debug;
+use std::sync::Arc;
 
 ///////////////////////////////////////////////////////////////////////////////////////////////
 /// Datatypes
@@ -19,18 +21,22 @@
 /// In the examples below, `x` is used as a placeholder showing how the a variable of that
 /// type would be declared. In general, these types map directly to C types; when they do not,
 /// the comment notes this.
+///
+/// Types are cloned into every expression, so nested types, components and parameters are
+/// interned behind `Arc`s (see `typ_interner`): cloning a type never copies more than its
+/// outermost node, and equal nested types share one allocation.
 #[derive(PartialEq, Debug, Clone)]
 pub enum Type {
     /// `typ x[size]`. E.g. `unsigned int x[3]`
-    Array { typ: Box<Type>, size: u64 },
+    Array { typ: Arc<Type>, size: u64 },
     /// CBMC specific. `__CPROVER_bool x`. A single bit boolean
     Bool,
     /// `typ x : width`. e.g. `unsigned int x: 3`.
-    CBitField { typ: Box<Type>, width: u64 },
+    CBitField { typ: Arc<Type>, width: u64 },
     /// Machine dependent integers: `bool`, `char`, `int`, `size_t`, etc.
     CInteger(CIntType),
     /// `return_type x(parameters)`
-    Code { parameters: Vec<Parameter>, return_type: Box<Type> },
+    Code { parameters: Arc<[Parameter]>, return_type: Arc<Type> },
     /// `__attribute__(constructor)`. Only valid as a function return type.
     /// https://gcc.gnu.org/onlinedocs/gcc-4.7.0/gcc/Function-Attributes.html
     Constructor,
@@ -39,7 +45,7 @@ pub enum Type {
     /// `void`
     Empty,
     /// `typ x[]`. Has a type, but no size. Only valid as the last element of a struct.
-    FlexibleArray { typ: Box<Type> },
+    FlexibleArray { typ: Arc<Type> },
     /// `float`
     Float,
     /// `struct x {}`
@@ -47,31 +53,31 @@ pub enum Type {
     /// `union x {}`
     IncompleteUnion { tag: InternedString },
     /// CBMC specific. `typ x[__CPROVER_infinity()]`
-    InfiniteArray { typ: Box<Type> },
+    InfiniteArray { typ: Arc<Type> },
     /// `typ*`
-    Pointer { typ: Box<Type> },
+    Pointer { typ: Arc<Type> },
     /// `int<width>_t`. e.g. `int32_t`
     Signedbv { width: u64 },
     /// `struct tag {component1.typ component1.name; component2.typ component2.name ... }`
-    Struct { tag: InternedString, components: Vec<DatatypeComponent> },
+    Struct { tag: InternedString, components: Arc<[DatatypeComponent]> },
     /// CBMC specific. A reference into the symbol table, where the tag is the name of the symbol.
     StructTag(InternedString),
     /// `union tag {component1.typ component1.name; component2.typ component2.name ... }`
-    Union { tag: InternedString, components: Vec<DatatypeComponent> },
+    Union { tag: InternedString, components: Arc<[DatatypeComponent]> },
     /// CBMC specific. A reference into the symbol table, where the tag is the name of the symbol.
     UnionTag(InternedString),
     /// `uint<width>_t`. e.g. `uint32_t`
     Unsignedbv { width: u64 },
     /// `return_type x(parameters, ...)`
-    VariadicCode { parameters: Vec<Parameter>, return_type: Box<Type> },
+    VariadicCode { parameters: Arc<[Parameter]>, return_type: Arc<Type> },
     /// Packed SIMD vectors
     /// In CBMC/gcc, variables of this type are declared as:
     /// `typ __attribute__((vector_size (size * sizeof(typ)))) var;`
-    Vector { typ: Box<Type>, size: u64 },
+    Vector { typ: Arc<Type>, size: u64 },
 }
 
 /// Machine dependent integers: `bool`, `char`, `int`, `size_t`, etc.
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub enum CIntType {
     /// `bool`
     Bool,
@@ -158,6 +164,10 @@ fn eq(&self, other: &Self) -> bool {
     }
 }
 
+/// Equality of types is an equivalence (parameters are compared regardless of their names),
+/// which lets `Arc<Type>` compare the same interned type by its address.
+impl Eq for Type {}
+
 /// Getters
 impl Parameter {
     pub fn base_name(&self) -> Option<InternedString> {
@@ -210,7 +220,7 @@ pub fn base_type(&self) -> Option<&Type> {
         }
     }
 
-    pub fn components(&self) -> Option<&Vec<DatatypeComponent>> {
+    pub fn components(&self) -> Option<&[DatatypeComponent]> {
         match self {
             Struct { components, .. } | Union { components, .. } => Some(components),
             _ => None,
@@ -235,7 +245,7 @@ pub fn native_width(&self, mm: &MachineModel) -> Option<u64> {
         }
     }
 
-    pub fn parameters(&self) -> Option<&Vec<Parameter>> {
+    pub fn parameters(&self) -> Option<&[Parameter]> {
         match self {
             Code { parameters, .. } | VariadicCode { parameters, .. } => Some(parameters),
             _ => None,
@@ -589,14 +599,14 @@ pub fn array_of<T>(self, size: T) -> Self
         T::Error: Debug,
     {
         let size: u64 = size.try_into().unwrap();
-        Array { typ: Box::new(self), size }
+        Array { typ: intern_type(self), size }
     }
 
     pub fn as_bitfield(self, width: u64) -> Self {
         assert!(width > 0);
         assert!(self.is_integer());
         assert!(self.width().unwrap() >= width);
-        CBitField { width, typ: Box::new(self) }
+        CBitField { width, typ: intern_type(self) }
     }
 
     /// A formal function parameter.
@@ -645,7 +655,7 @@ pub fn c_ssize_t() -> Self {
     /// corresponds to [code_typet] in CBMC, representing a function type
     ///    ret (params ..)
     pub fn code(parameters: Vec<Parameter>, return_type: Type) -> Self {
-        Code { parameters, return_type: Box::new(return_type) }
+        Code { parameters: intern_parameters(parameters), return_type: intern_type(return_type) }
     }
 
     /// CBMC, like c, allows function types to have unnamed formal paramaters
@@ -684,17 +694,17 @@ pub fn empty() -> Self {
     /// Empty struct.
     /// struct name {};
     pub fn empty_struct<T: Into<InternedString>>(tag: T) -> Self {
-        Struct { tag: tag.into(), components: vec![] }
+        Struct { tag: tag.into(), components: intern_components(vec![]) }
     }
 
     /// Empty union.
     /// union name {};
     pub fn empty_union<T: Into<InternedString>>(tag: T) -> Self {
-        Union { tag: tag.into(), components: vec![] }
+        Union { tag: tag.into(), components: intern_components(vec![]) }
     }
 
     pub fn flexible_array_of(self) -> Self {
-        FlexibleArray { typ: Box::new(self) }
+        FlexibleArray { typ: intern_type(self) }
     }
 
     pub fn float() -> Self {
@@ -716,12 +726,12 @@ pub fn incomplete_union<T: Into<InternedString>>(tag: T) -> Self {
     }
 
     pub fn infinite_array_of(self) -> Self {
-        InfiniteArray { typ: Box::new(self) }
+        InfiniteArray { typ: intern_type(self) }
     }
 
     /// self *
     pub fn to_pointer(self) -> Self {
-        Pointer { typ: Box::new(self) }
+        Pointer { typ: intern_type(self) }
     }
 
     pub fn signed_int<T>(w: T) -> Self
@@ -771,7 +781,7 @@ pub fn struct_type<T: Into<InternedString>>(
             components
         );
         let tag = tag.into();
-        Struct { tag, components }
+        Struct { tag, components: intern_components(components) }
     }
 
     /// union name
@@ -794,7 +804,7 @@ pub fn union_type<T: Into<InternedString>>(tag: T, components: Vec<DatatypeCompo
             "Components contain duplicates: {:?}",
             components
         );
-        Union { tag, components }
+        Union { tag, components: intern_components(components) }
     }
 
     pub fn unsigned_int<T>(w: T) -> Self
@@ -810,7 +820,10 @@ pub fn unsigned_int<T>(w: T) -> Self
     /// corresponds to [code_typet] in CBMC, representing a function type
     ///    ret (params, ... )
     pub fn variadic_code(parameters: Vec<Parameter>, return_type: Type) -> Self {
-        VariadicCode { parameters, return_type: Box::new(return_type) }
+        VariadicCode {
+            parameters: intern_parameters(parameters),
+            return_type: intern_type(return_type),
+        }
     }
 
     /// CBMC, like c, allows function types to have unnamed formal paramaters
@@ -829,7 +842,7 @@ pub fn variadic_code_with_unnamed_parameters(
     // `size` is the number of elements (e.g., a SIMD vector of 4 integers)
     pub fn vector(typ: Type, size: u64) -> Self {
         assert!(typ.is_numeric());
-        Type::Vector { typ: Box::new(typ), size }
+        Type::Vector { typ: intern_type(typ), size }
     }
 
     /// `void *`
@@ -981,3 +994,102 @@ pub fn to_identifier(&self) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DatatypeComponent, Type};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_clone_is_shallow() {
+        let components = vec![DatatypeComponent::field("f", Type::c_int().to_pointer())];
+        let s = Type::struct_type("s", components);
+        match (&s, &s.clone()) {
+            (Type::Struct { components: c1, .. }, Type::Struct { components: c2, .. }) => {
+                assert!(Arc::ptr_eq(c1, c2));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_interning() {
+        let p1 = Type::struct_tag("interned").to_pointer().to_pointer();
+        let p2 = Type::struct_tag("interned").to_pointer().to_pointer();
+        match (&p1, &p2) {
+            (Type::Pointer { typ: t1 }, Type::Pointer { typ: t2 }) => assert!(Arc::ptr_eq(t1, t2)),
+            _ => unreachable!(),
+        }
+
+        // Code types are equal regardless of the names of their parameters, but interning
+        // keeps the names.
+        let param = |name: &str| Type::c_int().as_parameter(Some(name.into()), Some(name.into()));
+        let c1 = Type::code(vec![param("a")], Type::empty());
+        let c2 = Type::code(vec![param("b")], Type::empty());
+        let c3 = Type::code(vec![param("a")], Type::empty());
+        assert_eq!(c1, c2);
+        match (&c1, &c2, &c3) {
+            (
+                Type::Code { parameters: p1, .. },
+                Type::Code { parameters: p2, .. },
+                Type::Code { parameters: p3, .. },
+            ) => {
+                assert!(!Arc::ptr_eq(p1, p2));
+                assert!(Arc::ptr_eq(p1, p3));
+                assert_eq!(p2[0].base_name().unwrap().to_string(), "b");
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Measures the time and peak memory of building the types of a codegen-like workload:
+    /// every expression gets a freshly built type, as `codegen_ty` builds them, and all
+    /// expressions stay alive, as in the symbol table. Run it with
+    /// `cargo test --release -- --ignored --nocapture bench_codegen_types`.
+    #[test]
+    #[ignore]
+    fn bench_codegen_types() {
+        use crate::goto_program::Expr;
+        use std::time::Instant;
+
+        let fn_type = |i: usize| {
+            let s = Type::struct_tag(format!("s{}", i % 2000));
+            let params = vec![
+                s.clone().to_pointer().as_parameter(None, None),
+                Type::unsigned_int(64).to_pointer().to_pointer().as_parameter(None, None),
+                Type::struct_tag("slice").as_parameter(None, None),
+            ];
+            Type::code(params, s.to_pointer().to_pointer())
+        };
+        let struct_type = |i: usize| {
+            let components = (0..8)
+                .map(|f| {
+                    let typ = Type::struct_tag(format!("s{}", (i + f) % 2000)).to_pointer();
+                    DatatypeComponent::field(format!("f{}", f), typ)
+                })
+                .collect();
+            Type::struct_type(format!("s{}", i % 2000), components)
+        };
+
+        let start = Instant::now();
+        let mut exprs = vec![];
+        for i in 0..1_000_000 {
+            exprs.push(Expr::symbol_expression(format!("f{}", i % 5000), fn_type(i)));
+            if i % 10 == 0 {
+                exprs.push(Expr::symbol_expression(format!("v{}", i % 5000), struct_type(i)));
+            }
+        }
+        let elapsed = start.elapsed();
+        // The peak resident set size, on Linux
+        let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+        let peak =
+            status.lines().find(|line| line.starts_with("VmHWM")).unwrap_or("VmHWM: unknown");
+        println!("{} expressions in {:.2?}, {}", exprs.len(), elapsed, peak);
+    }
+
+    /// Every expression holds a `Type`, so growing it grows every expression.
+    #[test]
+    fn test_type_size() {
+        assert!(std::mem::size_of::<Type>() <= 32);
+    }
+}
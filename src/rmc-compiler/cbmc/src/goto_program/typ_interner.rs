@@ -0,0 +1,214 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module hash-conses the nested types, components and parameters of `Type`s.
+//! Codegen builds the same types over and over, e.g. `struct tag-Foo *` for every expression
+//! that uses a pointer to `Foo`, and each expression holds its own copy of its type. With
+//! interning, equal nested types share a single allocation, and comparing them is a pointer
+//! comparison.
+//!
+//! Since the nested types of an interned value are interned as well, it is enough to look at
+//! its outermost node to find an equal value: two interned nested values are equal if and only
+//! if they are the same allocation.
+//!
+//! This has two costs:
+//! - Like `InternedString`s, interned values live the lifetime of the execution: the interners
+//!   hold a reference to every value they have seen, and never free any of them. This is fine
+//!   for a compiler session, which keeps its symbol table until the end anyway, but a long
+//!   running process that builds many distinct types will only ever grow.
+//! - The interners are global and protected by a Mutex (i.e. threadsafe), so every constructor
+//!   of a nested type, e.g. each `to_pointer`, takes a global lock. Codegen is single threaded,
+//!   so the lock is never contended, but it would serialize a parallel codegen.
+//!
+//! The `bench_codegen_types` test in `typ.rs` measures the time and peak memory of a
+//! codegen-like workload.
+
+use super::{DatatypeComponent, Parameter, Type};
+use lazy_static::lazy_static;
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::mem::discriminant;
+use std::sync::{Arc, Mutex};
+
+lazy_static! {
+    static ref TYPES: Mutex<Interner<Type>> = Mutex::new(Interner::default());
+    static ref COMPONENTS: Mutex<Interner<[DatatypeComponent]>> = Mutex::new(Interner::default());
+    static ref PARAMETERS: Mutex<Interner<[Parameter]>> = Mutex::new(Interner::default());
+}
+
+/// The shared type equal to `typ`.
+pub fn intern_type(typ: Type) -> Arc<Type> {
+    TYPES.lock().unwrap().intern(typ)
+}
+
+/// The shared components equal to `components`.
+pub fn intern_components(components: Vec<DatatypeComponent>) -> Arc<[DatatypeComponent]> {
+    COMPONENTS.lock().unwrap().intern(components)
+}
+
+/// The shared parameters equal to `parameters`. Unlike `Parameter::eq`, parameters with
+/// different names are different.
+pub fn intern_parameters(parameters: Vec<Parameter>) -> Arc<[Parameter]> {
+    PARAMETERS.lock().unwrap().intern(parameters)
+}
+
+/// The values of an interner, by the hash of their outermost node.
+struct Interner<T: ?Sized> {
+    buckets: HashMap<u64, Vec<Arc<T>>>,
+}
+
+impl<T: ?Sized> Default for Interner<T> {
+    fn default() -> Self {
+        Interner { buckets: HashMap::new() }
+    }
+}
+
+impl<T: ?Sized + Shallow> Interner<T> {
+    fn intern<V: Borrow<T> + Into<Arc<T>>>(&mut self, value: V) -> Arc<T> {
+        let mut hasher = DefaultHasher::new();
+        value.borrow().shallow_hash(&mut hasher);
+        let bucket = self.buckets.entry(hasher.finish()).or_default();
+        if let Some(interned) = bucket.iter().find(|interned| interned.shallow_eq(value.borrow())) {
+            return interned.clone();
+        }
+        let interned = value.into();
+        bucket.push(interned.clone());
+        interned
+    }
+}
+
+/// Hashing and equality of the outermost node of a value, whose nested values are interned.
+/// Values that are equal this way are equal (without ignoring the names of parameters), and a
+/// value that is not interned is only equal to itself.
+trait Shallow {
+    fn shallow_hash<H: Hasher>(&self, state: &mut H);
+    fn shallow_eq(&self, other: &Self) -> bool;
+}
+
+fn address<T: ?Sized>(value: &Arc<T>) -> usize {
+    Arc::as_ptr(value) as *const u8 as usize
+}
+
+impl Shallow for Type {
+    fn shallow_hash<H: Hasher>(&self, state: &mut H) {
+        discriminant(self).hash(state);
+        match self {
+            Type::Array { typ, size } | Type::Vector { typ, size } => {
+                address(typ).hash(state);
+                size.hash(state);
+            }
+            Type::CBitField { typ, width } => {
+                address(typ).hash(state);
+                width.hash(state);
+            }
+            Type::Code { parameters, return_type }
+            | Type::VariadicCode { parameters, return_type } => {
+                address(parameters).hash(state);
+                address(return_type).hash(state);
+            }
+            Type::FlexibleArray { typ } | Type::InfiniteArray { typ } | Type::Pointer { typ } => {
+                address(typ).hash(state)
+            }
+            Type::Struct { tag, components } | Type::Union { tag, components } => {
+                tag.hash(state);
+                address(components).hash(state);
+            }
+            Type::CInteger(int_type) => int_type.hash(state),
+            Type::IncompleteStruct { tag }
+            | Type::IncompleteUnion { tag }
+            | Type::StructTag(tag)
+            | Type::UnionTag(tag) => tag.hash(state),
+            Type::Signedbv { width } | Type::Unsignedbv { width } => width.hash(state),
+            Type::Bool | Type::Constructor | Type::Double | Type::Empty | Type::Float => {}
+        }
+    }
+
+    fn shallow_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Type::Array { typ: t1, size: s1 }, Type::Array { typ: t2, size: s2 })
+            | (Type::Vector { typ: t1, size: s1 }, Type::Vector { typ: t2, size: s2 })
+            | (Type::CBitField { typ: t1, width: s1 }, Type::CBitField { typ: t2, width: s2 }) => {
+                Arc::ptr_eq(t1, t2) && s1 == s2
+            }
+            (
+                Type::Code { parameters: p1, return_type: r1 },
+                Type::Code { parameters: p2, return_type: r2 },
+            )
+            | (
+                Type::VariadicCode { parameters: p1, return_type: r1 },
+                Type::VariadicCode { parameters: p2, return_type: r2 },
+            ) => Arc::ptr_eq(p1, p2) && Arc::ptr_eq(r1, r2),
+            (Type::FlexibleArray { typ: t1 }, Type::FlexibleArray { typ: t2 })
+            | (Type::InfiniteArray { typ: t1 }, Type::InfiniteArray { typ: t2 })
+            | (Type::Pointer { typ: t1 }, Type::Pointer { typ: t2 }) => Arc::ptr_eq(t1, t2),
+            (
+                Type::Struct { tag: g1, components: c1 },
+                Type::Struct { tag: g2, components: c2 },
+            )
+            | (Type::Union { tag: g1, components: c1 }, Type::Union { tag: g2, components: c2 }) => {
+                g1 == g2 && Arc::ptr_eq(c1, c2)
+            }
+            // The other types do not have nested types
+            _ => self == other,
+        }
+    }
+}
+
+impl Shallow for DatatypeComponent {
+    fn shallow_hash<H: Hasher>(&self, state: &mut H) {
+        discriminant(self).hash(state);
+        match self {
+            DatatypeComponent::Field { name, typ } => {
+                name.hash(state);
+                typ.shallow_hash(state);
+            }
+            DatatypeComponent::Padding { name, bits } => {
+                name.hash(state);
+                bits.hash(state);
+            }
+        }
+    }
+
+    fn shallow_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                DatatypeComponent::Field { name: n1, typ: t1 },
+                DatatypeComponent::Field { name: n2, typ: t2 },
+            ) => n1 == n2 && t1.shallow_eq(t2),
+            (
+                DatatypeComponent::Padding { name: n1, bits: b1 },
+                DatatypeComponent::Padding { name: n2, bits: b2 },
+            ) => n1 == n2 && b1 == b2,
+            _ => false,
+        }
+    }
+}
+
+impl Shallow for Parameter {
+    fn shallow_hash<H: Hasher>(&self, state: &mut H) {
+        self.identifier().hash(state);
+        self.base_name().hash(state);
+        self.typ().shallow_hash(state);
+    }
+
+    fn shallow_eq(&self, other: &Self) -> bool {
+        self.identifier() == other.identifier()
+            && self.base_name() == other.base_name()
+            && self.typ().shallow_eq(other.typ())
+    }
+}
+
+impl<T: Shallow> Shallow for [T] {
+    fn shallow_hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for value in self {
+            value.shallow_hash(state);
+        }
+    }
+
+    fn shallow_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(v1, v2)| v1.shallow_eq(v2))
+    }
+}
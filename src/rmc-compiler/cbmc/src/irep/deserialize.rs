@@ -0,0 +1,205 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This crate implements irep deserialization from the json symbol tables that
+//! `serialize` produces, e.g. to link the symbol tables of several crates.
+//!
+//! Ids are read back as `IrepId::FreeformString`, since there is no mapping from strings to
+//! the other ids. They serialize to the same strings, so a table that is read and written back
+//! is unchanged, but ids should be compared with `to_string`.
+use crate::irep::{Irep, IrepId, Symbol, SymbolTable};
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use vector_map::VecMap;
+
+impl<'de> Deserialize<'de> for IrepId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(IrepId::from_string)
+    }
+}
+
+/// The named subtrees of an irep, in the order of the json object.
+#[derive(Default)]
+struct NamedSub(VecMap<IrepId, Irep>);
+
+impl<'de> Deserialize<'de> for NamedSub {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(NamedSubVisitor)
+    }
+}
+
+struct NamedSubVisitor;
+
+impl<'de> Visitor<'de> for NamedSubVisitor {
+    type Value = NamedSub;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map of named subtrees")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut named_sub = VecMap::new();
+        while let Some((key, value)) = map.next_entry()? {
+            named_sub.insert(key, value);
+        }
+        Ok(NamedSub(named_sub))
+    }
+}
+
+impl<'de> Deserialize<'de> for Irep {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct RawIrep {
+            id: IrepId,
+            #[serde(default)]
+            sub: Vec<Irep>,
+            #[serde(default, rename = "namedSub")]
+            named_sub: NamedSub,
+        }
+
+        let raw = RawIrep::deserialize(deserializer)?;
+        Ok(Irep { id: raw.id, sub: raw.sub, named_sub: raw.named_sub.0 })
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RawSymbol {
+            #[serde(rename = "type")]
+            typ: Irep,
+            value: Irep,
+            location: Irep,
+            name: crate::InternedString,
+            module: crate::InternedString,
+            base_name: crate::InternedString,
+            pretty_name: crate::InternedString,
+            mode: crate::InternedString,
+            is_type: bool,
+            is_macro: bool,
+            is_exported: bool,
+            is_input: bool,
+            is_output: bool,
+            is_state_var: bool,
+            is_property: bool,
+            is_static_lifetime: bool,
+            is_thread_local: bool,
+            is_lvalue: bool,
+            is_file_local: bool,
+            is_extern: bool,
+            is_volatile: bool,
+            is_parameter: bool,
+            is_auxiliary: bool,
+            is_weak: bool,
+        }
+
+        let raw = RawSymbol::deserialize(deserializer)?;
+        Ok(Symbol {
+            typ: raw.typ,
+            value: raw.value,
+            location: raw.location,
+            name: raw.name,
+            module: raw.module,
+            base_name: raw.base_name,
+            pretty_name: raw.pretty_name,
+            mode: raw.mode,
+            is_type: raw.is_type,
+            is_macro: raw.is_macro,
+            is_exported: raw.is_exported,
+            is_input: raw.is_input,
+            is_output: raw.is_output,
+            is_state_var: raw.is_state_var,
+            is_property: raw.is_property,
+            is_static_lifetime: raw.is_static_lifetime,
+            is_thread_local: raw.is_thread_local,
+            is_lvalue: raw.is_lvalue,
+            is_file_local: raw.is_file_local,
+            is_extern: raw.is_extern,
+            is_volatile: raw.is_volatile,
+            is_parameter: raw.is_parameter,
+            is_auxiliary: raw.is_auxiliary,
+            is_weak: raw.is_weak,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for SymbolTable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct RawSymbolTable {
+            #[serde(rename = "symbolTable")]
+            symbol_table: BTreeMap<String, Symbol>,
+        }
+
+        let raw = RawSymbolTable::deserialize(deserializer)?;
+        let mut symtab = SymbolTable::new();
+        for symbol in raw.symbol_table.into_values() {
+            symtab.insert(symbol);
+        }
+        Ok(symtab)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_test::{assert_de_tokens, Token};
+
+    #[test]
+    fn deserialize_irep() {
+        let irep = Irep {
+            id: IrepId::from_string("pointer"),
+            sub: vec![Irep::just_id(IrepId::from_string("empty"))],
+            named_sub: vec![(
+                IrepId::from_string("width"),
+                Irep::just_id(IrepId::from_string("64")),
+            )]
+            .into_iter()
+            .collect(),
+        };
+        assert_de_tokens(
+            &irep,
+            &[
+                Token::Map { len: None },
+                Token::String("id"),
+                Token::String("pointer"),
+                Token::String("sub"),
+                Token::Seq { len: Some(1) },
+                Token::Map { len: None },
+                Token::String("id"),
+                Token::String("empty"),
+                Token::MapEnd,
+                Token::SeqEnd,
+                Token::String("namedSub"),
+                Token::Map { len: None },
+                Token::String("width"),
+                Token::Map { len: None },
+                Token::String("id"),
+                Token::String("64"),
+                Token::MapEnd,
+                Token::MapEnd,
+                Token::MapEnd,
+            ],
+        );
+    }
+}
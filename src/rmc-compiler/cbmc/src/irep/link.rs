@@ -0,0 +1,177 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This module links the symbol tables of several crates, e.g. of a crate, its dependencies and
+//! the standard library built together with them (`-Z build-std`), into a single symbol table.
+//!
+//! Symbols are resolved by name. When several tables have a symbol with the same name, we keep:
+//!  - a function definition over a declaration,
+//!  - a static variable with an initial value over one without,
+//!  - a complete struct or union over an incomplete one,
+//!  - otherwise, the symbol of the first table that has it. Since the names are mangled, these
+//!    are the same item codegenned by several crates, e.g. an instance of a generic function.
+//!
+//! The symbols must have the same type, ignoring comments such as `#source_location`.
+use super::{Irep, Symbol, SymbolTable};
+use crate::InternedString;
+use std::collections::BTreeMap;
+
+/// Links `tables`, each given with a name for its origin (e.g. its file), which is used in
+/// the error messages. Returns all type mismatches if there is any.
+pub fn link(tables: Vec<(String, SymbolTable)>) -> Result<SymbolTable, Vec<String>> {
+    let mut linked: BTreeMap<InternedString, (usize, Symbol)> = BTreeMap::new();
+    let mut errors = vec![];
+    for (idx, (origin, table)) in tables.iter().enumerate() {
+        for (name, symbol) in &table.symbol_table {
+            match linked.get(name) {
+                None => {
+                    linked.insert(*name, (idx, symbol.clone()));
+                }
+                Some((old_idx, old_symbol)) => {
+                    if !has_same_type(old_symbol, symbol) {
+                        errors.push(format!(
+                            "Symbol {} has different types in {} and {}",
+                            name, tables[*old_idx].0, origin
+                        ));
+                    } else if progresses(old_symbol, symbol) {
+                        linked.insert(*name, (idx, symbol.clone()));
+                    }
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let mut symtab = SymbolTable::new();
+    for (_, (_, symbol)) in linked {
+        symtab.insert(symbol);
+    }
+    Ok(symtab)
+}
+
+/// Whether `new` should replace `old`, which has the same name.
+fn progresses(old: &Symbol, new: &Symbol) -> bool {
+    if old.is_type {
+        is_incomplete(&old.typ) && !is_incomplete(&new.typ)
+    } else {
+        is_nil(&old.value) && !is_nil(&new.value)
+    }
+}
+
+fn is_incomplete(typ: &Irep) -> bool {
+    matches!(typ.id.to_string().as_str(), "incomplete_struct" | "incomplete_union")
+}
+
+fn has_same_type(old: &Symbol, new: &Symbol) -> bool {
+    old.is_type == new.is_type
+        && (is_incomplete(&old.typ)
+            || is_incomplete(&new.typ)
+            || eq_ignoring_comments(&old.typ, &new.typ))
+}
+
+/// Whether the ireps are equal, ignoring their comments, i.e. the named subtrees starting with
+/// `#`, such as source locations.
+fn eq_ignoring_comments(a: &Irep, b: &Irep) -> bool {
+    fn named_sub(irep: &Irep) -> Vec<(String, &Irep)> {
+        let mut named_sub: Vec<_> = irep
+            .named_sub
+            .iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .filter(|(key, _)| !key.starts_with('#'))
+            .collect();
+        named_sub.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+        named_sub
+    }
+
+    let (a_named_sub, b_named_sub) = (named_sub(a), named_sub(b));
+    a.id.to_string() == b.id.to_string()
+        && a.sub.len() == b.sub.len()
+        && a.sub.iter().zip(&b.sub).all(|(a, b)| eq_ignoring_comments(a, b))
+        && a_named_sub.len() == b_named_sub.len()
+        && a_named_sub
+            .iter()
+            .zip(&b_named_sub)
+            .all(|((ka, va), (kb, vb))| ka == kb && eq_ignoring_comments(va, vb))
+}
+
+/// Whether the id of `irep` is `nil`, also for ireps read back from json, whose ids are
+/// freeform strings.
+fn is_nil(irep: &Irep) -> bool {
+    irep.id.to_string() == "nil"
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::irep::IrepId;
+
+    fn irep(id: &str) -> Irep {
+        Irep::just_string_id(id)
+    }
+
+    fn symbol(name: &str, typ: Irep, value: Irep, is_type: bool) -> Symbol {
+        Symbol {
+            typ,
+            value,
+            location: Irep::nil(),
+            name: name.into(),
+            module: "".into(),
+            base_name: name.into(),
+            pretty_name: name.into(),
+            mode: "C".into(),
+            is_type,
+            is_macro: false,
+            is_exported: false,
+            is_input: false,
+            is_output: false,
+            is_state_var: false,
+            is_property: false,
+            is_static_lifetime: false,
+            is_thread_local: false,
+            is_lvalue: false,
+            is_file_local: false,
+            is_extern: false,
+            is_volatile: false,
+            is_parameter: false,
+            is_auxiliary: false,
+            is_weak: false,
+        }
+    }
+
+    fn table(symbols: Vec<Symbol>) -> SymbolTable {
+        let mut symtab = SymbolTable::new();
+        symbols.into_iter().for_each(|symbol| symtab.insert(symbol));
+        symtab
+    }
+
+    #[test]
+    fn test_link() {
+        let located_code = Irep::just_string_id("code")
+            .with_named_sub(IrepId::from_string("#source_location"), irep("lib.rs:1"));
+        let first = table(vec![
+            symbol("f", irep("code"), irep("nil"), false),
+            symbol("g", irep("code"), irep("first"), false),
+            symbol("tag-S", irep("incomplete_struct"), irep("nil"), true),
+        ]);
+        let second = table(vec![
+            symbol("f", located_code, irep("body"), false),
+            symbol("g", irep("code"), irep("second"), false),
+            symbol("tag-S", irep("struct"), irep("nil"), true),
+            symbol("h", irep("code"), irep("nil"), false),
+        ]);
+        let linked = link(vec![("a".into(), first), ("b".into(), second)]).unwrap();
+        let value = |name: &str| linked.symbol_table[&name.into()].value.id.to_string();
+        assert_eq!(value("f"), "body");
+        assert_eq!(value("g"), "first");
+        assert_eq!(value("h"), "nil");
+        assert_eq!(linked.symbol_table[&"tag-S".into()].typ.id.to_string(), "struct");
+    }
+
+    #[test]
+    fn test_link_type_mismatch() {
+        let first = table(vec![symbol("x", irep("bool"), irep("nil"), false)]);
+        let second = table(vec![symbol("x", irep("signedbv"), irep("nil"), false)]);
+        let errors = link(vec![("a".into(), first), ("b".into(), second)]).unwrap_err();
+        assert_eq!(errors, vec!["Symbol x has different types in a and b".to_string()]);
+    }
+}
@@ -11,11 +11,12 @@
 //! This representation does not take advantage of the sharing features available for ireps in CBMC to reduce memory usage.
 //!
 //! TODO: Complete the from-irep trait for remaining data types
-//! TODO: Parser for json symbol tables into the internal irep format
 //! TODO: Investigate memory usage, and consider using sharing to reduce memory usage
 
+pub mod deserialize;
 mod irep;
 mod irep_id;
+pub mod link;
 pub mod serialize;
 mod symbol;
 mod symbol_table;
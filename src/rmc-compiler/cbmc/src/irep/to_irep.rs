@@ -323,6 +323,16 @@ fn to_irep(&self, _mm: &MachineModel) -> Irep {
             ])
             .with_named_sub_option(IrepId::Column, col.map(Irep::just_int_id))
             .with_named_sub_option(IrepId::Function, function.map(Irep::just_string_id)),
+            Location::Property { file, function, line, col, comment, property_class } => {
+                Irep::just_named_sub(vector_map![
+                    (IrepId::File, Irep::just_string_id(file.to_string())),
+                    (IrepId::Line, Irep::just_int_id(*line)),
+                    (IrepId::Comment, Irep::just_string_id(comment.to_string())),
+                    (IrepId::PropertyClass, Irep::just_string_id(property_class.to_string())),
+                ])
+                .with_named_sub_option(IrepId::Column, col.map(Irep::just_int_id))
+                .with_named_sub_option(IrepId::Function, function.map(Irep::just_string_id))
+            }
         }
     }
 }
@@ -403,6 +413,7 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
                 code_irep(IrepId::Return, vec![e.as_ref().map_or(Irep::nil(), |x| x.to_irep(mm))])
             }
             StmtBody::Skip => code_irep(IrepId::Skip, vec![]),
+            StmtBody::StartThread(body) => code_irep(IrepId::StartThread, vec![body.to_irep(mm)]),
             StmtBody::Switch { control, cases, default } => {
                 let mut switch_arms: Vec<Irep> = cases.iter().map(|x| x.to_irep(mm)).collect();
                 if default.is_some() {
@@ -97,6 +97,36 @@ pub fn new(
     }
 }
 
+/// The machine model of x86_64, for the unit tests.
+#[cfg(test)]
+impl MachineModel {
+    pub(crate) fn test_default() -> Self {
+        MachineModel::new(
+            1,
+            "x86_64",
+            8,
+            false,
+            8,
+            64,
+            32,
+            32,
+            false,
+            128,
+            64,
+            64,
+            4,
+            true,
+            64,
+            RoundingMode::ToNearest,
+            16,
+            32,
+            false,
+            32,
+            32,
+        )
+    }
+}
+
 /// Getters
 impl MachineModel {
     pub fn alignment(&self) -> u64 {
@@ -7,14 +7,62 @@ pub trait UserInput {
     fn set_symbol_table_passes(&mut self, passes: Vec<String>);
     fn get_symbol_table_passes(&self) -> Vec<String>;
 
+    fn set_symbol_table_passes_file(&mut self, file: Option<String>);
+    fn get_symbol_table_passes_file(&self) -> Option<String>;
+
+    fn set_dump_after(&mut self, passes: Vec<String>);
+    fn get_dump_after(&self) -> Vec<String>;
+
+    fn set_dump_goto(&mut self, functions: Vec<String>);
+    fn get_dump_goto(&self) -> Vec<String>;
+
     fn set_emit_vtable_restrictions(&mut self, restrictions: bool);
     fn get_emit_vtable_restrictions(&self) -> bool;
+
+    fn set_emit_fn_ptr_restrictions(&mut self, restrictions: bool);
+    fn get_emit_fn_ptr_restrictions(&self) -> bool;
+
+    fn set_panic_unwind(&mut self, panic_unwind: bool);
+    fn get_panic_unwind(&self) -> bool;
+
+    fn set_havoc_inline_asm(&mut self, havoc_inline_asm: bool);
+    fn get_havoc_inline_asm(&self) -> bool;
+
+    fn set_inline_asm_models(&mut self, models: Vec<(String, String)>);
+    fn get_inline_asm_models(&self) -> Vec<(String, String)>;
+
+    fn set_simple_hashing(&mut self, simple_hashing: bool);
+    fn get_simple_hashing(&self) -> bool;
+
+    fn set_alloc_may_fail(&mut self, alloc_may_fail: bool);
+    fn get_alloc_may_fail(&self) -> bool;
+
+    fn set_heap_bound(&mut self, heap_bound: Option<u64>);
+    fn get_heap_bound(&self) -> Option<u64>;
+
+    fn set_check_leaks(&mut self, check_leaks: bool);
+    fn get_check_leaks(&self) -> bool;
+
+    fn set_entry_points(&mut self, entry_points: Vec<String>);
+    fn get_entry_points(&self) -> Vec<String>;
 }
 
 #[derive(Debug, Default)]
 pub struct QueryDb {
+    alloc_may_fail: AtomicBool,
+    check_leaks: AtomicBool,
+    dump_after: Vec<String>,
+    dump_goto: Vec<String>,
+    emit_fn_ptr_restrictions: AtomicBool,
     emit_vtable_restrictions: AtomicBool,
+    entry_points: Vec<String>,
+    panic_unwind: AtomicBool,
+    havoc_inline_asm: AtomicBool,
+    heap_bound: Option<u64>,
+    inline_asm_models: Vec<(String, String)>,
+    simple_hashing: AtomicBool,
     symbol_table_passes: Vec<String>,
+    symbol_table_passes_file: Option<String>,
 }
 
 impl UserInput for QueryDb {
@@ -26,6 +74,30 @@ fn get_symbol_table_passes(&self) -> Vec<String> {
         self.symbol_table_passes.clone()
     }
 
+    fn set_symbol_table_passes_file(&mut self, file: Option<String>) {
+        self.symbol_table_passes_file = file;
+    }
+
+    fn get_symbol_table_passes_file(&self) -> Option<String> {
+        self.symbol_table_passes_file.clone()
+    }
+
+    fn set_dump_after(&mut self, passes: Vec<String>) {
+        self.dump_after = passes;
+    }
+
+    fn get_dump_after(&self) -> Vec<String> {
+        self.dump_after.clone()
+    }
+
+    fn set_dump_goto(&mut self, functions: Vec<String>) {
+        self.dump_goto = functions;
+    }
+
+    fn get_dump_goto(&self) -> Vec<String> {
+        self.dump_goto.clone()
+    }
+
     fn set_emit_vtable_restrictions(&mut self, restrictions: bool) {
         self.emit_vtable_restrictions.store(restrictions, Ordering::Relaxed);
     }
@@ -33,4 +105,76 @@ fn set_emit_vtable_restrictions(&mut self, restrictions: bool) {
     fn get_emit_vtable_restrictions(&self) -> bool {
         self.emit_vtable_restrictions.load(Ordering::Relaxed)
     }
+
+    fn set_emit_fn_ptr_restrictions(&mut self, restrictions: bool) {
+        self.emit_fn_ptr_restrictions.store(restrictions, Ordering::Relaxed);
+    }
+
+    fn get_emit_fn_ptr_restrictions(&self) -> bool {
+        self.emit_fn_ptr_restrictions.load(Ordering::Relaxed)
+    }
+
+    fn set_panic_unwind(&mut self, panic_unwind: bool) {
+        self.panic_unwind.store(panic_unwind, Ordering::Relaxed);
+    }
+
+    fn get_panic_unwind(&self) -> bool {
+        self.panic_unwind.load(Ordering::Relaxed)
+    }
+
+    fn set_havoc_inline_asm(&mut self, havoc_inline_asm: bool) {
+        self.havoc_inline_asm.store(havoc_inline_asm, Ordering::Relaxed);
+    }
+
+    fn get_havoc_inline_asm(&self) -> bool {
+        self.havoc_inline_asm.load(Ordering::Relaxed)
+    }
+
+    fn set_inline_asm_models(&mut self, models: Vec<(String, String)>) {
+        self.inline_asm_models = models;
+    }
+
+    fn get_inline_asm_models(&self) -> Vec<(String, String)> {
+        self.inline_asm_models.clone()
+    }
+
+    fn set_simple_hashing(&mut self, simple_hashing: bool) {
+        self.simple_hashing.store(simple_hashing, Ordering::Relaxed);
+    }
+
+    fn get_simple_hashing(&self) -> bool {
+        self.simple_hashing.load(Ordering::Relaxed)
+    }
+
+    fn set_alloc_may_fail(&mut self, alloc_may_fail: bool) {
+        self.alloc_may_fail.store(alloc_may_fail, Ordering::Relaxed);
+    }
+
+    fn get_alloc_may_fail(&self) -> bool {
+        self.alloc_may_fail.load(Ordering::Relaxed)
+    }
+
+    fn set_heap_bound(&mut self, heap_bound: Option<u64>) {
+        self.heap_bound = heap_bound;
+    }
+
+    fn get_heap_bound(&self) -> Option<u64> {
+        self.heap_bound
+    }
+
+    fn set_check_leaks(&mut self, check_leaks: bool) {
+        self.check_leaks.store(check_leaks, Ordering::Relaxed);
+    }
+
+    fn get_check_leaks(&self) -> bool {
+        self.check_leaks.load(Ordering::Relaxed)
+    }
+
+    fn set_entry_points(&mut self, entry_points: Vec<String>) {
+        self.entry_points = entry_points;
+    }
+
+    fn get_entry_points(&self) -> Vec<String> {
+        self.entry_points.clone()
+    }
 }
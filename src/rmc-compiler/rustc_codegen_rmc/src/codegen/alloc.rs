@@ -0,0 +1,264 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module models the allocator shims `__rust_alloc`, `__rust_alloc_zeroed`,
+//! `__rust_realloc` and `__rust_dealloc`, which `alloc::alloc::Global` calls to manage the heap.
+//!
+//! If the crate declares a `#[global_allocator]`, the shims call the `__rg_alloc`,
+//! `__rg_alloc_zeroed`, `__rg_realloc` and `__rg_dealloc` functions that the attribute generates,
+//! so the code of the allocator is verified as well.
+//!
+//! Otherwise, the shims call the CBMC `malloc`, `calloc`, `realloc` and `free`, which never fail.
+//! Two options make allocations return null, which the standard library reports by calling
+//! `handle_alloc_error`, and which `try_reserve` returns as an error:
+//!  - With `--alloc-may-fail`, any allocation may fail.
+//!  - With `--heap-bound BYTES`, an allocation fails if the total size of the live allocations
+//!    would exceed `BYTES`. We track this size in the global variable `__rmc_heap_size`.
+//!
+//! The shims also track the allocations for leak checks (see `leak.rs`).
+
+use crate::GotocCtx;
+use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Type};
+use rmc_queries::UserInput;
+use rustc_ast::expand::allocator::AllocatorKind;
+use rustc_middle::mir::{BasicBlock, Place};
+
+const HEAP_SIZE_VAR_NAME: &str = "__rmc_heap_size";
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Whether some crate of the program declares a `#[global_allocator]`.
+    pub fn has_global_allocator(&self) -> bool {
+        self.tcx.allocator_kind(()) == Some(AllocatorKind::Global)
+    }
+
+    fn codegen_heap_size(&mut self) -> Expr {
+        self.ensure_global_var(
+            HEAP_SIZE_VAR_NAME,
+            false,
+            Type::size_t(),
+            Location::none(),
+            |_, var| Some(var.assign(Type::size_t().zero(), Location::none())),
+        )
+    }
+
+    /// Generates a call to the allocator shim `name` (e.g. `__rust_alloc`) with `fargs`.
+    /// The result, if any, is assigned to `p`.
+    pub fn codegen_allocator_shim(
+        &mut self,
+        name: &str,
+        fargs: Vec<Expr>,
+        p: Option<&Place<'tcx>>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let track = if self.track_leaks {
+            self.codegen_track_allocator_shim(name, &fargs, p, loc.clone())
+        } else {
+            Stmt::skip(loc.clone())
+        };
+        let call = if self.has_global_allocator() {
+            self.codegen_global_allocator_call(name, fargs, p, loc.clone())
+        } else {
+            match name {
+                "__rust_alloc" | "__rust_alloc_zeroed" => {
+                    self.codegen_default_alloc(name, fargs, p.unwrap(), loc.clone())
+                }
+                "__rust_realloc" => self.codegen_default_realloc(fargs, p.unwrap(), loc.clone()),
+                "__rust_dealloc" => self.codegen_default_dealloc(fargs, loc.clone()),
+                _ => unreachable!("Unexpected allocator shim {}", name),
+            }
+        };
+        Stmt::block(
+            vec![call, track, Stmt::goto(self.current_fn().find_label(&target), loc.clone())],
+            loc,
+        )
+    }
+
+    /// Tracks the allocations and deallocations of the shim `name` for leak checks, once the
+    /// shim returned.
+    fn codegen_track_allocator_shim(
+        &mut self,
+        name: &str,
+        fargs: &[Expr],
+        p: Option<&Place<'tcx>>,
+        loc: Location,
+    ) -> Stmt {
+        match name {
+            "__rust_alloc" | "__rust_alloc_zeroed" => {
+                let ptr = self.codegen_place(p.unwrap()).goto_expr;
+                self.codegen_track_alloc(ptr, loc)
+            }
+            "__rust_realloc" => {
+                // A failed reallocation leaves the original block allocated.
+                let ptr = self.codegen_place(p.unwrap()).goto_expr;
+                let freed = self.codegen_track_free(fargs[0].clone(), loc.clone());
+                Stmt::block(
+                    vec![
+                        ptr.clone().is_nonnull().if_then_else(freed, None, loc.clone()),
+                        self.codegen_track_alloc(ptr, loc.clone()),
+                    ],
+                    loc,
+                )
+            }
+            "__rust_dealloc" => self.codegen_track_free(fargs[0].clone(), loc),
+            _ => unreachable!("Unexpected allocator shim {}", name),
+        }
+    }
+
+    /// Calls the function that `#[global_allocator]` generates for the shim `name`, which has the
+    /// same signature as the shim.
+    fn codegen_global_allocator_call(
+        &mut self,
+        name: &str,
+        fargs: Vec<Expr>,
+        p: Option<&Place<'tcx>>,
+        loc: Location,
+    ) -> Stmt {
+        let call = match self.find_function(name.replacen("__rust_", "__rg_", 1)) {
+            Some(function) => function.call(fargs),
+            None => self.codegen_unimplemented(
+                "A global allocator declared in another crate",
+                Type::unsigned_int(8).to_pointer(),
+                loc.clone(),
+                "https://github.com/model-checking/rmc/issues/new/choose",
+            ),
+        };
+        match p {
+            Some(p) => self.codegen_expr_to_place(p, call),
+            None => call.as_stmt(loc),
+        }
+    }
+
+    /// The condition under which an allocation of `size` bytes fails, given that `freed` bytes
+    /// are freed if it succeeds, or `None` if allocations never fail.
+    fn codegen_alloc_fails(&mut self, size: Expr, freed: Option<Expr>) -> Option<Expr> {
+        let may_fail = self.queries.get_alloc_may_fail().then(|| Type::bool().nondet());
+        let exceeds_bound = self.queries.get_heap_bound().map(|bound| {
+            // `__rmc_heap_size <= bound` always holds, so this does not overflow.
+            let available = Expr::int_constant(bound, Type::size_t()).sub(self.codegen_heap_size());
+            let available = match freed {
+                Some(freed) => available.plus(freed),
+                None => available,
+            };
+            size.gt(available)
+        });
+        match (may_fail, exceeds_bound) {
+            (Some(may_fail), Some(exceeds_bound)) => Some(may_fail.or(exceeds_bound)),
+            (may_fail, exceeds_bound) => may_fail.or(exceeds_bound),
+        }
+    }
+
+    /// Updates `__rmc_heap_size` after `size` bytes were allocated or `freed` bytes were freed.
+    fn codegen_heap_size_update(
+        &mut self,
+        size: Option<Expr>,
+        freed: Option<Expr>,
+        loc: Location,
+    ) -> Stmt {
+        if self.queries.get_heap_bound().is_none() {
+            return Stmt::skip(loc);
+        }
+        let heap_size = self.codegen_heap_size();
+        let mut new_size = heap_size.clone();
+        if let Some(freed) = freed {
+            new_size = new_size.sub(freed);
+        }
+        if let Some(size) = size {
+            new_size = new_size.plus(size);
+        }
+        heap_size.assign(new_size, loc)
+    }
+
+    /// `__rust_alloc(size, align)` and `__rust_alloc_zeroed(size, align)` are modeled as:
+    /// ```c
+    /// if (fails(size)) { p = NULL; } else { p = malloc(size); __rmc_heap_size += size; }
+    /// ```
+    /// where `__rust_alloc_zeroed` uses `calloc(1, size)` instead.
+    fn codegen_default_alloc(
+        &mut self,
+        name: &str,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let size = fargs.remove(0);
+        let alloc = if name == "__rust_alloc_zeroed" {
+            BuiltinFn::Calloc.call(vec![Type::size_t().one(), size.clone()], loc.clone())
+        } else {
+            BuiltinFn::Malloc.call(vec![size.clone()], loc.clone())
+        };
+        let ptr_typ = Type::unsigned_int(8).to_pointer();
+        let place = self.codegen_place(p).goto_expr;
+        let success = Stmt::block(
+            vec![
+                place.clone().assign(alloc.cast_to(ptr_typ.clone()), loc.clone()),
+                self.codegen_heap_size_update(Some(size.clone()), None, loc.clone()),
+            ],
+            loc.clone(),
+        );
+        match self.codegen_alloc_fails(size, None) {
+            Some(fails) => {
+                fails.if_then_else(place.assign(ptr_typ.null(), loc.clone()), Some(success), loc)
+            }
+            None => success,
+        }
+    }
+
+    /// `__rust_realloc(ptr, old_size, align, new_size)` is modeled as:
+    /// ```c
+    /// if (fails(new_size)) {
+    ///     p = NULL;
+    /// } else {
+    ///     p = realloc(ptr, new_size);
+    ///     __rmc_heap_size = __rmc_heap_size - old_size + new_size;
+    /// }
+    /// ```
+    /// Like `realloc`, a failed reallocation leaves the original block untouched.
+    fn codegen_default_realloc(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let ptr = fargs.remove(0).cast_to(Type::void_pointer());
+        let old_size = fargs.remove(0);
+        fargs.remove(0); // align
+        let new_size = fargs.remove(0);
+        let ptr_typ = Type::unsigned_int(8).to_pointer();
+        let place = self.codegen_place(p).goto_expr;
+        let realloc = BuiltinFn::Realloc.call(vec![ptr, new_size.clone()], loc.clone());
+        let success = Stmt::block(
+            vec![
+                place.clone().assign(realloc.cast_to(ptr_typ.clone()), loc.clone()),
+                self.codegen_heap_size_update(
+                    Some(new_size.clone()),
+                    Some(old_size.clone()),
+                    loc.clone(),
+                ),
+            ],
+            loc.clone(),
+        );
+        match self.codegen_alloc_fails(new_size, Some(old_size)) {
+            Some(fails) => {
+                fails.if_then_else(place.assign(ptr_typ.null(), loc.clone()), Some(success), loc)
+            }
+            None => success,
+        }
+    }
+
+    /// `__rust_dealloc(ptr, size, align)` is modeled as:
+    /// ```c
+    /// free(ptr); __rmc_heap_size -= size;
+    /// ```
+    fn codegen_default_dealloc(&mut self, mut fargs: Vec<Expr>, loc: Location) -> Stmt {
+        let ptr = fargs.remove(0).cast_to(Type::void_pointer());
+        let size = fargs.remove(0);
+        Stmt::block(
+            vec![
+                BuiltinFn::Free.call(vec![ptr], loc.clone()).as_stmt(loc.clone()),
+                self.codegen_heap_size_update(None, Some(size), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
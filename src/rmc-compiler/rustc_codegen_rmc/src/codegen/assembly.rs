@@ -0,0 +1,191 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module models inline assembly, whose semantics RMC cannot verify.
+//!
+//! By default, executing inline assembly is reported as an unsupported construct. With
+//! `--havoc-inline-asm`, an assembly block instead assigns arbitrary valid values to its output
+//! operands, and leaves its inputs and the rest of the memory untouched.
+//!
+//! Users can also supply a model for the assembly of a given function with
+//! `--inline-asm-model FUNCTION=MODEL`. Each assembly block in `FUNCTION` is then replaced by a
+//! call to the (`#[no_mangle]`) function `MODEL`, which takes the value of each input operand
+//! followed by a mutable reference to each output operand, in the order of their declaration.
+//! For instance, a model for `asm!("rdtsc", out("eax") lo, out("edx") hi)` could be:
+//! ```rust
+//! #[no_mangle]
+//! fn rdtsc_model(lo: &mut u32, hi: &mut u32) {
+//!     *lo = rmc::any();
+//!     *hi = 0;
+//! }
+//! ```
+//!
+//! Either way, we record a warning for each modeled assembly block in the crate metadata.
+
+use crate::context::metadata::InlineAsmWarning;
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Symbol, Type};
+use rmc_queries::UserInput;
+use rustc_middle::mir::{BasicBlock, InlineAsmOperand, LlvmInlineAsm, Place};
+use rustc_middle::ty::layout::LayoutOf;
+use rustc_middle::ty::Ty;
+use rustc_target::abi::{Abi, WrappingRange};
+use tracing::warn;
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Generates the `InlineAsm` terminator, i.e. an `asm!` block.
+    pub fn codegen_inline_asm(
+        &mut self,
+        operands: &[InlineAsmOperand<'tcx>],
+        destination: Option<BasicBlock>,
+        loc: Location,
+    ) -> Stmt {
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        for operand in operands {
+            match operand {
+                InlineAsmOperand::In { value, .. } => inputs.push(self.codegen_operand(value)),
+                InlineAsmOperand::Out { place, .. } => outputs.extend(place.iter()),
+                InlineAsmOperand::InOut { in_value, out_place, .. } => {
+                    inputs.push(self.codegen_operand(in_value));
+                    outputs.extend(out_place.iter());
+                }
+                InlineAsmOperand::Const { .. }
+                | InlineAsmOperand::SymFn { .. }
+                | InlineAsmOperand::SymStatic { .. } => {}
+            }
+        }
+        let asm = self.codegen_asm_block(inputs, &outputs, loc);
+        let next = match destination {
+            Some(target) => Stmt::goto(self.current_fn().find_label(&target), loc),
+            // The assembly is `noreturn`, so the execution cannot continue after it.
+            None => Stmt::assume(Expr::bool_false(), loc),
+        };
+        Stmt::block(vec![asm, next], loc)
+    }
+
+    /// Generates the `LlvmInlineAsm` statement, i.e. an `llvm_asm!` block.
+    pub fn codegen_llvm_inline_asm(&mut self, asm: &LlvmInlineAsm<'tcx>, loc: Location) -> Stmt {
+        let inputs = asm.inputs.iter().map(|(_, value)| self.codegen_operand(value)).collect();
+        let outputs: Vec<_> = asm.outputs.iter().collect();
+        self.codegen_asm_block(inputs, &outputs, loc)
+    }
+
+    fn codegen_asm_block(
+        &mut self,
+        inputs: Vec<Expr>,
+        outputs: &[&Place<'tcx>],
+        loc: Location,
+    ) -> Stmt {
+        let function = self.current_fn().readable_name().to_string();
+        let model = self
+            .queries
+            .get_inline_asm_models()
+            .into_iter()
+            .find(|(f, _)| *f == function)
+            .map(|(_, model)| model);
+        if model.is_none() && !self.queries.get_havoc_inline_asm() {
+            return self
+                .codegen_unimplemented(
+                    "InlineAsm",
+                    Type::empty(),
+                    loc,
+                    "https://github.com/model-checking/rmc/issues/2",
+                )
+                .as_stmt(loc);
+        }
+
+        warn!(
+            "Inline assembly in {} at {} is not verified, it is modeled by {}",
+            function,
+            loc.short_string(),
+            model.as_deref().unwrap_or("havocking its outputs")
+        );
+        self.inline_asm_warnings.push(InlineAsmWarning {
+            function: function.clone(),
+            original_file: loc.filename().unwrap_or_default(),
+            original_line: loc.line().map_or(String::new(), |line| line.to_string()),
+            model: model.clone(),
+        });
+
+        match model {
+            Some(model) => self.codegen_asm_model_call(&function, &model, inputs, outputs, loc),
+            None => {
+                let havocs = outputs.iter().map(|place| self.codegen_havoc(place, loc)).collect();
+                Stmt::block(havocs, loc)
+            }
+        }
+    }
+
+    /// `MODEL(inputs..., &mut outputs...)`
+    fn codegen_asm_model_call(
+        &mut self,
+        function: &str,
+        model: &str,
+        inputs: Vec<Expr>,
+        outputs: &[&Place<'tcx>],
+        loc: Location,
+    ) -> Stmt {
+        let mut args = inputs;
+        args.extend(outputs.iter().map(|place| self.codegen_place(place).goto_expr.address_of()));
+        let param_types = args.iter().map(|arg| arg.typ().clone()).collect();
+        let return_type = self.codegen_ty(self.tcx.mk_unit());
+        // The model may be defined in another crate, so we declare it here.
+        let func = self
+            .ensure(model, |_, name| {
+                Symbol::function(
+                    name,
+                    Type::code_with_unnamed_parameters(param_types, return_type),
+                    None,
+                    None::<String>,
+                    Location::none(),
+                )
+                .with_is_extern(true)
+            })
+            .to_expr();
+        if !Expr::typecheck_call(&func, &args) {
+            self.tcx.sess.fatal(&format!(
+                "The inline assembly model {} for {} must take the value of each input operand, \
+                followed by a mutable reference to each output operand",
+                model, function
+            ));
+        }
+        func.call(args).as_stmt(loc)
+    }
+
+    /// Assigns an arbitrary valid value to the place.
+    fn codegen_havoc(&mut self, place: &Place<'tcx>, loc: Location) -> Stmt {
+        let ty = self.place_ty(place);
+        let place = self.codegen_place(place).goto_expr;
+        let mut stmts = vec![place.clone().assign(place.typ().nondet(), loc)];
+        if let Some(is_valid) = self.codegen_scalar_validity(place, ty) {
+            stmts.push(Stmt::assume(is_valid, loc));
+        }
+        Stmt::block(stmts, loc)
+    }
+
+    /// Whether the scalar `e` of type `ty` is in the valid range of its type, for the types whose
+    /// valid range does not include every bit pattern (e.g. `bool`, `char` and function pointers).
+    fn codegen_scalar_validity(&self, e: Expr, ty: Ty<'tcx>) -> Option<Expr> {
+        match self.layout_of(ty).abi {
+            Abi::Scalar(scalar) if !scalar.is_always_valid(self) => {
+                let WrappingRange { start, end } = scalar.valid_range;
+                if e.typ().is_pointer() {
+                    // The only restriction on the value of a pointer is that it is not null.
+                    return Some(e.is_nonnull());
+                }
+                let typ = Type::unsigned_int(e.typ().sizeof_in_bits(&self.symbol_table));
+                let value = e.cast_to(typ.clone());
+                let above_start = value.clone().ge(Expr::int_constant(start, typ.clone()));
+                let below_end = value.le(Expr::int_constant(end, typ));
+                // The valid range wraps around if `start > end`.
+                if start <= end {
+                    Some(above_start.and(below_end))
+                } else {
+                    Some(above_start.or(below_end))
+                }
+            }
+            _ => None,
+        }
+    }
+}
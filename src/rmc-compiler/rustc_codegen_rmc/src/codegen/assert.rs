@@ -0,0 +1,77 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module generates classified assertions, i.e. assertions whose source location carries
+//! a property class. CBMC uses the class to name and group the resulting properties
+//! (e.g. `main.undefined-shift.1` instead of `main.assertion.1`), which lets users and tools
+//! tell apart the different kinds of undefined behavior RMC checks for.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt};
+
+/// The kinds of properties RMC generates checks for.
+/// Where CBMC has an equivalent check, we reuse its class name so results are grouped together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyClass {
+    /// Arithmetic operations that must not overflow (e.g. `unchecked_add`).
+    ArithmeticOverflow,
+    /// The condition passed to `core::intrinsics::assume` must hold.
+    Assume,
+    /// Divisions and remainders must have a nonzero divisor.
+    DivisionByZero,
+    /// Memory copies whose source and destination regions must not overlap.
+    MemoryOverlap,
+    /// Heap allocations that must be freed before a leak-checked harness returns.
+    MemoryLeak,
+    /// Pointer arithmetic that must stay within the bounds of the pointed-to object.
+    PointerOffset,
+    /// Shifts whose amount must be smaller than the bit width of the shifted value.
+    UndefinedShift,
+}
+
+impl PropertyClass {
+    pub fn as_str(&self) -> &str {
+        match self {
+            PropertyClass::ArithmeticOverflow => "overflow",
+            PropertyClass::Assume => "assume",
+            PropertyClass::DivisionByZero => "division-by-zero",
+            PropertyClass::MemoryLeak => "memory-leak",
+            PropertyClass::MemoryOverlap => "memory-overlap",
+            PropertyClass::PointerOffset => "pointer-offset",
+            PropertyClass::UndefinedShift => "undefined-shift",
+        }
+    }
+}
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// `__CPROVER_assert(cond, msg)` where the location records `property_class`.
+    pub fn codegen_assert(
+        &self,
+        cond: Expr,
+        property_class: PropertyClass,
+        msg: &str,
+        loc: Location,
+    ) -> Stmt {
+        let property_loc =
+            Location::create_location_with_property(msg, property_class.as_str(), loc);
+        Stmt::assert(cond, msg, property_loc)
+    }
+
+    /// Assert `cond` and then assume it, so that a failed check does not lead to spurious
+    /// failures further down the same path.
+    pub fn codegen_assert_assume(
+        &self,
+        cond: Expr,
+        property_class: PropertyClass,
+        msg: &str,
+        loc: Location,
+    ) -> Stmt {
+        Stmt::block(
+            vec![
+                self.codegen_assert(cond.clone(), property_class, msg, loc),
+                Stmt::assume(cond, loc),
+            ],
+            loc,
+        )
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module restricts the targets of calls through plain function pointers with
+//! `--restrict-fn-ptrs`, like `VtableCtx` restricts the calls through vtables. Without
+//! restrictions, CBMC considers every function of a compatible type as a possible target.
+//!
+//! We use the same restrictions as for vtables, so `rmc-link-restrictions` links them across
+//! crates:
+//!  - Every function whose address is taken in Rust code, e.g. by coercing a function item or
+//!    a closure to a function pointer, is a possible method of its goto type, e.g.
+//!    `int32_t (int32_t)` with index 0.
+//!  - A call through a local of the caller that is only assigned functions whose address is
+//!    taken, directly or through other such locals, may only call these functions. E.g. in
+//!    `let f: fn() = if c { a } else { b }; f()`, `f` is either `a` or `b`. Its possible
+//!    methods are keyed by the name of the call's wrapper.
+//!  - Any other call, e.g. through a field, a parameter or a local that is borrowed, may call
+//!    any function of its type whose address is taken.
+//!
+//! Function pointers that do not come from Rust code, e.g. from C code or from transmuting
+//! an integer or a function pointer of another type, are not tracked. A call through such a
+//! pointer fails the check that CBMC adds for the restriction.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, ExprValue};
+use cbmc::{InternString, InternedString};
+use rustc_middle::mir::visit::{NonMutatingUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::{Body, CastKind, Local, Location, Operand, Place, Rvalue};
+use rustc_middle::ty::adjustment::PointerCast;
+use rustc_middle::ty::{self, Instance};
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Records that the address of the function `func` is taken, so that calls through
+    /// function pointers of its type may call it.
+    pub fn record_fn_address_taken(&mut self, func: &Expr) {
+        if self.vtable_ctx.emit_fn_ptr_restrictions {
+            if let ExprValue::Symbol { identifier } = func.value() {
+                let key = func.typ().to_string().into();
+                self.vtable_ctx.add_possible_method(key, 0, *identifier);
+            }
+        }
+    }
+
+    /// Calls the function pointer `fn_ptr`, the value of `func`, through a wrapper whose call
+    /// is restricted to the functions that `func` may point to.
+    pub fn codegen_restricted_fn_ptr_call(
+        &mut self,
+        func: &Operand<'tcx>,
+        fn_ptr: Expr,
+        args: Vec<Expr>,
+    ) -> Expr {
+        assert!(self.vtable_ctx.emit_fn_ptr_restrictions);
+        let wrapper_name = self.restricted_call_wrapper_name();
+        let key = match self.local_fn_ptr_targets(func) {
+            Some(targets) => {
+                for target in targets {
+                    self.vtable_ctx.add_possible_method(wrapper_name, 0, target);
+                }
+                wrapper_name
+            }
+            None => fn_ptr.typ().base_type().unwrap().to_string().into(),
+        };
+        self.call_with_restricted_fn_ptr(wrapper_name, key, 0, fn_ptr, args)
+    }
+
+    /// The functions that `func` may point to, if it is a local that is only assigned
+    /// functions whose address is taken, directly or through other such locals. The names of
+    /// the functions are those that `codegen_pointer_cast` gives them.
+    fn local_fn_ptr_targets(&self, func: &Operand<'tcx>) -> Option<Vec<InternedString>> {
+        let mir = self.current_fn().mir();
+        let mut worklist = vec![func.place()?.as_local()?];
+        let mut visited = vec![];
+        let mut targets = vec![];
+        while let Some(local) = worklist.pop() {
+            if visited.contains(&local) {
+                continue;
+            }
+            visited.push(local);
+            for rvalue in local_assignments(mir, local)? {
+                match rvalue {
+                    Rvalue::Cast(CastKind::Pointer(PointerCast::ReifyFnPointer), operand, _) => {
+                        match self.operand_ty(&operand).kind() {
+                            ty::FnDef(def_id, substs) => {
+                                let instance = Instance::resolve_for_fn_ptr(
+                                    self.tcx,
+                                    ty::ParamEnv::reveal_all(),
+                                    *def_id,
+                                    substs,
+                                )?;
+                                targets.push(self.symbol_name(instance).intern())
+                            }
+                            _ => return None,
+                        }
+                    }
+                    Rvalue::Cast(
+                        CastKind::Pointer(PointerCast::ClosureFnPointer(_)),
+                        operand,
+                        _,
+                    ) => match self.operand_ty(&operand).kind() {
+                        ty::Closure(def_id, substs) => {
+                            let instance = Instance::resolve_closure(
+                                self.tcx,
+                                *def_id,
+                                substs,
+                                ty::ClosureKind::FnOnce,
+                            );
+                            targets.push(self.closure_fn_ptr_shim_name(instance).intern())
+                        }
+                        _ => return None,
+                    },
+                    Rvalue::Cast(CastKind::Pointer(PointerCast::UnsafeFnPointer), operand, _)
+                    | Rvalue::Use(operand) => worklist.push(operand.place()?.as_local()?),
+                    _ => return None,
+                }
+            }
+        }
+        Some(targets)
+    }
+}
+
+/// The values assigned to `local` in `mir`, unless `local` may also change in other ways,
+/// e.g. as a parameter, as the destination of a call, or through a reference.
+fn local_assignments<'tcx>(mir: &Body<'tcx>, local: Local) -> Option<Vec<Rvalue<'tcx>>> {
+    if mir.args_iter().any(|arg| arg == local) {
+        return None;
+    }
+    let mut visitor = LocalAssignments { local, rvalues: vec![], escapes: false };
+    visitor.visit_body(mir);
+    if visitor.escapes { None } else { Some(visitor.rvalues) }
+}
+
+struct LocalAssignments<'tcx> {
+    local: Local,
+    rvalues: Vec<Rvalue<'tcx>>,
+    escapes: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalAssignments<'tcx> {
+    fn visit_assign(&mut self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>, location: Location) {
+        if place.as_local() == Some(self.local) {
+            self.rvalues.push(rvalue.clone());
+            self.visit_rvalue(rvalue, location);
+        } else {
+            self.super_assign(place, rvalue, location);
+        }
+    }
+
+    fn visit_local(&mut self, local: &Local, context: PlaceContext, _location: Location) {
+        // The assignments to the local itself do not get here, see `visit_assign`
+        if *local == self.local
+            && (context.is_mutating_use()
+                || context.is_borrow()
+                || context == PlaceContext::NonMutatingUse(NonMutatingUseContext::AddressOf))
+        {
+            self.escapes = true;
+        }
+    }
+}
@@ -20,14 +20,11 @@ fn should_skip_current_fn(&self) -> bool {
         match self.current_fn().readable_name() {
             // https://github.com/model-checking/rmc/issues/202
             "fmt::ArgumentV1::<'a>::as_usize" => true,
-            // https://github.com/model-checking/rmc/issues/204
-            name if name.ends_with("__getit") => true,
             // https://github.com/model-checking/rmc/issues/281
             name if name.starts_with("bridge::client") => true,
             // https://github.com/model-checking/rmc/issues/282
             "bridge::closure::Closure::<'a, A, R>::call" => true,
             // Generators
-            name if name.starts_with("<std::future::from_generator::GenFuture<T>") => true,
             name if name.contains("reusable_box::ReusableBoxFuture") => true,
             "tokio::sync::Semaphore::acquire_owned::{closure#0}" => true,
             _ => false,
@@ -96,6 +93,7 @@ pub fn codegen_function(&mut self, instance: Instance<'tcx>) {
             self.current_fn_mut().set_labels(labels);
             self.codegen_function_prelude();
             self.codegen_declare_variables();
+            self.codegen_declare_caller_location_param();
 
             mir.basic_blocks().iter_enumerated().for_each(|(bb, bbd)| self.codegen_block(bb, bbd));
 
@@ -104,7 +102,9 @@ pub fn codegen_function(&mut self, instance: Instance<'tcx>) {
             let body = Stmt::block(stmts, loc);
             self.symbol_table.update_fn_declaration_with_definition(&name, body);
 
+            self.record_loops();
             self.handle_rmctool_attributes();
+            self.dump_goto(mir);
         }
         self.reset_current_fn();
     }
@@ -277,7 +277,7 @@ fn handle_rmctool_proof(&mut self) {
 }
 
 /// If the attribute is named `rmctool::name`, this extracts `name`
-fn rmctool_attr_name(attr: &ast::Attribute) -> Option<String> {
+pub fn rmctool_attr_name(attr: &ast::Attribute) -> Option<String> {
     match &attr.kind {
         ast::AttrKind::Normal(ast::AttrItem { path: ast::Path { segments, .. }, .. }, _)
             if segments.len() == 2 && segments[0].ident.as_str() == "rmctool" =>
@@ -0,0 +1,98 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module models the hashing of `std::collections::HashMap` and `HashSet` with a cheap hash
+//! function, when RMC runs with `--simple-hashing`.
+//!
+//! By default, `RandomState::new` reads random keys from the OS, and `DefaultHasher` computes the
+//! SipHash-1-3 of the hashed bytes, both of which are very expensive to verify. Instead, a new
+//! `RandomState` gets arbitrary keys, and `DefaultHasher` rotates and XORs each hashed byte into
+//! the `k0` field of its SipHash state, which `RandomState::build_hasher` initializes with the
+//! first key. Hashes are thus arbitrary, but a map hashes equal keys to the same value, so the
+//! semantics of the map are unchanged.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use rustc_middle::mir::{BasicBlock, Place};
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// `hasher->0.hasher.k0`, where `hasher` is a `&DefaultHasher`.
+    fn default_hasher_state(&self, hasher: Expr) -> Expr {
+        // `DefaultHasher(SipHasher13 { hasher: Hasher<Sip13Rounds> { k0, k1, .. } })`
+        hasher
+            .dereference()
+            .member("0", &self.symbol_table)
+            .member("hasher", &self.symbol_table)
+            .member("k0", &self.symbol_table)
+    }
+
+    /// `RandomState::new()` returns a `RandomState` with arbitrary keys.
+    pub fn codegen_random_state_new(
+        &mut self,
+        p: &Place<'tcx>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let state = self.codegen_place(p).goto_expr;
+        let keys = state.typ().nondet();
+        Stmt::block(
+            vec![state.assign(keys, loc), Stmt::goto(self.current_fn().find_label(&target), loc)],
+            loc,
+        )
+    }
+
+    /// `<DefaultHasher as Hasher>::write(hasher, msg)` is modeled as:
+    /// ```c
+    /// for (size_t i = 0; i < msg.len; i++) {
+    ///     hasher->0.hasher.k0 = (hasher->0.hasher.k0 <<< 8) ^ msg.data[i];
+    /// }
+    /// ```
+    /// This is the identity on the bytes of integers with a zero key.
+    pub fn codegen_default_hasher_write(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let state = self.default_hasher_state(fargs.remove(0));
+        let msg = fargs.remove(0);
+        let data = msg.clone().member("data", &self.symbol_table);
+        let len = msg.member("len", &self.symbol_table);
+        let idx = self.gen_temp_variable(Type::size_t(), loc).to_expr();
+        let state_typ = state.typ().clone();
+        let byte = data.index(idx.clone()).cast_to(state_typ.clone());
+        let next = state.clone().rol(Expr::int_constant(8, state_typ)).bitxor(byte);
+        Stmt::block(
+            vec![
+                Stmt::decl(idx.clone(), Some(Type::size_t().zero()), loc),
+                Stmt::for_loop(
+                    Stmt::skip(loc),
+                    idx.clone().lt(len),
+                    idx.postincr().as_stmt(loc),
+                    state.assign(next, loc),
+                    loc,
+                ),
+                Stmt::goto(self.current_fn().find_label(&target), loc),
+            ],
+            loc,
+        )
+    }
+
+    /// `<DefaultHasher as Hasher>::finish(hasher)` returns `hasher->0.hasher.k0`.
+    pub fn codegen_default_hasher_finish(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let state = self.default_hasher_state(fargs.remove(0));
+        Stmt::block(
+            vec![
+                self.codegen_expr_to_place(p, state),
+                Stmt::goto(self.current_fn().find_label(&target), loc),
+            ],
+            loc,
+        )
+    }
+}
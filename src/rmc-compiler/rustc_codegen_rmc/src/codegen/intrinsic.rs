@@ -1,8 +1,9 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //! this module handles intrinsics
-use tracing::{debug, warn};
+use tracing::debug;
 
+use super::PropertyClass;
 use crate::GotocCtx;
 use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Type};
 use rustc_middle::mir::Place;
@@ -73,6 +74,7 @@ pub fn codegen_intrinsic(
         /// https://doc.rust-lang.org/core/intrinsics/fn.copy.html
         /// https://doc.rust-lang.org/core/intrinsics/fn.copy_nonoverlapping.html
         /// An intrinsic that translates directly into either memmove (for copy) or memcpy (copy_nonoverlapping)
+        /// For memcpy, we also check that the source and destination regions do not overlap.
         macro_rules! codegen_intrinsic_copy {
             ($f:ident) => {{
                 let src = fargs.remove(0).cast_to(Type::void_pointer());
@@ -90,6 +92,17 @@ macro_rules! codegen_intrinsic_copy {
                     }
                 };
                 let n = sz.mul(count);
+                let nonoverlapping_check = if matches!(BuiltinFn::$f, BuiltinFn::Memcpy) {
+                    Some(self.codegen_nonoverlapping_check(
+                        src.clone(),
+                        dst.clone(),
+                        n.clone(),
+                        intrinsic,
+                        loc,
+                    ))
+                } else {
+                    None
+                };
                 let call_memcopy = BuiltinFn::$f.call(vec![dst.clone(), src, n.clone()], loc);
 
                 // The C implementation of memcpy does not allow an invalid pointer for
@@ -99,7 +112,11 @@ macro_rules! codegen_intrinsic_copy {
                 // string unless we codegen this zero check.
                 // https://llvm.org/docs/LangRef.html#llvm-memcpy-intrinsic
                 let copy_if_nontrivial = n.is_zero().ternary(dst, call_memcopy);
-                self.codegen_expr_to_place(p, copy_if_nontrivial)
+                let copy_stmt = self.codegen_expr_to_place(p, copy_if_nontrivial);
+                match nonoverlapping_check {
+                    Some(check) => Stmt::block(vec![check, copy_stmt], loc),
+                    None => copy_stmt,
+                }
             }};
         }
 
@@ -155,8 +172,9 @@ macro_rules! codegen_op_with_overflow_check {
                 let a = fargs.remove(0);
                 let b = fargs.remove(0);
                 let res = a.$f(b);
-                let check = Stmt::assert(
+                let check = self.codegen_assert(
                     res.overflowed.not(),
+                    PropertyClass::ArithmeticOverflow,
                     format!("attempt to compute {} which would overflow", intrinsic).as_str(),
                     loc,
                 );
@@ -264,8 +282,9 @@ macro_rules! codegen_unimplemented_intrinsic {
         // Most atomic intrinsics do:
         //   1. Perform an operation on a primary argument (e.g., addition)
         //   2. Return the previous value of the primary argument
-        // The primary argument is always passed by reference. In a sequential
-        // context, atomic orderings can be ignored.
+        // The primary argument is always passed by reference. The operation is
+        // performed in an atomic block, so other threads cannot interleave with it.
+        // We assume sequential consistency, so atomic orderings can be ignored.
         //
         // Atomic binops are transformed as follows:
         // -------------------------
@@ -279,7 +298,6 @@ macro_rules! codegen_unimplemented_intrinsic {
         // Note: Atomic arithmetic operations wrap around on overflow.
         macro_rules! codegen_atomic_binop {
             ($op: ident) => {{
-                warn!("RMC does not support concurrency for now. {} treated as a sequential operation.", intrinsic);
                 let loc = self.codegen_span_option(span);
                 let var1_ref = fargs.remove(0);
                 let var1 = var1_ref.dereference();
@@ -300,6 +318,8 @@ macro_rules! codegen_atomic_binop {
 
         match intrinsic {
             "add_with_overflow" => codegen_op_with_overflow!(add_overflow),
+            // `arith_offset` is the intrinsic behind `wrapping_offset`: computing an out-of-bounds
+            // pointer is allowed, only dereferencing it is UB, so there is nothing to check here.
             "arith_offset" => codegen_wrapping_op!(plus),
             "assert_inhabited" => {
                 let ty = instance.substs.type_at(0);
@@ -326,44 +346,45 @@ macro_rules! codegen_atomic_binop {
             // https://doc.rust-lang.org/core/intrinsics/fn.assume.html
             // Informs the optimizer that a condition is always true.
             // If the condition is false, the behavior is undefined.
-            "assume" => {
-                Stmt::assert(fargs.remove(0).cast_to(Type::bool()), "assumption failed", loc)
-            }
+            "assume" => self.codegen_assert_assume(
+                fargs.remove(0).cast_to(Type::bool()),
+                PropertyClass::Assume,
+                "assumption failed",
+                loc,
+            ),
             "atomic_and" => codegen_atomic_binop!(bitand),
             "atomic_and_acq" => codegen_atomic_binop!(bitand),
             "atomic_and_acqrel" => codegen_atomic_binop!(bitand),
             "atomic_and_rel" => codegen_atomic_binop!(bitand),
             "atomic_and_relaxed" => codegen_atomic_binop!(bitand),
-            name if name.starts_with("atomic_cxchg") => {
-                self.codegen_atomic_cxchg(intrinsic, fargs, p, loc)
-            }
-            "atomic_fence" => self.codegen_atomic_noop(intrinsic, loc),
-            "atomic_fence_acq" => self.codegen_atomic_noop(intrinsic, loc),
-            "atomic_fence_acqrel" => self.codegen_atomic_noop(intrinsic, loc),
-            "atomic_fence_rel" => self.codegen_atomic_noop(intrinsic, loc),
-            "atomic_load" => self.codegen_atomic_load(intrinsic, fargs, p, loc),
-            "atomic_load_acq" => self.codegen_atomic_load(intrinsic, fargs, p, loc),
-            "atomic_load_relaxed" => self.codegen_atomic_load(intrinsic, fargs, p, loc),
-            "atomic_load_unordered" => self.codegen_atomic_load(intrinsic, fargs, p, loc),
+            name if name.starts_with("atomic_cxchg") => self.codegen_atomic_cxchg(fargs, p, loc),
+            "atomic_fence" => self.codegen_atomic_noop(loc),
+            "atomic_fence_acq" => self.codegen_atomic_noop(loc),
+            "atomic_fence_acqrel" => self.codegen_atomic_noop(loc),
+            "atomic_fence_rel" => self.codegen_atomic_noop(loc),
+            "atomic_load" => self.codegen_atomic_load(fargs, p, loc),
+            "atomic_load_acq" => self.codegen_atomic_load(fargs, p, loc),
+            "atomic_load_relaxed" => self.codegen_atomic_load(fargs, p, loc),
+            "atomic_load_unordered" => self.codegen_atomic_load(fargs, p, loc),
             "atomic_or" => codegen_atomic_binop!(bitor),
             "atomic_or_acq" => codegen_atomic_binop!(bitor),
             "atomic_or_acqrel" => codegen_atomic_binop!(bitor),
             "atomic_or_rel" => codegen_atomic_binop!(bitor),
             "atomic_or_relaxed" => codegen_atomic_binop!(bitor),
-            "atomic_store" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_store_rel" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_store_relaxed" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_store_unordered" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
+            "atomic_store" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_store_rel" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_store_relaxed" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_store_unordered" => self.codegen_atomic_store(fargs, p, loc),
             "atomic_xadd" => codegen_atomic_binop!(plus),
             "atomic_xadd_acq" => codegen_atomic_binop!(plus),
             "atomic_xadd_acqrel" => codegen_atomic_binop!(plus),
             "atomic_xadd_rel" => codegen_atomic_binop!(plus),
             "atomic_xadd_relaxed" => codegen_atomic_binop!(plus),
-            "atomic_xchg" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_xchg_acq" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_xchg_acqrel" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_xchg_rel" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
-            "atomic_xchg_relaxed" => self.codegen_atomic_store(intrinsic, fargs, p, loc),
+            "atomic_xchg" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_xchg_acq" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_xchg_acqrel" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_xchg_rel" => self.codegen_atomic_store(fargs, p, loc),
+            "atomic_xchg_relaxed" => self.codegen_atomic_store(fargs, p, loc),
             "atomic_xor" => codegen_atomic_binop!(bitxor),
             "atomic_xor_acq" => codegen_atomic_binop!(bitxor),
             "atomic_xor_acqrel" => codegen_atomic_binop!(bitxor),
@@ -380,7 +401,8 @@ macro_rules! codegen_atomic_binop {
             "breakpoint" => Stmt::skip(loc),
             "bswap" => self.codegen_expr_to_place(p, fargs.remove(0).bswap()),
             "caller_location" => {
-                codegen_unimplemented_intrinsic!("https://github.com/model-checking/rmc/issues/374")
+                let caller_location = self.codegen_caller_location();
+                self.codegen_expr_to_place(p, caller_location)
             }
             "ceilf32" => codegen_simple_intrinsic!(Ceilf),
             "ceilf64" => codegen_simple_intrinsic!(Ceil),
@@ -429,7 +451,7 @@ macro_rules! codegen_atomic_binop {
             "nearbyintf32" => codegen_simple_intrinsic!(Nearbyintf),
             "nearbyintf64" => codegen_simple_intrinsic!(Nearbyint),
             "needs_drop" => codegen_intrinsic_const!(),
-            "offset" => codegen_op_with_overflow_check!(add_overflow),
+            "offset" => self.codegen_offset(intrinsic, instance, fargs, p, loc),
             "powf32" => codegen_simple_intrinsic!(Powf),
             "powf64" => codegen_simple_intrinsic!(Pow),
             "powif32" => codegen_simple_intrinsic!(Powif),
@@ -487,26 +509,18 @@ macro_rules! codegen_atomic_binop {
             "transmute" => self.codegen_intrinsic_transmute(fargs, ret_ty, p),
             "truncf32" => codegen_simple_intrinsic!(Truncf),
             "truncf64" => codegen_simple_intrinsic!(Trunc),
-            "try" => {
-                codegen_unimplemented_intrinsic!("https://github.com/model-checking/rmc/issues/267")
-            }
+            "try" => self.codegen_try(fargs, p, cbmc_ret_ty, loc),
             "type_id" => codegen_intrinsic_const!(),
             "type_name" => codegen_intrinsic_const!(),
             "unaligned_volatile_load" => {
                 self.codegen_expr_to_place(p, fargs.remove(0).dereference())
             }
             "unchecked_add" => codegen_op_with_overflow_check!(add_overflow),
-            "unchecked_div" => codegen_intrinsic_binop!(div),
+            "unchecked_div" => self.codegen_unchecked_div_rem(intrinsic, fargs, p, loc),
             "unchecked_mul" => codegen_op_with_overflow_check!(mul_overflow),
-            "unchecked_rem" => codegen_intrinsic_binop!(rem),
-            "unchecked_shl" => codegen_intrinsic_binop!(shl),
-            "unchecked_shr" => {
-                if fargs[0].typ().is_signed(self.symbol_table.machine_model()) {
-                    codegen_intrinsic_binop!(ashr)
-                } else {
-                    codegen_intrinsic_binop!(lshr)
-                }
-            }
+            "unchecked_rem" => self.codegen_unchecked_div_rem(intrinsic, fargs, p, loc),
+            "unchecked_shl" => self.codegen_unchecked_shift(intrinsic, fargs, p, loc),
+            "unchecked_shr" => self.codegen_unchecked_shift(intrinsic, fargs, p, loc),
             "unchecked_sub" => codegen_op_with_overflow_check!(sub_overflow),
             "unlikely" => self.codegen_expr_to_place(p, fargs.remove(0)),
             "unreachable" => Stmt::assert_false("unreachable", loc),
@@ -567,6 +581,161 @@ fn codegen_exact_div(&mut self, mut fargs: Vec<Expr>, p: &Place<'tcx>, loc: Loca
         )
     }
 
+    /// Checks for the undefined behavior conditions of `unchecked_div` and `unchecked_rem`:
+    /// the divisor must be nonzero and, for signed types, `MIN / -1` must not overflow.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.unchecked_div.html
+    /// https://doc.rust-lang.org/std/intrinsics/fn.unchecked_rem.html
+    fn codegen_unchecked_div_rem(
+        &mut self,
+        intrinsic: &str,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let mm = self.symbol_table.machine_model();
+        let a = fargs.remove(0);
+        let b = fargs.remove(0);
+        let atyp = a.typ();
+        let btyp = b.typ();
+        let divisor_is_nonzero = b.clone().neq(btyp.zero());
+        let dividend_is_int_min = if atyp.is_signed(mm) {
+            a.clone().eq(atyp.min_int_expr(mm))
+        } else {
+            Expr::bool_false()
+        };
+        let divisor_is_minus_one =
+            if btyp.is_signed(mm) { b.clone().eq(btyp.one().neg()) } else { Expr::bool_false() };
+        let division_does_not_overflow = dividend_is_int_min.and(divisor_is_minus_one).not();
+        let res = if intrinsic == "unchecked_div" { a.div(b) } else { a.rem(b) };
+        Stmt::block(
+            vec![
+                self.codegen_assert(
+                    divisor_is_nonzero,
+                    PropertyClass::DivisionByZero,
+                    &format!("{}: attempt to divide by zero", intrinsic),
+                    loc,
+                ),
+                self.codegen_assert(
+                    division_does_not_overflow,
+                    PropertyClass::ArithmeticOverflow,
+                    &format!("{}: attempt to compute MIN / -1 which would overflow", intrinsic),
+                    loc,
+                ),
+                self.codegen_expr_to_place(p, res),
+            ],
+            loc,
+        )
+    }
+
+    /// `unchecked_shl` and `unchecked_shr` are UB if the shift amount is negative or not
+    /// smaller than the bit width of the shifted value.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.unchecked_shl.html
+    /// https://doc.rust-lang.org/std/intrinsics/fn.unchecked_shr.html
+    fn codegen_unchecked_shift(
+        &mut self,
+        intrinsic: &str,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let mm = self.symbol_table.machine_model();
+        let a = fargs.remove(0);
+        let b = fargs.remove(0);
+        let width = a.typ().native_width(mm).unwrap();
+        let btyp = b.typ().clone();
+        let amount_is_nonnegative =
+            if btyp.is_signed(mm) { b.clone().is_negative().not() } else { Expr::bool_true() };
+        let amount_is_in_bounds = b.clone().lt(Expr::int_constant(width, btyp));
+        let res = if intrinsic == "unchecked_shl" {
+            a.shl(b)
+        } else if a.typ().is_signed(mm) {
+            a.ashr(b)
+        } else {
+            a.lshr(b)
+        };
+        Stmt::block(
+            vec![
+                self.codegen_assert(
+                    amount_is_nonnegative.and(amount_is_in_bounds),
+                    PropertyClass::UndefinedShift,
+                    &format!("{}: attempt to shift by excessive shift distance", intrinsic),
+                    loc,
+                ),
+                self.codegen_expr_to_place(p, res),
+            ],
+            loc,
+        )
+    }
+
+    /// `offset` computes `src + count * size_of::<T>()` and is UB if the result overflows
+    /// an `isize` or lies outside the object `src` points into (one past the end is allowed).
+    /// Offsets by zero and offsets of zero-sized types are always in bounds.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.offset.html
+    fn codegen_offset(
+        &mut self,
+        intrinsic: &str,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let src_ptr = fargs.remove(0);
+        let count = fargs.remove(0);
+        let res = src_ptr.add_overflow(count.clone());
+        let overflow_check = self.codegen_assert(
+            res.overflowed.not(),
+            PropertyClass::ArithmeticOverflow,
+            &format!("attempt to compute {} which would overflow", intrinsic),
+            loc,
+        );
+        let pointee_ty = self.monomorphize(instance.substs.type_at(0));
+        let bounds_check = if self.layout_of(pointee_ty).is_zst() {
+            Stmt::skip(loc)
+        } else {
+            let dst_offset = res.result.clone().pointer_offset();
+            let dst_is_in_bounds = dst_offset
+                .clone()
+                .is_negative()
+                .not()
+                .and(dst_offset.cast_to(Type::size_t()).le(res.result.clone().object_size()));
+            self.codegen_assert(
+                count.is_zero().or(dst_is_in_bounds),
+                PropertyClass::PointerOffset,
+                &format!("{}: attempt to compute a pointer outside of its allocation", intrinsic),
+                loc,
+            )
+        };
+        let expr_place = self.codegen_expr_to_place(p, res.result);
+        Stmt::block(vec![overflow_check, bounds_check, expr_place], loc)
+    }
+
+    /// `copy_nonoverlapping` (and the `CopyNonOverlapping` MIR statement) require that the
+    /// `n` bytes starting at `src` and at `dst` do not overlap.
+    /// `src` and `dst` are expected to be `void*` and `n` a `size_t` byte count.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.copy_nonoverlapping.html
+    pub fn codegen_nonoverlapping_check(
+        &self,
+        src: Expr,
+        dst: Expr,
+        n: Expr,
+        operation: &str,
+        loc: Location,
+    ) -> Stmt {
+        let src_offset = src.clone().pointer_offset().cast_to(Type::size_t());
+        let dst_offset = dst.clone().pointer_offset().cast_to(Type::size_t());
+        let regions_are_disjoint = src
+            .same_object(dst)
+            .not()
+            .or(src_offset.clone().plus(n.clone()).le(dst_offset.clone()))
+            .or(dst_offset.plus(n.clone()).le(src_offset));
+        self.codegen_assert(
+            n.is_zero().or(regions_are_disjoint),
+            PropertyClass::MemoryOverlap,
+            &format!("{}: memory regions must not overlap", operation),
+            loc,
+        )
+    }
+
     /// An atomic load simply returns the value referenced
     /// in its argument (as in other atomic operations)
     /// -------------------------
@@ -576,15 +745,10 @@ fn codegen_exact_div(&mut self, mut fargs: Vec<Expr>, p: &Place<'tcx>, loc: Loca
     /// -------------------------
     fn codegen_atomic_load(
         &mut self,
-        intrinsic: &str,
         mut fargs: Vec<Expr>,
         p: &Place<'tcx>,
         loc: Location,
     ) -> Stmt {
-        warn!(
-            "RMC does not support concurrency for now. {} treated as a sequential operation.",
-            intrinsic
-        );
         let var1_ref = fargs.remove(0);
         let var1 = var1_ref.dereference().with_location(loc.clone());
         let res_stmt = self.codegen_expr_to_place(p, var1);
@@ -595,27 +759,22 @@ fn codegen_atomic_load(
     /// its primary argument and returns a tuple that contains:
     ///  * the previous value
     ///  * a boolean value indicating whether the operation was successful or not
-    /// In a sequential context, the update is always sucessful so we assume the
-    /// second value to be true.
+    /// The weak variants are allowed to fail spuriously, but we model them as the
+    /// strong ones, which is one of their valid behaviors.
     /// -------------------------
     /// var = atomic_cxchg(var1, var2, var3)
     /// -------------------------
     /// unsigned char tmp;
     /// tmp = *var1;
     /// if (*var1 == var2) *var1 = var3;
-    /// var = (tmp, true);
+    /// var = (tmp, tmp == var2);
     /// -------------------------
     fn codegen_atomic_cxchg(
         &mut self,
-        intrinsic: &str,
         mut fargs: Vec<Expr>,
         p: &Place<'tcx>,
         loc: Location,
     ) -> Stmt {
-        warn!(
-            "RMC does not support concurrency for now. {} treated as a sequential operation.",
-            intrinsic
-        );
         let var1_ref = fargs.remove(0);
         let var1 = var1_ref.dereference().with_location(loc.clone());
         let tmp = self.gen_temp_variable(var1.typ().clone(), loc.clone()).to_expr();
@@ -627,8 +786,9 @@ fn codegen_atomic_cxchg(
         let cond_update_stmt = Stmt::if_then_else(eq_expr, assign_stmt, None, loc.clone());
         let place_type = self.place_ty(p);
         let res_type = self.codegen_ty(place_type);
+        let success = tmp.clone().eq(var2).cast_to(Type::c_bool());
         let tuple_expr =
-            Expr::struct_expr_from_values(res_type, vec![tmp, Expr::c_true()], &self.symbol_table)
+            Expr::struct_expr_from_values(res_type, vec![tmp, success], &self.symbol_table)
                 .with_location(loc.clone());
         let res_stmt = self.codegen_expr_to_place(p, tuple_expr);
         Stmt::atomic_block(vec![decl_stmt, cond_update_stmt, res_stmt], loc)
@@ -646,15 +806,10 @@ fn codegen_atomic_cxchg(
     /// -------------------------
     fn codegen_atomic_store(
         &mut self,
-        intrinsic: &str,
         mut fargs: Vec<Expr>,
         p: &Place<'tcx>,
         loc: Location,
     ) -> Stmt {
-        warn!(
-            "RMC does not support concurrency for now. {} treated as a sequential operation.",
-            intrinsic
-        );
         let var1_ref = fargs.remove(0);
         let var1 = var1_ref.dereference().with_location(loc.clone());
         let tmp = self.gen_temp_variable(var1.typ().clone(), loc.clone()).to_expr();
@@ -666,11 +821,7 @@ fn codegen_atomic_store(
     }
 
     /// Atomic no-ops (e.g., atomic_fence) are transformed into SKIP statements
-    fn codegen_atomic_noop(&mut self, intrinsic: &str, loc: Location) -> Stmt {
-        warn!(
-            "RMC does not support concurrency for now. {} treated as a sequential operation.",
-            intrinsic
-        );
+    fn codegen_atomic_noop(&mut self, loc: Location) -> Stmt {
         let skip_stmt = Stmt::skip(loc.clone());
         Stmt::atomic_block(vec![skip_stmt], loc)
     }
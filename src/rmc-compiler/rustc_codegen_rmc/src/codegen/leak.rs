@@ -0,0 +1,230 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module checks that harnesses free every heap allocation they make, either for every
+//! harness with `--check-leaks`, or for the harnesses annotated with `#[rmc::check_leaks]`.
+//!
+//! We track a single live allocation, chosen nondeterministically, so that every allocation is
+//! tracked on some path:
+//!  - `__rmc_leak_candidate` is the tracked allocation. Each successful call to an allocator
+//!    shim may replace it with the new allocation, and freeing it resets it to null.
+//!  - `__rmc_leak_site` is the index of the allocation site of the tracked allocation.
+//!
+//! The allocations of the standard library (e.g. of `Vec::push` or `Rc::new`) are reported at
+//! the innermost call from outside of it, since the allocator shims are only called deep inside
+//! the standard library. Each call that a function outside of the standard library makes is an
+//! allocation site, and `__rmc_leak_caller` is the index of the innermost such call, plus one
+//! (zero if there is none).
+//!
+//! A checked harness calls `__rmc_check_leaks` right before returning, which asserts that no
+//! allocation is tracked, with one assertion per allocation site. Since we only know all the
+//! allocation sites once the whole crate is codegenned, we generate its body last.
+
+use super::function::rmctool_attr_name;
+use super::PropertyClass;
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Symbol, Type};
+use cbmc::NO_PRETTY_NAME;
+use rmc_queries::{QueryDb, UserInput};
+use rustc_middle::ty::{Instance, TyCtxt};
+
+const LEAK_CANDIDATE_VAR_NAME: &str = "__rmc_leak_candidate";
+const LEAK_SITE_VAR_NAME: &str = "__rmc_leak_site";
+const LEAK_CALLER_VAR_NAME: &str = "__rmc_leak_caller";
+const LEAK_CHECK_FN_NAME: &str = "__rmc_check_leaks";
+
+/// Whether some harness of the crate checks for memory leaks.
+pub fn has_leak_checked_harness(tcx: TyCtxt<'_>, queries: &QueryDb) -> bool {
+    queries.get_check_leaks()
+        || tcx.hir().body_owners().any(|def_id| {
+            tcx.get_attrs(def_id.to_def_id())
+                .iter()
+                .any(|attr| rmctool_attr_name(attr).as_deref() == Some("check_leaks"))
+        })
+}
+
+impl<'tcx> GotocCtx<'tcx> {
+    fn codegen_leak_candidate(&mut self) -> Expr {
+        self.ensure_global_var(
+            LEAK_CANDIDATE_VAR_NAME,
+            false,
+            Type::void_pointer(),
+            Location::none(),
+            |_, var| Some(var.assign(Type::void_pointer().null(), Location::none())),
+        )
+    }
+
+    fn codegen_leak_site(&mut self) -> Expr {
+        self.ensure_global_var(
+            LEAK_SITE_VAR_NAME,
+            false,
+            Type::size_t(),
+            Location::none(),
+            |_, var| Some(var.assign(Type::size_t().zero(), Location::none())),
+        )
+    }
+
+    fn codegen_leak_caller(&mut self) -> Expr {
+        self.ensure_global_var(
+            LEAK_CALLER_VAR_NAME,
+            false,
+            Type::size_t(),
+            Location::none(),
+            |_, var| Some(var.assign(Type::size_t().zero(), Location::none())),
+        )
+    }
+
+    /// Whether `instance` is part of the standard library, whose allocations are reported at
+    /// their callers.
+    fn is_std_instance(&self, instance: Instance<'tcx>) -> bool {
+        let krate = instance.def_id().krate;
+        matches!(self.tcx.crate_name(krate).as_str(), "alloc" | "core" | "std")
+    }
+
+    /// Adds an allocation site at `loc` in the current function, and returns its index.
+    fn codegen_alloc_site(&mut self, loc: Location) -> usize {
+        let function = self.current_fn().readable_name().to_string();
+        self.alloc_sites.push((function, loc));
+        self.alloc_sites.len() - 1
+    }
+
+    /// Whether the current function is a harness that checks for memory leaks.
+    pub fn is_leak_checked_fn(&self) -> bool {
+        let instance = self.current_fn().instance();
+        let attrs: Vec<_> =
+            self.tcx.get_attrs(instance.def_id()).iter().filter_map(rmctool_attr_name).collect();
+        let is_harness =
+            attrs.iter().any(|attr| attr == "proof") || self.current_fn().readable_name() == "main";
+        attrs.iter().any(|attr| attr == "check_leaks")
+            || (is_harness && self.queries.get_check_leaks())
+    }
+
+    /// Records that `ptr` was allocated at `loc`:
+    /// ```c
+    /// if (ptr != NULL && nondet()) { __rmc_leak_candidate = ptr; __rmc_leak_site = site; }
+    /// ```
+    /// In the standard library, the site is the innermost call from outside of it, if any:
+    /// ```c
+    /// site = __rmc_leak_caller == 0 ? <loc> : __rmc_leak_caller - 1;
+    /// ```
+    pub fn codegen_track_alloc(&mut self, ptr: Expr, loc: Location) -> Stmt {
+        let site = Expr::int_constant(self.codegen_alloc_site(loc.clone()), Type::size_t());
+        let site = if self.is_std_instance(self.current_fn().instance()) {
+            let caller = self.codegen_leak_caller();
+            let caller_site = caller.clone().sub(Type::size_t().one());
+            caller.is_zero().ternary(site, caller_site)
+        } else {
+            site
+        };
+        let ptr = ptr.cast_to(Type::void_pointer());
+        let candidate = self.codegen_leak_candidate();
+        let site_var = self.codegen_leak_site();
+        let tracked = ptr.clone().neq(Type::void_pointer().null()).and(Type::bool().nondet());
+        tracked.if_then_else(
+            Stmt::block(
+                vec![candidate.assign(ptr, loc.clone()), site_var.assign(site, loc.clone())],
+                loc.clone(),
+            ),
+            None,
+            loc,
+        )
+    }
+
+    /// Makes the call `call` at `loc` an allocation site, if the current function is not part of
+    /// the standard library, and the callee may be (`None` if it is not known statically):
+    /// ```c
+    /// size_t caller = __rmc_leak_caller;
+    /// __rmc_leak_caller = site + 1;
+    /// call;
+    /// __rmc_leak_caller = caller;
+    /// ```
+    pub fn codegen_track_call(
+        &mut self,
+        callee: Option<Instance<'tcx>>,
+        call: Stmt,
+        loc: Location,
+    ) -> Stmt {
+        let callee_is_std = callee.map_or(true, |callee| self.is_std_instance(callee));
+        if !self.track_leaks || self.is_std_instance(self.current_fn().instance()) || !callee_is_std
+        {
+            return call;
+        }
+        let site = self.codegen_alloc_site(loc.clone());
+        let caller = self.codegen_leak_caller();
+        let saved = self.gen_temp_variable(Type::size_t(), loc.clone()).to_expr();
+        Stmt::block(
+            vec![
+                Stmt::decl(saved.clone(), Some(caller.clone()), loc.clone()),
+                caller.clone().assign(Expr::int_constant(site + 1, Type::size_t()), loc.clone()),
+                call,
+                caller.assign(saved, loc.clone()),
+            ],
+            loc,
+        )
+    }
+
+    /// Records that `ptr` was freed:
+    /// ```c
+    /// if (__rmc_leak_candidate == ptr) { __rmc_leak_candidate = NULL; }
+    /// ```
+    pub fn codegen_track_free(&mut self, ptr: Expr, loc: Location) -> Stmt {
+        let candidate = self.codegen_leak_candidate();
+        candidate.clone().eq(ptr.cast_to(Type::void_pointer())).if_then_else(
+            candidate.assign(Type::void_pointer().null(), loc.clone()),
+            None,
+            loc,
+        )
+    }
+
+    /// `__rmc_check_leaks()`, which checks that no allocation is live.
+    pub fn codegen_leak_check_call(&mut self, loc: Location) -> Stmt {
+        let check = self.ensure(LEAK_CHECK_FN_NAME, |_, name| {
+            Symbol::function(
+                name,
+                Type::code(vec![], Type::empty()),
+                None,
+                NO_PRETTY_NAME,
+                Location::none(),
+            )
+        });
+        check.to_expr().call(vec![]).as_stmt(loc)
+    }
+
+    /// Generates the body of `__rmc_check_leaks`, once all allocation sites are known:
+    /// ```c
+    /// assert(!(__rmc_leak_candidate != NULL && __rmc_leak_site == 0));
+    /// ...
+    /// ```
+    pub fn codegen_leak_check_fn(&mut self) {
+        if !self.symbol_table.contains(LEAK_CHECK_FN_NAME) {
+            return;
+        }
+        let candidate = self.codegen_leak_candidate();
+        let site_var = self.codegen_leak_site();
+        let leaked = candidate.neq(Type::void_pointer().null());
+        let sites = std::mem::take(&mut self.alloc_sites);
+        let checks = sites
+            .into_iter()
+            .enumerate()
+            .map(|(site, (function, loc))| {
+                let leaked_here = leaked
+                    .clone()
+                    .and(site_var.clone().eq(Expr::int_constant(site, Type::size_t())));
+                self.codegen_assert(
+                    leaked_here.not(),
+                    PropertyClass::MemoryLeak,
+                    &format!(
+                        "memory allocated in {} at {} is never freed",
+                        function,
+                        loc.short_string()
+                    ),
+                    loc,
+                )
+            })
+            .collect();
+        self.symbol_table.update_fn_declaration_with_definition(
+            LEAK_CHECK_FN_NAME,
+            Stmt::block(checks, Location::none()),
+        );
+    }
+}
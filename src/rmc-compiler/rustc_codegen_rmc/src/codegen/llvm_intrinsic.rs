@@ -0,0 +1,477 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module contains models of the LLVM intrinsics that `core::arch` uses to implement the
+//! x86 vendor intrinsics. For instance, `_mm_movemask_epi8` calls `llvm.x86.sse2.pmovmskb.128`,
+//! which is declared in an `extern "unadjusted"` block, so there is no Rust body to codegen.
+//! The vendor intrinsics that are implemented with generic SIMD intrinsics (e.g. `_mm_cmpeq_epi8`
+//! uses `simd_eq`) are handled in `intrinsic.rs`.
+//!
+//! Calls to the `llvm.x86.*` intrinsics that we do not model are reported as unsupported.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use rustc_middle::mir::{BasicBlock, Field, Place};
+
+/// The reflected CRC-32C (Castagnoli) polynomial, which the SSE4.2 `crc32` instructions use.
+const CRC32C_POLY: u32 = 0x82f6_3b78;
+
+/// The coefficients of the first row of the AES MixColumns matrix. The other rows are rotations.
+const MIX_COLUMNS: [u8; 4] = [2, 3, 1, 1];
+
+/// The coefficients of the first row of the AES InvMixColumns matrix.
+const INV_MIX_COLUMNS: [u8; 4] = [14, 11, 13, 9];
+
+const AES_SBOX_NAME: &str = "__rmc_aes_sbox";
+const AES_INV_SBOX_NAME: &str = "__rmc_aes_inv_sbox";
+
+/// The AES S-box, which SubBytes applies to each byte of the state.
+const AES_SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// The inverse of the AES S-box, which InvSubBytes applies to each byte of the state.
+const AES_INV_SBOX: [u8; 256] = [
+    0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7, 0xfb,
+    0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde, 0xe9, 0xcb,
+    0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42, 0xfa, 0xc3, 0x4e,
+    0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49, 0x6d, 0x8b, 0xd1, 0x25,
+    0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c, 0xcc, 0x5d, 0x65, 0xb6, 0x92,
+    0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15, 0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84,
+    0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7, 0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06,
+    0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02, 0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b,
+    0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc, 0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73,
+    0x96, 0xac, 0x74, 0x22, 0xe7, 0xad, 0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e,
+    0x47, 0xf1, 0x1a, 0x71, 0x1d, 0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b,
+    0xfc, 0x56, 0x3e, 0x4b, 0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4,
+    0x1f, 0xdd, 0xa8, 0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f,
+    0x60, 0x51, 0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef,
+    0xa0, 0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
+    0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c, 0x7d,
+];
+
+#[derive(Clone, Copy)]
+enum AesRound {
+    Decrypt,
+    DecryptLast,
+    Encrypt,
+    EncryptLast,
+}
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Generates a call to the LLVM intrinsic `name`, e.g. `llvm.x86.sse2.pmovmskb.128`.
+    pub fn codegen_llvm_intrinsic(
+        &mut self,
+        name: &str,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let ret_typ = self.codegen_ty(self.place_ty(p));
+        let stmt = match name {
+            "llvm.x86.aesni.aesdec" => self.codegen_aes_round(fargs, AesRound::Decrypt, p, loc),
+            "llvm.x86.aesni.aesdeclast" => {
+                self.codegen_aes_round(fargs, AesRound::DecryptLast, p, loc)
+            }
+            "llvm.x86.aesni.aesenc" => self.codegen_aes_round(fargs, AesRound::Encrypt, p, loc),
+            "llvm.x86.aesni.aesenclast" => {
+                self.codegen_aes_round(fargs, AesRound::EncryptLast, p, loc)
+            }
+            "llvm.x86.aesni.aesimc" => self.codegen_aes_inv_mix_columns(fargs, p, loc),
+            "llvm.x86.aesni.aeskeygenassist" => self.codegen_aes_keygen_assist(fargs, p, loc),
+            "llvm.x86.avx.cmp.pd.256"
+            | "llvm.x86.avx.cmp.ps.256"
+            | "llvm.x86.sse.cmp.ps"
+            | "llvm.x86.sse2.cmp.pd" => {
+                let e = self.codegen_packed_float_cmp(fargs, ret_typ);
+                self.codegen_expr_to_place(p, e)
+            }
+            "llvm.x86.avx.movmsk.pd.256"
+            | "llvm.x86.avx.movmsk.ps.256"
+            | "llvm.x86.avx2.pmovmskb"
+            | "llvm.x86.sse.movmsk.ps"
+            | "llvm.x86.sse2.movmsk.pd"
+            | "llvm.x86.sse2.pmovmskb.128" => {
+                let e = self.codegen_movemask(fargs.remove(0), ret_typ);
+                self.codegen_expr_to_place(p, e)
+            }
+            "llvm.x86.avx2.permd" | "llvm.x86.avx2.permps" => {
+                let e = self.codegen_permute(fargs, ret_typ);
+                self.codegen_expr_to_place(p, e)
+            }
+            "llvm.x86.avx2.pshuf.b" | "llvm.x86.ssse3.pshuf.b.128" => {
+                let e = self.codegen_shuffle_bytes(fargs, ret_typ);
+                self.codegen_expr_to_place(p, e)
+            }
+            "llvm.x86.rdrand.16" | "llvm.x86.rdrand.32" | "llvm.x86.rdrand.64"
+            | "llvm.x86.rdseed.16" | "llvm.x86.rdseed.32" | "llvm.x86.rdseed.64" => {
+                self.codegen_rdrand(p, loc)
+            }
+            "llvm.x86.sse42.crc32.32.8"
+            | "llvm.x86.sse42.crc32.32.16"
+            | "llvm.x86.sse42.crc32.32.32"
+            | "llvm.x86.sse42.crc32.64.64" => self.codegen_crc32c(fargs, p, loc),
+            _ => {
+                let e = self.codegen_unimplemented(
+                    name,
+                    ret_typ,
+                    loc,
+                    "https://github.com/model-checking/rmc/issues/new/choose",
+                );
+                self.codegen_expr_to_place(p, e)
+            }
+        };
+        Stmt::block(vec![stmt, Stmt::goto(self.current_fn().find_label(&target), loc)], loc)
+    }
+
+    fn vector_lanes(&self, v: &Expr) -> u64 {
+        let lane_bits = v.typ().base_type().unwrap().sizeof_in_bits(&self.symbol_table);
+        v.typ().sizeof_in_bits(&self.symbol_table) / lane_bits
+    }
+
+    /// `_mm_movemask_epi8` and friends gather the most significant bit of each lane of `v`.
+    fn codegen_movemask(&self, v: Expr, ret_typ: Type) -> Expr {
+        let mask_typ = Type::unsigned_int(32);
+        let mask = (0..self.vector_lanes(&v)).fold(mask_typ.zero(), |mask, i| {
+            let lane = lane(&v, i);
+            let width = lane.typ().sizeof_in_bits(&self.symbol_table);
+            let signed_typ = Type::signed_int(width);
+            let bits = if lane.typ().is_floating_point() {
+                lane.transmute_to(signed_typ.clone(), &self.symbol_table)
+            } else {
+                lane.cast_to(signed_typ.clone())
+            };
+            let bit = Expr::int_constant(1u64 << i, mask_typ.clone());
+            mask.bitor(bits.lt(signed_typ.zero()).ternary(bit, mask_typ.zero()))
+        });
+        mask.cast_to(ret_typ)
+    }
+
+    /// `_mm_cmp_ps` and friends compare each pair of lanes of `a` and `b` with the predicate
+    /// `imm8`, and set the lane of the result to all ones if it holds, or to zero otherwise.
+    /// Bit 4 of the predicate only selects whether NaN operands signal an exception, so we
+    /// ignore it.
+    fn codegen_packed_float_cmp(&self, mut fargs: Vec<Expr>, ret_typ: Type) -> Expr {
+        let a = fargs.remove(0);
+        let b = fargs.remove(0);
+        let byte = Type::unsigned_int(8);
+        let predicate = fargs.remove(0).cast_to(byte.clone()).bitand(Expr::int_constant(0xf, byte));
+        let lanes = self.vector_lanes(&a);
+        let width = a.typ().base_type().unwrap().sizeof_in_bits(&self.symbol_table);
+        let mask_typ = Type::unsigned_int(width);
+        let elems = (0..lanes)
+            .map(|i| {
+                let (x, y) = (lane(&a, i), lane(&b, i));
+                let holds = (0..16).rev().fold(Expr::bool_false(), |otherwise, k| {
+                    let is_k = predicate.clone().eq(Expr::int_constant(k, predicate.typ().clone()));
+                    is_k.ternary(float_cmp_predicate(k, x.clone(), y.clone()), otherwise)
+                });
+                holds.ternary(mask_typ.zero().bitnot(), mask_typ.zero())
+            })
+            .collect();
+        Expr::vector_expr(Type::vector(mask_typ, lanes), elems)
+            .transmute_to(ret_typ, &self.symbol_table)
+    }
+
+    /// `_mm256_permutevar8x32_epi32` and `_mm256_permutevar8x32_ps`: each lane of the result is
+    /// the lane of `a` selected by the low bits of the corresponding lane of `idx`.
+    fn codegen_permute(&self, mut fargs: Vec<Expr>, ret_typ: Type) -> Expr {
+        let a = fargs.remove(0);
+        let idx = fargs.remove(0);
+        let lanes = self.vector_lanes(&a);
+        let elems = (0..lanes)
+            .map(|i| {
+                let selected = lane(&idx, i).bitand(Expr::int_constant(lanes - 1, lane_typ(&idx)));
+                a.clone().index(selected.cast_to(Type::ssize_t()))
+            })
+            .collect();
+        Expr::vector_expr(ret_typ, elems)
+    }
+
+    /// `_mm_shuffle_epi8` and `_mm256_shuffle_epi8`: each byte of the result is the byte of `a`
+    /// selected by the low 4 bits of the corresponding byte of `b`, within the same 128-bit lane.
+    /// It is zero instead if the most significant bit of that byte of `b` is set.
+    fn codegen_shuffle_bytes(&self, mut fargs: Vec<Expr>, ret_typ: Type) -> Expr {
+        let a = fargs.remove(0);
+        let b = fargs.remove(0);
+        let byte = Type::unsigned_int(8);
+        let elems = (0..self.vector_lanes(&a))
+            .map(|i| {
+                let control = lane(&b, i).cast_to(byte.clone());
+                let offset = Expr::int_constant(i & !0xf, Type::ssize_t());
+                let selected = control
+                    .clone()
+                    .bitand(Expr::int_constant(0xf, byte.clone()))
+                    .cast_to(Type::ssize_t())
+                    .plus(offset);
+                let keeps_lane = control.bitand(Expr::int_constant(0x80, byte.clone())).is_zero();
+                keeps_lane.ternary(a.clone().index(selected), lane_typ(&a).zero())
+            })
+            .collect();
+        Expr::vector_expr(ret_typ, elems)
+    }
+
+    /// `rdrand` and `rdseed` return a random value and whether it is valid. They may fail to
+    /// produce a random value, in which case the value is zero.
+    fn codegen_rdrand(&mut self, p: &Place<'tcx>, loc: Location) -> Stmt {
+        let fields: Vec<_> = self.place_ty(p).tuple_fields().collect();
+        let value = self.tcx.mk_place_field(*p, Field::new(0), fields[0]);
+        let is_valid = self.tcx.mk_place_field(*p, Field::new(1), fields[1]);
+        let value_typ = self.codegen_ty(fields[0]);
+        let is_valid_typ = self.codegen_ty(fields[1]);
+        let success = self.gen_temp_variable(Type::bool(), loc).to_expr();
+        Stmt::block(
+            vec![
+                Stmt::decl(success.clone(), Some(Type::bool().nondet()), loc),
+                self.codegen_expr_to_place(
+                    &value,
+                    success.clone().ternary(value_typ.nondet(), value_typ.zero()),
+                ),
+                self.codegen_expr_to_place(
+                    &is_valid,
+                    success.ternary(is_valid_typ.one(), is_valid_typ.zero()),
+                ),
+            ],
+            loc,
+        )
+    }
+
+    /// The SSE4.2 `crc32` instructions accumulate the CRC-32C of `data` into `crc`. We compute it
+    /// one bit at a time, like the reference implementation of the reflected CRC.
+    fn codegen_crc32c(&mut self, mut fargs: Vec<Expr>, p: &Place<'tcx>, loc: Location) -> Stmt {
+        let crc = fargs.remove(0);
+        let data = fargs.remove(0);
+        let typ = crc.typ().clone();
+        let bits = data.typ().sizeof_in_bits(&self.symbol_table);
+        let poly = Expr::int_constant(CRC32C_POLY, typ.clone());
+        let reg = self.gen_temp_variable(typ.clone(), loc).to_expr();
+        let mut stmts =
+            vec![Stmt::decl(reg.clone(), Some(crc.bitxor(data.cast_to(typ.clone()))), loc)];
+        for _ in 0..bits {
+            let is_lsb_zero = reg.clone().bitand(typ.one()).is_zero();
+            let next =
+                reg.clone().lshr(typ.one()).bitxor(is_lsb_zero.ternary(typ.zero(), poly.clone()));
+            stmts.push(reg.clone().assign(next, loc));
+        }
+        stmts.push(self.codegen_expr_to_place(p, reg));
+        Stmt::block(stmts, loc)
+    }
+
+    /// The AES S-box or its inverse, as a global array.
+    fn codegen_aes_sbox(&mut self, inverse: bool) -> Expr {
+        let (name, table) =
+            if inverse { (AES_INV_SBOX_NAME, &AES_INV_SBOX) } else { (AES_SBOX_NAME, &AES_SBOX) };
+        let typ = Type::unsigned_int(8).array_of(table.len());
+        self.ensure_global_var(name, false, typ.clone(), Location::none(), |_, var| {
+            let elems =
+                table.iter().map(|b| Expr::int_constant(*b, Type::unsigned_int(8))).collect();
+            Some(var.assign(Expr::array_expr(typ, elems), Location::none()))
+        })
+    }
+
+    /// Declares a temporary that holds the 16 bytes of an AES state.
+    fn codegen_aes_state(&mut self, value: Expr, stmts: &mut Vec<Stmt>, loc: Location) -> Expr {
+        let typ = Type::vector(Type::unsigned_int(8), 16);
+        let value = if value.typ() == &typ {
+            value
+        } else {
+            value.transmute_to(typ.clone(), &self.symbol_table)
+        };
+        let state = self.gen_temp_variable(typ, loc).to_expr();
+        stmts.push(Stmt::decl(state.clone(), Some(value), loc));
+        state
+    }
+
+    fn codegen_aes_result(
+        &mut self,
+        bytes: Vec<Expr>,
+        p: &Place<'tcx>,
+        mut stmts: Vec<Stmt>,
+        loc: Location,
+    ) -> Stmt {
+        let ret_typ = self.codegen_ty(self.place_ty(p));
+        let result = Expr::vector_expr(Type::vector(Type::unsigned_int(8), 16), bytes)
+            .transmute_to(ret_typ, &self.symbol_table);
+        stmts.push(self.codegen_expr_to_place(p, result));
+        Stmt::block(stmts, loc)
+    }
+
+    /// `aesenc`, `aesenclast`, `aesdec` and `aesdeclast` perform one round of AES on the state
+    /// `a` with the given round key. The last round skips (Inv)MixColumns.
+    fn codegen_aes_round(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        round: AesRound,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let mut stmts = vec![];
+        let state = self.codegen_aes_state(fargs.remove(0), &mut stmts, loc);
+        let round_key = self.codegen_aes_state(fargs.remove(0), &mut stmts, loc);
+        let is_decrypt = matches!(round, AesRound::Decrypt | AesRound::DecryptLast);
+        let sbox = self.codegen_aes_sbox(is_decrypt);
+        // (Inv)ShiftRows rotates row `r` of the column-major state by `r` columns to the left
+        // (or to the right), and (Inv)SubBytes substitutes each byte.
+        let shift = if is_decrypt { 3 } else { 1 };
+        let substituted = (0..16)
+            .map(|i| {
+                let (row, column) = (i % 4, i / 4);
+                let byte = lane(&state, row + 4 * ((column + shift * row) % 4));
+                sbox.clone().index_array(byte.cast_to(Type::size_t()))
+            })
+            .collect();
+        let state = self.codegen_aes_state(
+            Expr::vector_expr(Type::vector(Type::unsigned_int(8), 16), substituted),
+            &mut stmts,
+            loc,
+        );
+        let state = match round {
+            AesRound::Decrypt => mix_columns(&state, &INV_MIX_COLUMNS),
+            AesRound::Encrypt => mix_columns(&state, &MIX_COLUMNS),
+            AesRound::DecryptLast | AesRound::EncryptLast => {
+                (0..16).map(|i| lane(&state, i)).collect()
+            }
+        };
+        let bytes = state
+            .into_iter()
+            .enumerate()
+            .map(|(i, b)| b.bitxor(lane(&round_key, i as u64)))
+            .collect();
+        self.codegen_aes_result(bytes, p, stmts, loc)
+    }
+
+    /// `aesimc` applies InvMixColumns to a round key.
+    fn codegen_aes_inv_mix_columns(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let mut stmts = vec![];
+        let state = self.codegen_aes_state(fargs.remove(0), &mut stmts, loc);
+        let bytes = mix_columns(&state, &INV_MIX_COLUMNS);
+        self.codegen_aes_result(bytes, p, stmts, loc)
+    }
+
+    /// `aeskeygenassist(a, rcon)` computes `SubWord(X1)`, `RotWord(SubWord(X1)) ^ rcon`,
+    /// `SubWord(X3)` and `RotWord(SubWord(X3)) ^ rcon`, where `Xi` is the `i`-th word of `a`.
+    fn codegen_aes_keygen_assist(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        loc: Location,
+    ) -> Stmt {
+        let mut stmts = vec![];
+        let state = self.codegen_aes_state(fargs.remove(0), &mut stmts, loc);
+        let rcon = fargs.remove(0).cast_to(Type::unsigned_int(8));
+        let sbox = self.codegen_aes_sbox(false);
+        let sub_byte = |i| sbox.clone().index_array(lane(&state, i).cast_to(Type::size_t()));
+        let bytes = vec![
+            sub_byte(4),
+            sub_byte(5),
+            sub_byte(6),
+            sub_byte(7),
+            sub_byte(5).bitxor(rcon.clone()),
+            sub_byte(6),
+            sub_byte(7),
+            sub_byte(4),
+            sub_byte(12),
+            sub_byte(13),
+            sub_byte(14),
+            sub_byte(15),
+            sub_byte(13).bitxor(rcon),
+            sub_byte(14),
+            sub_byte(15),
+            sub_byte(12),
+        ];
+        self.codegen_aes_result(bytes, p, stmts, loc)
+    }
+}
+
+/// `v[i]`
+fn lane(v: &Expr, i: u64) -> Expr {
+    v.clone().index(Expr::int_constant(i, Type::ssize_t()))
+}
+
+fn lane_typ(v: &Expr) -> Type {
+    v.typ().base_type().unwrap().clone()
+}
+
+/// The comparison predicates of `cmpps`, see the `_CMP_*` constants in `core::arch::x86_64`.
+fn float_cmp_predicate(predicate: u64, x: Expr, y: Expr) -> Expr {
+    let unordered = x.clone().fneq(x.clone()).or(y.clone().fneq(y.clone()));
+    match predicate {
+        0 => x.feq(y),
+        1 => x.lt(y),
+        2 => x.le(y),
+        3 => unordered,
+        4 => x.feq(y).not(),
+        5 => x.lt(y).not(),
+        6 => x.le(y).not(),
+        7 => unordered.not(),
+        8 => x.feq(y).or(unordered),
+        9 => x.ge(y).not(),
+        10 => x.gt(y).not(),
+        11 => Expr::bool_false(),
+        12 => x.fneq(y).and(unordered.not()),
+        13 => x.ge(y),
+        14 => x.gt(y),
+        15 => Expr::bool_true(),
+        _ => unreachable!("unexpected comparison predicate {}", predicate),
+    }
+}
+
+/// Multiplies the byte `b` by `x` in the AES field GF(2^8).
+fn xtime(b: Expr) -> Expr {
+    let typ = b.typ().clone();
+    let carry = b.clone().bitand(Expr::int_constant(0x80, typ.clone())).is_zero();
+    let reduction = carry.ternary(typ.zero(), Expr::int_constant(0x1b, typ.clone()));
+    b.shl(typ.one()).bitxor(reduction)
+}
+
+/// Multiplies the byte `b` by the constant `k` in the AES field GF(2^8).
+fn gf_mul(b: Expr, k: u8) -> Expr {
+    let mut power = b;
+    let mut product: Option<Expr> = None;
+    for bit in 0..4 {
+        if k & (1 << bit) != 0 {
+            product = Some(match product {
+                Some(product) => product.bitxor(power.clone()),
+                None => power.clone(),
+            });
+        }
+        power = xtime(power);
+    }
+    product.unwrap()
+}
+
+/// (Inv)MixColumns multiplies each column of the state by a circulant matrix.
+fn mix_columns(state: &Expr, coefficients: &[u8; 4]) -> Vec<Expr> {
+    (0..16)
+        .map(|i| {
+            let (row, column) = (i % 4, i / 4);
+            (0..4)
+                .map(|j| {
+                    gf_mul(lane(state, 4 * column + j), coefficients[((j + 4 - row) % 4) as usize])
+                })
+                .reduce(|acc, e| acc.bitxor(e))
+                .unwrap()
+        })
+        .collect()
+}
@@ -0,0 +1,94 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module builds the loop table of `rmc-metadata.json`, which relates the loop ids of
+//! CBMC, e.g. `_RNvCs..main.0` in unwinding failures and `--unwindset`, to the loops of the Rust
+//! code. With it, tools can report the source location of a loop that exceeded its bound, and
+//! translate bounds written against Rust source lines to CBMC loop ids.
+//!
+//! The loops of a function are its MIR loop headers, i.e. the targets of back edges, which
+//! jump to a block that dominates their source. We record them when we codegen the function,
+//! but only know their CBMC ids once the symbol table passes ran, since the passes may remove
+//! or redirect gotos.
+
+use crate::context::metadata::LoopMetadata;
+use crate::GotocCtx;
+use cbmc::goto_program::{SymbolTable, SymbolValues};
+use rustc_middle::mir::Location;
+use std::collections::BTreeMap;
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Records the loop headers of the current function.
+    pub fn record_loops(&mut self) {
+        let mir = self.current_fn().mir();
+        let dominators = mir.dominators();
+        let mut headers = vec![];
+        for (bb, bbd) in mir.basic_blocks().iter_enumerated() {
+            if !dominators.is_reachable(bb) {
+                continue;
+            }
+            for succ in bbd.terminator().successors() {
+                if dominators.is_dominated_by(bb, *succ) && !headers.contains(succ) {
+                    headers.push(*succ);
+                }
+            }
+        }
+        headers.sort();
+
+        let loops: Vec<_> = headers
+            .into_iter()
+            .map(|header| {
+                let span = mir.source_info(Location { block: header, statement_index: 0 }).span;
+                let loc = self.codegen_span(&span);
+                LoopMetadata {
+                    label: Some(self.current_fn().find_label(&header)),
+                    original_file: loc.filename().unwrap_or_default(),
+                    original_line: loc.line().map_or(String::new(), |line| line.to_string()),
+                    cbmc_loop_ids: vec![],
+                }
+            })
+            .collect();
+        if !loops.is_empty() {
+            self.loops.insert(self.current_fn().name(), loops);
+        }
+    }
+}
+
+/// Gives the loops of `loops` their ids in CBMC, from the function bodies of `symtab`, the
+/// symbol table once the passes ran. Drops the functions that the passes removed. The loops
+/// that CBMC finds but that are not MIR loops, e.g. the loops that the codegen adds to
+/// initialize arrays, are recorded without a label, at the location of their backward jump.
+pub fn assign_cbmc_loop_ids(
+    mut loops: BTreeMap<String, Vec<LoopMetadata>>,
+    symtab: &SymbolTable,
+) -> BTreeMap<String, Vec<LoopMetadata>> {
+    let mut result = BTreeMap::new();
+    for (function, symbol) in symtab.iter() {
+        let body = match &symbol.value {
+            SymbolValues::Stmt(body) => body,
+            _ => continue,
+        };
+        let mut fn_loops = loops.remove(&function.to_string()).unwrap_or_default();
+        let mut unmatched = vec![];
+        for (idx, (label, location)) in body.loop_labels().into_iter().enumerate() {
+            let cbmc_loop_id = format!("{}.{}", function, idx);
+            let header = label.and_then(|label| {
+                fn_loops.iter_mut().find(|l| l.label.as_ref().map_or(false, |name| label == *name))
+            });
+            match header {
+                Some(header) => header.cbmc_loop_ids.push(cbmc_loop_id),
+                None => unmatched.push(LoopMetadata {
+                    label: None,
+                    original_file: location.filename().unwrap_or_default(),
+                    original_line: location.line().map_or(String::new(), |line| line.to_string()),
+                    cbmc_loop_ids: vec![cbmc_loop_id],
+                }),
+            }
+        }
+        fn_loops.extend(unmatched);
+        if !fn_loops.is_empty() {
+            result.insert(function.to_string(), fn_loops);
+        }
+    }
+    result
+}
@@ -4,15 +4,28 @@
 //! This module does that actual translation of MIR constructs to goto constructs.
 //! Each subfile is named for the MIR construct it translates.
 
+mod alloc;
+mod assembly;
+mod assert;
 mod block;
+mod fn_ptr;
 mod function;
+mod hash;
 mod intrinsic;
+mod leak;
+mod llvm_intrinsic;
+mod loops;
 mod operand;
+mod panic;
 mod place;
 mod rvalue;
 mod span;
 mod statement;
 mod static_var;
+mod thread;
 mod typ;
 
+pub use assert::PropertyClass;
+pub use leak::has_leak_checked_harness;
+pub use loops::assign_cbmc_loop_ids;
 pub use typ::TypeExt;
@@ -350,7 +350,9 @@ fn codegen_alloc_pointer(
         let base_addr = match self.tcx.global_alloc(alloc_id) {
             GlobalAlloc::Function(instance) => {
                 // here we have a function pointer
-                self.codegen_func_expr(instance, span).address_of()
+                let func = self.codegen_func_expr(instance, span);
+                self.record_fn_address_taken(&func);
+                func.address_of()
             }
             GlobalAlloc::Static(def_id) => {
                 // here we have a potentially unevaluated static
@@ -378,6 +380,7 @@ fn codegen_alloc_pointer(
                         ctx.codegen_span(&span),
                     )
                     .with_is_extern(rlinkage.is_none())
+                    .with_is_thread_local(ctx.tcx.is_thread_local_static(def_id))
                 });
                 sym.clone().to_expr().address_of()
             }
@@ -536,13 +539,23 @@ fn codegen_alloc_in_memory(&mut self, alloc: &'tcx Allocation, name: String) {
         let temp_var = self.gen_function_local_variable(0, &fn_name, alloc_typ_ref).to_expr();
         let body = Stmt::block(
             vec![
-                Stmt::decl(temp_var.clone(), Some(val), Location::none()),
-                var.assign(temp_var.transmute_to(var_typ, &self.symbol_table), Location::none()),
+                Stmt::decl(temp_var.clone(), Some(val.clone()), Location::none()),
+                var.assign(
+                    temp_var.transmute_to(var_typ.clone(), &self.symbol_table),
+                    Location::none(),
+                ),
             ],
             Location::none(),
         );
         self.register_initializer(&name, body);
 
+        // The initializer only runs in the main thread. CBMC initializes the copies of a
+        // thread-local variable in other threads with the value of its symbol instead.
+        if self.symbol_table.lookup(&name).unwrap().is_thread_local {
+            let init = val.transmute_to(var_typ, &self.symbol_table);
+            self.symbol_table.update_static_variable_initial_value(&name, init);
+        }
+
         self.alloc_map.insert(alloc, name);
     }
 
@@ -605,7 +618,7 @@ fn codegen_niche_literal(&mut self, ty: Ty<'tcx>, offset: usize, init: Expr) ->
 
     pub fn codegen_func_expr(&mut self, instance: Instance<'tcx>, span: Option<&Span>) -> Expr {
         let func = self.symbol_name(instance);
-        let funct = self.codegen_function_sig(self.fn_sig_of_instance(instance).unwrap());
+        let funct = self.codegen_instance_fn_sig(instance);
         // make sure the functions imported from other modules are in the symbol table
         self.ensure(&func, |ctx, _| {
             Symbol::function(
@@ -0,0 +1,159 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module models unwinding panics (`--panic-unwind`).
+//!
+//! We use two global variables to track the state of a panic:
+//!  - `__rmc_unwinding` is set when a function returns because it is unwinding. Every call site
+//!    checks it right after the call returns. If it is set, the caller clears it and jumps to
+//!    the MIR cleanup block of the call (or returns right away if the call has no cleanup).
+//!    The `Resume` terminator at the end of a cleanup block sets it again before returning, so
+//!    the panic continues to propagate to the next caller.
+//!  - `__rmc_catch_depth` counts how many `try` intrinsics are active. A panic with no active
+//!    `try` can never be caught, so we report it as a failure right away. We still unwind, so
+//!    the assertions in the destructors that run during unwinding are checked as well.
+//!
+//! Clearing the flag while executing cleanup code means that the destructors invoked during
+//! unwinding run as regular code.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use rmc_queries::UserInput;
+use rustc_middle::mir::{BasicBlock, Place};
+
+const UNWINDING_VAR_NAME: &str = "__rmc_unwinding";
+const CATCH_DEPTH_VAR_NAME: &str = "__rmc_catch_depth";
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Whether panics are modeled as unwinding rather than aborting.
+    pub fn is_panic_unwind(&self) -> bool {
+        self.queries.get_panic_unwind()
+    }
+
+    fn codegen_unwinding_flag(&mut self) -> Expr {
+        self.ensure_global_var(
+            UNWINDING_VAR_NAME,
+            false,
+            Type::bool(),
+            Location::none(),
+            |_, var| Some(var.assign(Expr::bool_false(), Location::none())),
+        )
+    }
+
+    fn codegen_catch_depth(&mut self) -> Expr {
+        self.ensure_global_var(
+            CATCH_DEPTH_VAR_NAME,
+            false,
+            Type::size_t(),
+            Location::none(),
+            |_, var| Some(var.assign(Type::size_t().zero(), Location::none())),
+        )
+    }
+
+    /// Start unwinding from the current function.
+    /// A panic that cannot be caught is a verification failure.
+    pub fn codegen_start_unwind(&mut self, msg: &str, loc: Location) -> Stmt {
+        let can_be_caught = self.codegen_catch_depth().gt(Type::size_t().zero());
+        let unwinding = self.codegen_unwinding_flag();
+        Stmt::block(
+            vec![
+                Stmt::assert(can_be_caught, msg, loc.clone()),
+                unwinding.assign(Expr::bool_true(), loc.clone()),
+            ],
+            loc,
+        )
+    }
+
+    /// Generates the check that follows a call which may unwind, i.e.:
+    /// ```c
+    /// if (__rmc_unwinding) { __rmc_unwinding = false; goto cleanup; }
+    /// ```
+    /// If the call has no cleanup block, we keep unwinding by returning from the current
+    /// function. Unwinding out of a cleanup block aborts, like a panic while panicking does.
+    /// This is a no-op unless panics are modeled as unwinding.
+    pub fn codegen_unwind_check(&mut self, cleanup: Option<BasicBlock>, loc: Location) -> Stmt {
+        if !self.is_panic_unwind() {
+            return Stmt::skip(loc);
+        }
+        let unwinding = self.codegen_unwinding_flag();
+        let on_unwind = match cleanup {
+            Some(cleanup) => Stmt::block(
+                vec![
+                    unwinding.clone().assign(Expr::bool_false(), loc.clone()),
+                    Stmt::goto(self.current_fn().find_label(&cleanup), loc.clone()),
+                ],
+                loc.clone(),
+            ),
+            None => {
+                let bb = self.current_fn().current_bb();
+                if self.current_fn().mir().basic_blocks()[bb].is_cleanup {
+                    Stmt::block(
+                        vec![
+                            Stmt::assert_false("panic while unwinding", loc.clone()),
+                            Stmt::assume(Expr::bool_false(), loc.clone()),
+                        ],
+                        loc.clone(),
+                    )
+                } else {
+                    self.codegen_return(loc.clone())
+                }
+            }
+        };
+        unwinding.if_then_else(on_unwind, None, loc)
+    }
+
+    /// Generates the `Resume` terminator, which ends a cleanup block and continues unwinding.
+    pub fn codegen_resume(&mut self, loc: Location) -> Stmt {
+        let unwinding = self.codegen_unwinding_flag();
+        Stmt::block(
+            vec![
+                unwinding.assign(Expr::bool_true(), loc.clone()),
+                self.codegen_return(loc.clone()),
+            ],
+            loc,
+        )
+    }
+
+    /// `try(try_fn, data, catch_fn)` calls `try_fn(data)`. If it unwinds, the panic is caught,
+    /// `catch_fn(data, payload)` is called, and `try` returns 1. Otherwise, `try` returns 0.
+    /// We do not model panic payloads, so the payload is always null.
+    /// https://doc.rust-lang.org/std/intrinsics/fn.try.html
+    pub fn codegen_try(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        ret_typ: Type,
+        loc: Location,
+    ) -> Stmt {
+        let try_fn = fargs.remove(0);
+        let data = fargs.remove(0);
+        let catch_fn = fargs.remove(0);
+        let call_try = try_fn.dereference().call(vec![data.clone()]).as_stmt(loc.clone());
+        if !self.is_panic_unwind() {
+            // Panics abort, so `try_fn` either returns normally or never returns.
+            return Stmt::block(vec![call_try, self.codegen_expr_to_place(p, ret_typ.zero())], loc);
+        }
+
+        let depth = self.codegen_catch_depth();
+        let unwinding = self.codegen_unwinding_flag();
+        let payload = Type::unsigned_int(8).to_pointer().null();
+        let caught = Stmt::block(
+            vec![
+                unwinding.clone().assign(Expr::bool_false(), loc.clone()),
+                catch_fn.dereference().call(vec![data, payload]).as_stmt(loc.clone()),
+                self.codegen_expr_to_place(p, ret_typ.one()),
+            ],
+            loc.clone(),
+        );
+        let returned = self.codegen_expr_to_place(p, ret_typ.zero());
+        Stmt::block(
+            vec![
+                depth.clone().assign(depth.clone().plus(Type::size_t().one()), loc.clone()),
+                call_try,
+                depth.clone().assign(depth.sub(Type::size_t().one()), loc.clone()),
+                unwinding.if_then_else(caught, Some(returned), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
@@ -15,15 +15,17 @@
     mir::{Field, Local, Place, ProjectionElem},
     ty::{self, Ty, TyS, TypeAndMut, VariantDef},
 };
-use rustc_target::abi::{TagEncoding, Variants};
+use rustc_target::abi::{TagEncoding, VariantIdx, Variants};
 use tracing::{debug, warn};
 
 /// A projection in RMC can either be to a type (the normal case),
 /// or a variant in the case of a downcast.
+/// Generator variants have no `VariantDef`, so we identify them by their index.
 #[derive(Debug)]
 pub enum TypeOrVariant<'tcx> {
     Type(Ty<'tcx>),
     Variant(&'tcx VariantDef),
+    GeneratorVariant(VariantIdx),
 }
 
 /// Relevent information about a projected place (i.e. an lvalue).
@@ -81,7 +83,7 @@ fn check_expr_typ_mismatch(
                 if expr_ty != type_from_mir { Some((expr_ty, type_from_mir)) } else { None }
             }
             // TODO: handle Variant https://github.com/model-checking/rmc/issues/448
-            TypeOrVariant::Variant(_) => None,
+            TypeOrVariant::Variant(_) | TypeOrVariant::GeneratorVariant(_) => None,
         }
     }
 
@@ -141,7 +143,7 @@ impl<'tcx> TypeOrVariant<'tcx> {
     pub fn monomorphize(self, ctx: &GotocCtx<'tcx>) -> Self {
         match self {
             TypeOrVariant::Type(t) => TypeOrVariant::Type(ctx.monomorphize(t)),
-            TypeOrVariant::Variant(_) => self,
+            TypeOrVariant::Variant(_) | TypeOrVariant::GeneratorVariant(_) => self,
         }
     }
 }
@@ -151,6 +153,9 @@ pub fn expect_type(&self) -> Ty<'tcx> {
         match self {
             TypeOrVariant::Type(t) => t,
             TypeOrVariant::Variant(v) => panic!("expect a type but variant is found: {:?}", v),
+            TypeOrVariant::GeneratorVariant(v) => {
+                panic!("expect a type but generator variant is found: {:?}", v)
+            }
         }
     }
 
@@ -159,6 +164,9 @@ pub fn expect_variant(&self) -> &'tcx VariantDef {
         match self {
             TypeOrVariant::Type(t) => panic!("expect a variant but type is found: {:?}", t),
             TypeOrVariant::Variant(v) => v,
+            TypeOrVariant::GeneratorVariant(v) => {
+                panic!("expect a variant but generator variant is found: {:?}", v)
+            }
         }
     }
 }
@@ -208,12 +216,10 @@ fn codegen_field(&mut self, res: Expr, t: TypeOrVariant<'tcx>, f: &Field) -> Exp
                         res.member(&field.ident.name.to_string(), &self.symbol_table)
                     }
                     ty::Closure(..) => res.member(&f.index().to_string(), &self.symbol_table),
-                    ty::Generator(..) => self.codegen_unimplemented(
-                        "ty::Generator",
-                        Type::code(vec![], Type::empty()),
-                        res.location().clone(),
-                        "https://github.com/model-checking/rmc/issues/416",
-                    ),
+                    // a field of a generator that has not been downcast is one of its upvars
+                    ty::Generator(..) => self
+                        .codegen_generator_prefix(res)
+                        .member(&Self::tuple_fld_name(f.index()), &self.symbol_table),
                     _ => unimplemented!(),
                 }
             }
@@ -222,6 +228,10 @@ fn codegen_field(&mut self, res: Expr, t: TypeOrVariant<'tcx>, f: &Field) -> Exp
                 let field = &v.fields[f.index()];
                 res.member(&field.ident.name.to_string(), &self.symbol_table)
             }
+            // the saved locals of a generator variant are named like tuple fields
+            TypeOrVariant::GeneratorVariant(_) => {
+                res.member(&Self::tuple_fld_name(f.index()), &self.symbol_table)
+            }
         }
     }
 
@@ -448,6 +458,17 @@ fn codegen_projection(
                             self,
                         )
                     }
+                    ty::Generator(..) => {
+                        let variant_name = Self::generator_variant_name(idx);
+                        let expr = before.goto_expr.member(&variant_name, &self.symbol_table);
+                        ProjectedPlace::new(
+                            expr,
+                            TypeOrVariant::GeneratorVariant(idx),
+                            before.fat_ptr_goto_expr,
+                            before.fat_ptr_mir_typ,
+                            self,
+                        )
+                    }
                     _ => unreachable!("it's a bug to reach here!"),
                 }
             }
@@ -12,7 +12,9 @@
 use rustc_middle::mir::{AggregateKind, BinOp, CastKind, NullOp, Operand, Place, Rvalue, UnOp};
 use rustc_middle::ty::adjustment::PointerCast;
 use rustc_middle::ty::layout::LayoutOf;
-use rustc_middle::ty::{self, Instance, IntTy, Ty, UintTy, VtblEntry, COMMON_VTABLE_ENTRIES};
+use rustc_middle::ty::{
+    self, Instance, InstanceDef, IntTy, Ty, UintTy, VtblEntry, COMMON_VTABLE_ENTRIES,
+};
 use rustc_target::abi::{FieldsShape, Primitive, TagEncoding, Variants};
 use tracing::{debug, warn};
 
@@ -431,15 +433,7 @@ pub fn codegen_rvalue(&mut self, rv: &Rvalue<'tcx>) -> Expr {
             Rvalue::Aggregate(ref k, operands) => {
                 self.codegen_rvalue_aggregate(&*k, operands, res_ty)
             }
-            Rvalue::ThreadLocalRef(_) => {
-                let typ = self.codegen_ty(res_ty);
-                self.codegen_unimplemented(
-                    "Rvalue::ThreadLocalRef",
-                    typ,
-                    Location::none(),
-                    "https://github.com/model-checking/rmc/issues/541",
-                )
-            }
+            Rvalue::ThreadLocalRef(def_id) => self.codegen_thread_local_ref(*def_id),
         }
     }
 
@@ -457,6 +451,7 @@ pub fn codegen_get_discriminant(&mut self, e: Expr, ty: Ty<'tcx>, res_ty: Ty<'tc
             }
             Variants::Multiple { tag, tag_encoding, .. } => match tag_encoding {
                 TagEncoding::Direct => {
+                    let e = if ty.is_generator() { self.codegen_generator_prefix(e) } else { e };
                     e.member("case", &self.symbol_table).cast_to(self.codegen_ty(res_ty))
                 }
                 TagEncoding::Niche { dataful_variant, niche_variants, niche_start } => {
@@ -628,6 +623,41 @@ fn codegen_misc_cast(&mut self, src: &Operand<'tcx>, dst_t: Ty<'tcx>) -> Expr {
         }
     }
 
+    /// Only closures that capture nothing can be coerced to function pointers. The function
+    /// that implements such a closure still takes its empty environment as first argument,
+    /// which the callers of the function pointer do not pass. So we point to a shim instead:
+    /// ```c
+    /// ret closure_shim(arg1, ..., argn) { return closure(env, arg1, ..., argn); }
+    /// ```
+    fn codegen_closure_fn_ptr_shim(&mut self, instance: Instance<'tcx>) -> Expr {
+        let func = self.codegen_func_expr(instance, None);
+        let func_name = self.closure_fn_ptr_shim_name(instance);
+        self.ensure(&func_name, |tcx, _| {
+            let params = func.typ().parameters().unwrap();
+            let ret_typ = func.typ().return_type().unwrap().clone();
+            let env_typ = params[0].typ().clone();
+            let env = Expr::struct_expr_from_values(env_typ, vec![], &tcx.symbol_table);
+            let args: Vec<_> = params[1..]
+                .iter()
+                .enumerate()
+                .map(|(i, param)| {
+                    tcx.gen_function_local_variable(i as u64 + 1, &func_name, param.typ().clone())
+                })
+                .collect();
+            let mut call_args = vec![env];
+            call_args.extend(args.iter().map(|arg| arg.to_expr()));
+            let call = func.clone().call(call_args);
+            Symbol::function(
+                &func_name,
+                Type::code(args.iter().map(|arg| arg.to_function_parameter()).collect(), ret_typ),
+                Some(call.ret(Location::none())),
+                NO_PRETTY_NAME,
+                Location::none(),
+            )
+        });
+        self.find_function(&func_name).unwrap()
+    }
+
     pub fn codegen_pointer_cast(
         &mut self,
         k: &PointerCast,
@@ -635,17 +665,38 @@ pub fn codegen_pointer_cast(
         t: Ty<'tcx>,
     ) -> Expr {
         match k {
-            PointerCast::ReifyFnPointer => self.codegen_operand(o).address_of(),
+            PointerCast::ReifyFnPointer => match self.operand_ty(o).kind() {
+                // Function pointers to `#[track_caller]` functions point to a shim that
+                // supplies the implicit caller location argument, so resolve accordingly.
+                ty::FnDef(def_id, substs) => {
+                    let instance = Instance::resolve_for_fn_ptr(
+                        self.tcx,
+                        ty::ParamEnv::reveal_all(),
+                        *def_id,
+                        substs,
+                    )
+                    .unwrap();
+                    let func = self.codegen_func_expr(instance, None);
+                    self.record_fn_address_taken(&func);
+                    func.address_of()
+                }
+                _ => self.codegen_operand(o).address_of(),
+            },
             PointerCast::UnsafeFnPointer => self.codegen_operand(o),
-            PointerCast::ClosureFnPointer(_) => {
-                let dest_typ = self.codegen_ty(t);
-                self.codegen_unimplemented(
-                    "PointerCast::ClosureFnPointer",
-                    dest_typ,
-                    Location::none(),
-                    "https://github.com/model-checking/rmc/issues/274",
-                )
-            }
+            PointerCast::ClosureFnPointer(_) => match self.operand_ty(o).kind() {
+                ty::Closure(def_id, substs) => {
+                    let instance = Instance::resolve_closure(
+                        self.tcx,
+                        *def_id,
+                        substs,
+                        ty::ClosureKind::FnOnce,
+                    );
+                    let shim = self.codegen_closure_fn_ptr_shim(instance);
+                    self.record_fn_address_taken(&shim);
+                    shim.address_of()
+                }
+                _ => unreachable!("Expected a closure, got {:?}", self.operand_ty(o)),
+            },
             PointerCast::MutToConstPointer => self.codegen_operand(o),
             PointerCast::ArrayToPointer => {
                 // TODO: I am not sure whether it is correct or not.
@@ -758,60 +809,40 @@ fn codegen_vtable_drop_in_place(
         let drop_instance = Instance::resolve_drop_in_place(self.tcx, ty).polymorphize(self.tcx);
         let drop_sym_name: InternedString = self.symbol_name(drop_instance).into();
 
-        // The drop instance has the concrete object type, for consistency with
-        // type codegen we need the trait type for the function parameter.
-        let trait_fn_ty = self.trait_vtable_drop_type(trait_ty);
-
-        if let Some(drop_sym) = self.symbol_table.lookup(drop_sym_name) {
-            if self.vtable_ctx.emit_vtable_restrictions {
-                // Add to the possible method names for this trait type
-                self.vtable_ctx.add_possible_method(
-                    self.normalized_trait_name(trait_ty).into(),
-                    VtableCtx::drop_index(),
-                    drop_sym_name,
-                );
-            }
-
-            Expr::symbol_expression(drop_sym_name, drop_sym.clone().typ)
-                .address_of()
-                .cast_to(trait_fn_ty)
-        } else {
-            // We skip an entire submodule of the standard library, so drop is missing
-            // for it. Build and insert a function that just calls an unimplemented block
-            // to maintain soundness.
-            let drop_sym_name = format!("{}_unimplemented", self.symbol_name(drop_instance));
-            let drop_sym = self.ensure(&drop_sym_name, |ctx, name| {
-                // Function body
-                let unimplemented = ctx
-                    .codegen_unimplemented(
-                        format!("drop_in_place for {}", drop_sym_name).as_str(),
-                        Type::empty(),
-                        Location::none(),
-                        "https://github.com/model-checking/rmc/issues/281",
-                    )
-                    .as_stmt(Location::none());
-
-                // Declare symbol for the single, self parameter
-                let param_name = format!("{}::1::var{:?}", drop_sym_name, 0);
-                let param_sym = Symbol::variable(
-                    param_name.clone(),
-                    param_name,
-                    ctx.codegen_ty(trait_ty).to_pointer(),
-                    Location::none(),
-                );
-                ctx.symbol_table.insert(param_sym.clone());
-
-                // Build and insert the function itself
-                Symbol::function(
-                    name,
-                    Type::code(vec![param_sym.to_function_parameter()], Type::empty()),
-                    Some(Stmt::block(vec![unimplemented], Location::none())),
-                    NO_PRETTY_NAME,
+        // The drop glue of the types in a vtable is collected as well, so it is declared unless
+        // it is empty. We declare it either way, and give an empty drop glue its (empty) body.
+        // A missing drop glue that is not empty would be skipped by CBMC, so we make its calls
+        // fail instead.
+        let is_missing_drop = self.symbol_table.lookup(drop_sym_name).is_none();
+        let drop_fn = self.codegen_func_expr(drop_instance, None);
+        if is_missing_drop {
+            let body = if matches!(drop_instance.def, InstanceDef::DropGlue(_, None)) {
+                Stmt::block(vec![], Location::none())
+            } else {
+                Stmt::assert_false(
+                    &format!(
+                        "The drop glue {} is not currently supported by RMC",
+                        self.readable_instance_name(drop_instance)
+                    ),
                     Location::none(),
                 )
-            });
-            drop_sym.to_expr().address_of().cast_to(trait_fn_ty)
+            };
+            self.symbol_table.update_fn_declaration_with_definition(drop_sym_name, body);
+        }
+
+        if self.vtable_ctx.emit_vtable_restrictions {
+            // Add to the possible method names for this trait type
+            self.vtable_ctx.add_possible_method(
+                self.normalized_trait_name(trait_ty).into(),
+                VtableCtx::drop_index(),
+                drop_sym_name,
+            );
         }
+
+        // The drop instance has the concrete object type, for consistency with
+        // type codegen we need the trait type for the function parameter.
+        let trait_fn_ty = self.trait_vtable_drop_type(trait_ty);
+        drop_fn.address_of().cast_to(trait_fn_ty)
     }
 
     /// The size and alignment for the vtable is of the underlying type.
@@ -864,7 +895,7 @@ fn check_vtable_size(&mut self, operand_type: Ty<'tcx>, vt_size: Expr) -> Stmt {
         Stmt::block(vec![decl, size_assert], Location::none())
     }
 
-    fn codegen_vtable(&mut self, src_mir_type: Ty<'tcx>, dst_mir_type: Ty<'tcx>) -> Expr {
+    pub fn codegen_vtable(&mut self, src_mir_type: Ty<'tcx>, dst_mir_type: Ty<'tcx>) -> Expr {
         let trait_type = match dst_mir_type.kind() {
             // DST is pointer type
             ty::Ref(_, pointee_type, ..) => pointee_type,
@@ -4,7 +4,7 @@
 //! MIR Span related functions
 
 use crate::GotocCtx;
-use cbmc::goto_program::Location;
+use cbmc::goto_program::{Expr, Location, Symbol};
 use rustc_middle::mir::{Local, VarDebugInfo, VarDebugInfoContents};
 use rustc_span::Span;
 
@@ -40,6 +40,64 @@ pub fn codegen_caller_span(&self, sp: &Option<Span>) -> Location {
         }
     }
 
+    /// The `&'static core::panic::Location<'static>` describing where the current call
+    /// terminator was called from, as returned by `Location::caller()`.
+    ///
+    /// This mirrors `get_caller_location` in rustc_codegen_ssa/src/mir/block.rs:
+    /// spans of calls inlined from `#[track_caller]` functions are replaced by their
+    /// call sites, and if the current function is itself `#[track_caller]`, we forward
+    /// the location we received from our caller.
+    pub fn codegen_caller_location(&mut self) -> Expr {
+        let mir = self.current_fn().mir();
+        let bb = self.current_fn().current_bb();
+        let mut source_info = mir.basic_blocks()[bb].terminator().source_info;
+        loop {
+            let scope_data = &mir.source_scopes[source_info.scope];
+            if let Some((callee, callsite_span)) = scope_data.inlined {
+                // Stop inside the most nested non-`#[track_caller]` function.
+                if !callee.def.requires_caller_location(self.tcx) {
+                    return self.codegen_caller_location_const(source_info.span);
+                }
+                source_info.span = callsite_span;
+            }
+            match scope_data.inlined_parent_scope {
+                Some(parent) => source_info.scope = parent,
+                None => break,
+            }
+        }
+
+        if self.current_fn().instance().def.requires_caller_location(self.tcx) {
+            let (name, _) = self.codegen_caller_location_param_name();
+            Expr::symbol_expression(name, self.codegen_caller_location_ty())
+        } else {
+            self.codegen_caller_location_const(source_info.span)
+        }
+    }
+
+    /// A constant `core::panic::Location` for the (macro caller of the) span.
+    fn codegen_caller_location_const(&mut self, span: Span) -> Expr {
+        let topmost = span.ctxt().outer_expn().expansion_cause().unwrap_or(span);
+        let caller = self.tcx.sess.source_map().lookup_char_pos(topmost.lo());
+        let const_loc = self.tcx.const_caller_location((
+            rustc_span::Symbol::intern(&caller.file.name.prefer_remapped().to_string_lossy()),
+            caller.line as u32,
+            caller.col_display as u32 + 1,
+        ));
+        self.codegen_const_value(const_loc, self.tcx.caller_location_ty(), Some(&span))
+    }
+
+    /// Add the implicit caller location parameter of `#[track_caller]` functions to the
+    /// symbol table. Like the spread arguments, it is patched into the signature by `fn_typ`.
+    pub fn codegen_declare_caller_location_param(&mut self) {
+        if !self.current_fn().instance().def.requires_caller_location(self.tcx) {
+            return;
+        }
+        let (name, base_name) = self.codegen_caller_location_param_name();
+        let typ = self.codegen_caller_location_ty();
+        let loc = self.codegen_span(&self.current_fn().mir().span);
+        self.symbol_table.insert(Symbol::variable(name, base_name, typ, loc));
+    }
+
     pub fn codegen_span_option(&self, sp: Option<Span>) -> Location {
         sp.map_or(Location::none(), |x| self.codegen_span(&x))
     }
@@ -13,7 +13,7 @@
 use rustc_middle::ty::layout::LayoutOf;
 use rustc_middle::ty::{Instance, InstanceDef, Ty};
 use rustc_span::Span;
-use rustc_target::abi::{FieldsShape, Primitive, TagEncoding, Variants};
+use rustc_target::abi::{FieldsShape, Primitive, TagEncoding, VariantIdx, Variants};
 use tracing::debug;
 
 impl<'tcx> GotocCtx<'tcx> {
@@ -30,6 +30,22 @@ fn codegen_ret_unit(&mut self) -> Stmt {
         Stmt::ret(Some(var), Location::none())
     }
 
+    /// Return from the current function, returning the value in the MIR return place.
+    pub fn codegen_return(&mut self, loc: Location) -> Stmt {
+        let rty = self.current_fn().sig().unwrap().skip_binder().output();
+        if rty.is_unit() {
+            self.codegen_ret_unit()
+        } else {
+            let p = Place::from(mir::RETURN_PLACE);
+            let v = self.codegen_place(&p).goto_expr;
+            if self.place_ty(&p).is_bool() {
+                v.cast_to(Type::c_bool()).ret(loc)
+            } else {
+                v.ret(loc)
+            }
+        }
+    }
+
     pub fn codegen_terminator(&mut self, term: &Terminator<'tcx>) -> Stmt {
         let loc = self.codegen_span(&term.source_info.span);
         debug!("handling terminator {:?}", term);
@@ -41,20 +57,20 @@ pub fn codegen_terminator(&mut self, term: &Terminator<'tcx>) -> Stmt {
             TerminatorKind::SwitchInt { discr, switch_ty, targets } => {
                 self.codegen_switch_int(discr, switch_ty, targets)
             }
-            TerminatorKind::Resume => Stmt::assert_false("resume instruction", loc),
+            TerminatorKind::Resume => {
+                if self.is_panic_unwind() {
+                    self.codegen_resume(loc)
+                } else {
+                    Stmt::assert_false("resume instruction", loc)
+                }
+            }
             TerminatorKind::Abort => Stmt::assert_false("abort instruction", loc),
             TerminatorKind::Return => {
-                let rty = self.current_fn().sig().unwrap().skip_binder().output();
-                if rty.is_unit() {
-                    self.codegen_ret_unit()
+                let ret = self.codegen_return(loc.clone());
+                if self.is_leak_checked_fn() {
+                    Stmt::block(vec![self.codegen_leak_check_call(loc.clone()), ret], loc)
                 } else {
-                    let p = Place::from(mir::RETURN_PLACE);
-                    let v = self.codegen_place(&p).goto_expr;
-                    if self.place_ty(&p).is_bool() {
-                        v.cast_to(Type::c_bool()).ret(loc)
-                    } else {
-                        v.ret(loc)
-                    }
+                    ret
                 }
             }
             TerminatorKind::Unreachable => Stmt::block(
@@ -64,55 +80,64 @@ pub fn codegen_terminator(&mut self, term: &Terminator<'tcx>) -> Stmt {
                 ],
                 loc,
             ),
-            TerminatorKind::Drop { place, target, unwind: _ } => self.codegen_drop(place, target),
+            TerminatorKind::Drop { place, target, unwind } => {
+                self.codegen_drop(place, target, *unwind)
+            }
             TerminatorKind::DropAndReplace { .. } => {
                 unreachable!("this instruction is unreachable")
             }
-            TerminatorKind::Call { func, args, destination, .. } => {
-                self.codegen_funcall(func, args, destination, term.source_info.span)
+            TerminatorKind::Call { func, args, destination, cleanup, .. } => {
+                self.codegen_funcall(func, args, destination, *cleanup, term.source_info.span)
             }
-            TerminatorKind::Assert { cond, expected, msg, target, .. } => {
+            TerminatorKind::Assert { cond, expected, msg, target, cleanup } => {
                 let cond = {
                     let r = self.codegen_operand(cond);
                     if *expected { r } else { Expr::not(r) }
                 };
 
-                Stmt::block(
+                let msg = format!("{:?}", msg);
+                let on_failure = if self.is_panic_unwind() {
                     vec![
-                        cond.cast_to(Type::bool()).if_then_else(
-                            Stmt::goto(self.current_fn().find_label(target), loc.clone()),
-                            None,
-                            loc.clone(),
-                        ),
-                        Stmt::assert_false(&format!("{:?}", msg), loc.clone()),
+                        self.codegen_start_unwind(&msg, loc.clone()),
+                        self.codegen_unwind_check(*cleanup, loc.clone()),
+                    ]
+                } else {
+                    vec![
+                        Stmt::assert_false(&msg, loc.clone()),
                         Stmt::goto(self.current_fn().find_label(target), loc.clone()),
-                    ],
-                    loc,
-                )
+                    ]
+                };
+                let mut stmts = vec![cond.cast_to(Type::bool()).if_then_else(
+                    Stmt::goto(self.current_fn().find_label(target), loc.clone()),
+                    None,
+                    loc.clone(),
+                )];
+                stmts.extend(on_failure);
+                Stmt::block(stmts, loc)
             }
             TerminatorKind::Yield { .. }
             | TerminatorKind::GeneratorDrop
             | TerminatorKind::FalseEdge { .. }
             | TerminatorKind::FalseUnwind { .. } => unreachable!("we should not hit these cases"),
-            TerminatorKind::InlineAsm { .. } => self
-                .codegen_unimplemented(
-                    "InlineAsm",
-                    Type::empty(),
-                    loc.clone(),
-                    "https://github.com/model-checking/rmc/issues/2",
-                )
-                .as_stmt(loc),
+            TerminatorKind::InlineAsm { operands, destination, .. } => {
+                self.codegen_inline_asm(operands, *destination, loc)
+            }
         }
     }
 
-    // TODO: this function doesn't handle unwinding which begins if the destructor panics
-    // https://github.com/model-checking/rmc/issues/221
-    fn codegen_drop(&mut self, location: &Place<'tcx>, target: &BasicBlock) -> Stmt {
+    /// If the destructor panics and panics are modeled as unwinding, we continue at `unwind`.
+    fn codegen_drop(
+        &mut self,
+        location: &Place<'tcx>,
+        target: &BasicBlock,
+        unwind: Option<BasicBlock>,
+    ) -> Stmt {
         let loc_ty = self.place_ty(location);
         let drop_instance = Instance::resolve_drop_in_place(self.tcx, loc_ty);
         if let Some(hk) = self.hooks.hook_applies(self.tcx, drop_instance) {
-            let le = self.codegen_place(location).goto_expr;
-            hk.handle(self, drop_instance, vec![le], None, Some(*target), None)
+            // Like a call to `drop_in_place`, the hook gets a `*mut T` to the dropped place.
+            let place_ref = self.codegen_rvalue_ref(location, self.tcx.mk_mut_ptr(loc_ty));
+            hk.handle(self, drop_instance, vec![place_ref], None, Some(*target), None)
         } else {
             let drop_implementation = match drop_instance.def {
                 InstanceDef::DropGlue(_, None) => {
@@ -120,11 +145,16 @@ fn codegen_drop(&mut self, location: &Place<'tcx>, target: &BasicBlock) -> Stmt
                     Stmt::skip(Location::none())
                 }
                 _ => {
+                    // `drop_in_place` takes a `*mut T` to the dropped place. We build it like
+                    // `&raw mut place`: if the place is unsized but only a part of the object
+                    // behind a fat pointer (e.g. the `dyn Trait` field of an `ArcInner`), the
+                    // metadata comes from that fat pointer, but the data pointer must point to
+                    // the place itself.
+                    let place_ref = self.codegen_rvalue_ref(location, self.tcx.mk_mut_ptr(loc_ty));
                     match loc_ty.kind() {
                         ty::Dynamic(..) => {
                             // Virtual drop via a vtable lookup
-                            let trait_fat_ptr =
-                                self.codegen_place(location).fat_ptr_goto_expr.unwrap();
+                            let trait_fat_ptr = place_ref;
 
                             // Pull the function off of the fat pointer's vtable pointer
                             let vtable_ref =
@@ -154,41 +184,16 @@ fn codegen_drop(&mut self, location: &Place<'tcx>, target: &BasicBlock) -> Stmt
                             // Non-virtual, direct drop call
                             assert!(!matches!(drop_instance.def, InstanceDef::Virtual(_, _)));
 
-                            let func = self.codegen_func_expr(drop_instance, None);
-                            let place = self.codegen_place(location);
-                            let arg = if let Some(fat_ptr) = place.fat_ptr_goto_expr {
-                                // Drop takes the fat pointer if it exists
-                                fat_ptr
-                            } else {
-                                place.goto_expr.address_of()
-                            };
                             // The only argument should be a self reference
-                            let args = vec![arg];
-
-                            // We have a known issue where nested Arc and Mutex objects result in
-                            // drop_in_place call implementations that fail to typecheck. Skipping
-                            // drop entirely causes unsound verification results in common cases
-                            // like vector extend, so for now, add a sound special case workaround
-                            // for calls that fail the typecheck.
-                            // https://github.com/model-checking/rmc/issues/426
-                            // Unblocks: https://github.com/model-checking/rmc/issues/435
-                            if Expr::typecheck_call(&func, &args) {
-                                func.call(args)
-                            } else {
-                                self.codegen_unimplemented(
-                                    format!("drop_in_place call for {:?}", func).as_str(),
-                                    func.typ().return_type().unwrap().clone(),
-                                    Location::none(),
-                                    "https://github.com/model-checking/rmc/issues/426",
-                                )
-                            }
-                            .as_stmt(Location::none())
+                            let func = self.codegen_func_expr(drop_instance, None);
+                            func.call(vec![place_ref]).as_stmt(Location::none())
                         }
                     }
                 }
             };
+            let unwind_check = self.codegen_unwind_check(unwind, Location::none());
             let goto_target = Stmt::goto(self.current_fn().find_label(target), Location::none());
-            let block = vec![drop_implementation, goto_target];
+            let block = vec![drop_implementation, unwind_check, goto_target];
             Stmt::block(block, Location::none())
         }
     }
@@ -294,6 +299,7 @@ fn codegen_funcall(
         func: &Operand<'tcx>,
         args: &[Operand<'tcx>],
         destination: &Option<(Place<'tcx>, BasicBlock)>,
+        cleanup: Option<BasicBlock>,
         span: Span,
     ) -> Stmt {
         let loc = self.codegen_span(&span);
@@ -323,7 +329,7 @@ fn codegen_funcall(
                 }
 
                 if let Some(hk) = self.hooks.hook_applies(self.tcx, instance) {
-                    return hk.handle(
+                    let hook_stmt = hk.handle(
                         self,
                         instance,
                         fargs,
@@ -331,6 +337,10 @@ fn codegen_funcall(
                         destination.map(|t| t.1),
                         Some(span),
                     );
+                    // Hooks that return jump to the target block themselves, so we only reach
+                    // the unwind check after a hook that started unwinding (i.e. a panic).
+                    let unwind_check = self.codegen_unwind_check(cleanup, loc.clone());
+                    return Stmt::block(vec![hook_stmt, unwind_check], loc);
                 }
 
                 if destination.is_none() {
@@ -344,7 +354,7 @@ fn codegen_funcall(
 
                 let (p, target) = destination.unwrap();
 
-                let mut stmts: Vec<Stmt> = match instance.def {
+                let call: Vec<Stmt> = match instance.def {
                     // Here an empty drop glue is invoked; we just ignore it.
                     InstanceDef::DropGlue(_, None) => {
                         return Stmt::goto(self.current_fn().find_label(&target), Location::none());
@@ -380,6 +390,9 @@ fn codegen_funcall(
                     | InstanceDef::ReifyShim(..)
                     | InstanceDef::ClosureOnceShim { .. }
                     | InstanceDef::CloneShim(..) => {
+                        if instance.def.requires_caller_location(self.tcx) {
+                            fargs.push(self.codegen_caller_location());
+                        }
                         let func_exp = self.codegen_operand(func);
                         vec![
                             self.codegen_expr_to_place(&p, func_exp.call(fargs))
@@ -387,18 +400,33 @@ fn codegen_funcall(
                         ]
                     }
                 };
-                stmts.push(Stmt::goto(self.current_fn().find_label(&target), loc.clone()));
+                let call = self.codegen_track_call(
+                    Some(instance),
+                    Stmt::block(call, loc.clone()),
+                    loc.clone(),
+                );
+                let stmts = vec![
+                    call,
+                    self.codegen_unwind_check(cleanup, loc.clone()),
+                    Stmt::goto(self.current_fn().find_label(&target), loc.clone()),
+                ];
                 return Stmt::block(stmts, loc);
             }
             // Function call through a pointer
             ty::FnPtr(_) => {
                 let (p, target) = destination.unwrap();
-                let func_expr = self.codegen_operand(func).dereference();
+                let fn_ptr = self.codegen_operand(func);
+                let call = if self.vtable_ctx.emit_fn_ptr_restrictions {
+                    self.codegen_restricted_fn_ptr_call(func, fn_ptr, fargs)
+                } else {
+                    fn_ptr.dereference().call(fargs)
+                };
+                let call = self.codegen_expr_to_place(&p, call).with_location(loc.clone());
                 // Actually generate the function call and return.
                 return Stmt::block(
                     vec![
-                        self.codegen_expr_to_place(&p, func_expr.call(fargs))
-                            .with_location(loc.clone()),
+                        self.codegen_track_call(None, call, loc.clone()),
+                        self.codegen_unwind_check(cleanup, loc.clone()),
                         Stmt::goto(self.current_fn().find_label(&target), loc.clone()),
                     ],
                     loc,
@@ -452,6 +480,67 @@ fn codegen_virtual_funcall(
         vec![assert_nonnull, self.codegen_expr_to_place(place, call).with_location(loc.clone())]
     }
 
+    /// Sets the discriminant of the enum or generator `place` to the one of `variant_index`.
+    pub fn codegen_set_discriminant(
+        &mut self,
+        place: &Place<'tcx>,
+        variant_index: VariantIdx,
+    ) -> Stmt {
+        // this requires place points to an enum type.
+        let pt = self.place_ty(place);
+        let (def, _) = match pt.kind() {
+            ty::Adt(def, substs) => (def, substs),
+            ty::Generator(..) => {
+                // the discriminant of a generator is the index of its variant
+                let discr_t = self.codegen_enum_discr_typ(pt);
+                let discr = Expr::int_constant(variant_index.as_u32(), self.codegen_ty(discr_t));
+                let generator = self.codegen_place(place).goto_expr;
+                return self
+                    .codegen_generator_prefix(generator)
+                    .member("case", &self.symbol_table)
+                    .assign(discr, Location::none());
+            }
+            _ => unreachable!(),
+        };
+        let layout = self.layout_of(pt);
+        match &layout.variants {
+            Variants::Single { .. } => Stmt::skip(Location::none()),
+            Variants::Multiple { tag, tag_encoding, .. } => match tag_encoding {
+                TagEncoding::Direct => {
+                    let discr = def.discriminant_for_variant(self.tcx, variant_index);
+                    let discr_t = self.codegen_enum_discr_typ(pt);
+                    let discr = Expr::int_constant(discr.val, self.codegen_ty(discr_t));
+                    self.codegen_place(place)
+                        .goto_expr
+                        .member("case", &self.symbol_table)
+                        .assign(discr, Location::none())
+                }
+                TagEncoding::Niche { dataful_variant, niche_variants, niche_start } => {
+                    if *dataful_variant != variant_index {
+                        let offset = match &layout.fields {
+                            FieldsShape::Arbitrary { offsets, .. } => offsets[0].bytes_usize(),
+                            _ => unreachable!("niche encoding must have arbitrary fields"),
+                        };
+                        let discr_ty = self.codegen_enum_discr_typ(pt);
+                        let discr_ty = self.codegen_ty(discr_ty);
+                        let niche_value = variant_index.as_u32() - niche_variants.start().as_u32();
+                        let niche_value = (niche_value as u128).wrapping_add(*niche_start);
+                        let value = if niche_value == 0 && tag.value == Primitive::Pointer {
+                            discr_ty.null()
+                        } else {
+                            Expr::int_constant(niche_value, discr_ty.clone())
+                        };
+                        let place = self.codegen_place(place).goto_expr;
+                        self.codegen_get_niche(place, offset, discr_ty)
+                            .assign(value, Location::none())
+                    } else {
+                        Stmt::skip(Location::none())
+                    }
+                }
+            },
+        }
+    }
+
     /// A place is similar to the C idea of a LHS. For example, the returned value of a function call is stored to a place.
     /// If the place is unit (i.e. the statement value is not stored anywhere), then we can just turn it directly to a statement.
     /// Otherwise, we assign the value of the expression to the place.
@@ -463,7 +552,7 @@ pub fn codegen_expr_to_place(&mut self, p: &Place<'tcx>, e: Expr) -> Stmt {
         }
     }
 
-    pub fn codegen_panic(&self, span: Option<Span>, fargs: Vec<Expr>) -> Stmt {
+    pub fn codegen_panic(&mut self, span: Option<Span>, fargs: Vec<Expr>) -> Stmt {
         // CBMC requires that the argument to the assertion must be a string constant.
         // If there is one in the MIR, use it; otherwise, explain that we can't.
         assert!(!fargs.is_empty(), "Panic requires a string message");
@@ -471,7 +560,12 @@ pub fn codegen_panic(&self, span: Option<Span>, fargs: Vec<Expr>) -> Stmt {
             "This is a placeholder message; RMC doesn't support message formatted at runtime",
         ));
 
-        self.codegen_fatal_error(&msg, span)
+        if self.is_panic_unwind() {
+            let loc = self.codegen_caller_span(&span);
+            self.codegen_start_unwind(&msg, loc)
+        } else {
+            self.codegen_fatal_error(&msg, span)
+        }
     }
 
     // Generate code for fatal error which should trigger an assertion failure and abort the
@@ -513,78 +607,20 @@ pub fn codegen_statement(&mut self, stmt: &Statement<'tcx>) -> Stmt {
                 }
             }
             StatementKind::SetDiscriminant { place, variant_index } => {
-                // this requires place points to an enum type.
-                let pt = self.place_ty(place);
-                let (def, _) = match pt.kind() {
-                    ty::Adt(def, substs) => (def, substs),
-                    ty::Generator(..) => {
-                        return self
-                            .codegen_unimplemented(
-                                "ty::Generator",
-                                Type::code(vec![], Type::empty()),
-                                Location::none(),
-                                "https://github.com/model-checking/rmc/issues/416",
-                            )
-                            .as_stmt(Location::none());
-                    }
-                    _ => unreachable!(),
-                };
-                let layout = self.layout_of(pt);
-                match &layout.variants {
-                    Variants::Single { .. } => Stmt::skip(Location::none()),
-                    Variants::Multiple { tag, tag_encoding, .. } => match tag_encoding {
-                        TagEncoding::Direct => {
-                            let discr = def.discriminant_for_variant(self.tcx, *variant_index);
-                            let discr_t = self.codegen_enum_discr_typ(pt);
-                            let discr = Expr::int_constant(discr.val, self.codegen_ty(discr_t));
-                            self.codegen_place(place)
-                                .goto_expr
-                                .member("case", &self.symbol_table)
-                                .assign(discr, Location::none())
-                        }
-                        TagEncoding::Niche { dataful_variant, niche_variants, niche_start } => {
-                            if dataful_variant != variant_index {
-                                let offset = match &layout.fields {
-                                    FieldsShape::Arbitrary { offsets, .. } => {
-                                        offsets[0].bytes_usize()
-                                    }
-                                    _ => unreachable!("niche encoding must have arbitrary fields"),
-                                };
-                                let discr_ty = self.codegen_enum_discr_typ(pt);
-                                let discr_ty = self.codegen_ty(discr_ty);
-                                let niche_value =
-                                    variant_index.as_u32() - niche_variants.start().as_u32();
-                                let niche_value = (niche_value as u128).wrapping_add(*niche_start);
-                                let value = if niche_value == 0 && tag.value == Primitive::Pointer {
-                                    discr_ty.null()
-                                } else {
-                                    Expr::int_constant(niche_value, discr_ty.clone())
-                                };
-                                let place = self.codegen_place(place).goto_expr;
-                                self.codegen_get_niche(place, offset, discr_ty)
-                                    .assign(value, Location::none())
-                            } else {
-                                Stmt::skip(Location::none())
-                            }
-                        }
-                    },
-                }
+                self.codegen_set_discriminant(place, *variant_index)
             }
             StatementKind::StorageLive(_) => Stmt::skip(Location::none()), // TODO: fix me
             StatementKind::StorageDead(_) => Stmt::skip(Location::none()), // TODO: fix me
-            StatementKind::LlvmInlineAsm(_) => self
-                .codegen_unimplemented(
-                    "InlineAsm",
-                    Type::empty(),
-                    Location::none(),
-                    "https://github.com/model-checking/rmc/issues/2",
-                )
-                .as_stmt(Location::none()),
+            StatementKind::LlvmInlineAsm(asm) => {
+                let loc = self.codegen_span(&stmt.source_info.span);
+                self.codegen_llvm_inline_asm(asm, loc)
+            }
             StatementKind::CopyNonOverlapping(box mir::CopyNonOverlapping {
                 ref src,
                 ref dst,
                 ref count,
             }) => {
+                let loc = self.codegen_span(&stmt.source_info.span);
                 let src = self.codegen_operand(src).cast_to(Type::void_pointer());
                 let dst = self.codegen_operand(dst);
                 let count = self.codegen_operand(count);
@@ -592,6 +628,13 @@ pub fn codegen_statement(&mut self, stmt: &Statement<'tcx>) -> Stmt {
                 let sz = Expr::int_constant(sz, Type::size_t());
                 let n = sz.mul(count);
                 let dst = dst.cast_to(Type::void_pointer());
+                let nonoverlapping_check = self.codegen_nonoverlapping_check(
+                    src.clone(),
+                    dst.clone(),
+                    n.clone(),
+                    "copy_nonoverlapping",
+                    loc,
+                );
                 let e = BuiltinFn::Memcpy.call(vec![dst, src, n.clone()], Location::none());
 
                 // The C implementation of memcpy does not allow an invalid pointer for
@@ -600,11 +643,17 @@ pub fn codegen_statement(&mut self, stmt: &Statement<'tcx>) -> Stmt {
                 // the empty string; CBMC will fail on passing a reference to empty
                 // string unless we codegen this zero check.
                 // https://llvm.org/docs/LangRef.html#llvm-memcpy-intrinsic
-                Stmt::if_then_else(
-                    n.is_zero().not(),
-                    e.as_stmt(Location::none()),
-                    None,
-                    Location::none(),
+                Stmt::block(
+                    vec![
+                        nonoverlapping_check,
+                        Stmt::if_then_else(
+                            n.is_zero().not(),
+                            e.as_stmt(Location::none()),
+                            None,
+                            Location::none(),
+                        ),
+                    ],
+                    loc,
                 )
             }
             StatementKind::FakeRead(_)
@@ -4,7 +4,7 @@
 //! This file contains functions related to codegenning MIR static variables into gotoc
 
 use crate::GotocCtx;
-use cbmc::goto_program::Symbol;
+use cbmc::goto_program::{Expr, Symbol};
 use rustc_hir::def_id::DefId;
 use rustc_middle::mir::mono::MonoItem;
 use tracing::debug;
@@ -23,7 +23,22 @@ pub fn declare_static(&mut self, def_id: DefId, item: MonoItem<'tcx>) {
         let typ = self.codegen_ty(self.tcx.type_of(def_id));
         let span = self.tcx.def_span(def_id);
         let location = self.codegen_span(&span);
-        let symbol = Symbol::static_variable(symbol_name.to_string(), symbol_name, typ, location);
+        let symbol = Symbol::static_variable(symbol_name.to_string(), symbol_name, typ, location)
+            .with_is_thread_local(self.tcx.is_thread_local_static(def_id));
         self.symbol_table.insert(symbol);
     }
+
+    /// A thread-local static is a static variable with the `is_thread_local` flag set, so that
+    /// CBMC gives each thread its own copy.
+    /// Thread-local statics from other crates (e.g. the ones behind `thread_local!` in std) are
+    /// not mono items of the current crate, so we declare and codegen them on first use.
+    pub fn codegen_thread_local_ref(&mut self, def_id: DefId) -> Expr {
+        let item = MonoItem::Static(def_id);
+        let symbol_name = item.symbol_name(self.tcx).to_string();
+        if self.symbol_table.lookup(&symbol_name).is_none() {
+            self.declare_static(def_id, item);
+            self.codegen_static(def_id, item);
+        }
+        self.symbol_table.lookup(&symbol_name).unwrap().to_expr().address_of()
+    }
 }
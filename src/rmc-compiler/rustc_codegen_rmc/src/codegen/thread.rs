@@ -0,0 +1,344 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module models threads and mutexes with CBMC's thread primitives, so that CBMC explores
+//! every interleaving of the threads under sequential consistency.
+//!
+//! `std::thread::spawn(f)` runs `f` in a new thread. The spawned thread stores the value returned
+//! by `f` in a heap-allocated `ThreadState`, and then sets its `finished` flag. The `JoinHandle`
+//! returned by `spawn` only holds a pointer to this state, and `JoinHandle::join` waits for the
+//! flag to be set before returning the stored value.
+//!
+//! Dropping a `JoinHandle` instead detaches its thread, which sets the `detached` flag of the
+//! state. Whichever of the thread and the drop comes last drops the stored value and frees the
+//! state, like the last reference to the `Packet` of the standard library does. The state is
+//! allocated with `malloc` rather than an allocator shim, so it is not tracked by leak checks.
+//!
+//! The standard library implements `std::sync::Mutex` on top of a boxed `pthread_mutex_t`. We
+//! only use the first 32 bits of a `pthread_mutex_t`, which are zero if and only if the mutex is
+//! unlocked. Waiting for a lock is modeled as an assumption, so paths that deadlock are dropped.
+//!
+//! Atomic intrinsics are executed in atomic blocks, see `intrinsic.rs`.
+
+use crate::GotocCtx;
+use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Type};
+use rustc_hir::LangItem;
+use rustc_middle::mir::{BasicBlock, Field, Place};
+use rustc_middle::ty::layout::LayoutOf;
+use rustc_middle::ty::{self, Instance, InstanceDef, Ty};
+use rustc_target::abi::VariantIdx;
+
+const DETACHED_FIELD_NAME: &str = "detached";
+const FINISHED_FIELD_NAME: &str = "finished";
+const RESULT_FIELD_NAME: &str = "result";
+
+/// The error that `pthread_mutex_trylock` returns if the mutex is already locked.
+const EBUSY: i32 = 16;
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// The state shared by a thread that returns a `T` and its `JoinHandle<T>`:
+    /// ```c
+    /// struct ThreadState<T> { bool finished; bool detached; T result; };
+    /// ```
+    fn codegen_thread_state_typ(&mut self, t: Ty<'tcx>) -> Type {
+        let name = format!("{}::ThreadState", self.ty_mangled_name(t));
+        let pretty_name = format!("ThreadState<{}>", self.ty_pretty_name(t));
+        self.ensure_struct(name, Some(pretty_name), |ctx, _| {
+            vec![
+                Type::datatype_component(FINISHED_FIELD_NAME, Type::c_bool()),
+                Type::datatype_component(DETACHED_FIELD_NAME, Type::c_bool()),
+                Type::datatype_component(RESULT_FIELD_NAME, ctx.codegen_ty(t)),
+            ]
+        })
+    }
+
+    /// The instance of `<F as FnOnce<()>>::call_once`, which runs the closure of a new thread.
+    /// `std::thread::spawn` calls it as well, so it is always part of the codegen units.
+    fn fn_once_call_once(&self, f_ty: Ty<'tcx>) -> Instance<'tcx> {
+        let fn_once = self.tcx.require_lang_item(LangItem::FnOnce, None);
+        let call_once = self
+            .tcx
+            .associated_items(fn_once)
+            .in_definition_order()
+            .find(|item| item.kind == ty::AssocKind::Fn)
+            .unwrap()
+            .def_id;
+        let substs = self.tcx.mk_substs_trait(f_ty, &[self.tcx.mk_unit().into()]);
+        Instance::resolve(self.tcx, ty::ParamEnv::reveal_all(), call_once, substs).unwrap().unwrap()
+    }
+
+    /// `std::thread::spawn::<F, T>(f)` is modeled as:
+    /// ```c
+    /// struct ThreadState<T> *state = malloc(sizeof(struct ThreadState<T>));
+    /// state->finished = false;
+    /// state->detached = false;
+    /// F closure = f;
+    /// __CPROVER_ASYNC_1: {
+    ///     state->result = <F as FnOnce<()>>::call_once(closure);
+    ///     bool detached;
+    ///     __CPROVER_atomic_begin();
+    ///     state->finished = true;
+    ///     detached = state->detached;
+    ///     __CPROVER_atomic_end();
+    ///     if (detached) { drop_in_place(&state->result); free(state); }
+    /// }
+    /// *(struct ThreadState<T> **)&handle = state;
+    /// ```
+    pub fn codegen_thread_spawn(
+        &mut self,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let f_ty = instance.substs.type_at(0);
+        let t = instance.substs.type_at(1);
+        let state_typ = self.codegen_thread_state_typ(t);
+        let state = self.gen_temp_variable(state_typ.clone().to_pointer(), loc).to_expr();
+        let size = Expr::int_constant(state_typ.sizeof(&self.symbol_table), Type::size_t());
+        let alloc = BuiltinFn::Malloc.call(vec![size], loc).cast_to(state_typ.clone().to_pointer());
+        let finished = state.clone().dereference().member(FINISHED_FIELD_NAME, &self.symbol_table);
+        let detached = state.clone().dereference().member(DETACHED_FIELD_NAME, &self.symbol_table);
+        let mut stmts = vec![
+            Stmt::decl(state.clone(), Some(alloc), loc),
+            finished.clone().assign(Expr::c_false(), loc),
+            detached.clone().assign(Expr::c_false(), loc),
+        ];
+
+        // The new thread starts with a copy of the local variables of the spawning thread, so we
+        // move the closure to a temporary that the spawning thread does not modify afterwards.
+        // There is no argument if `F` is a function item.
+        let mut args = vec![];
+        if let Some(f) = fargs.pop() {
+            let closure = self.gen_temp_variable(f.typ().clone(), loc).to_expr();
+            stmts.push(Stmt::decl(closure.clone(), Some(f), loc));
+            args.push(closure);
+        }
+        let call_once = self.fn_once_call_once(f_ty);
+        let call = self.codegen_func_expr(call_once, None).call(args);
+        let run = if self.layout_of(t).is_zst() {
+            call.as_stmt(loc)
+        } else {
+            let result = state.clone().dereference().member(RESULT_FIELD_NAME, &self.symbol_table);
+            result.assign(call, loc)
+        };
+        let was_detached = self.gen_temp_variable(Type::c_bool(), loc).to_expr();
+        let finish = Stmt::atomic_block(
+            vec![finished.assign(Expr::c_true(), loc), was_detached.clone().assign(detached, loc)],
+            loc,
+        );
+        let release = self.codegen_thread_state_release(t, state.clone(), loc);
+        let body = Stmt::block(
+            vec![
+                run,
+                Stmt::decl(was_detached.clone(), None, loc),
+                finish,
+                was_detached.cast_to(Type::bool()).if_then_else(release, None, loc),
+            ],
+            loc,
+        );
+        stmts.push(Stmt::start_thread(body, loc));
+
+        let handle = self.codegen_place(p).goto_expr;
+        stmts.push(handle.reinterpret_cast(state_typ.to_pointer()).assign(state, loc));
+        stmts.push(Stmt::goto(self.current_fn().find_label(&target), loc));
+        Stmt::block(stmts, loc)
+    }
+
+    /// `JoinHandle::<T>::join(handle)` is modeled as:
+    /// ```c
+    /// struct ThreadState<T> *state = *(struct ThreadState<T> **)&handle;
+    /// __CPROVER_assume(state->finished);
+    /// ret = Ok(state->result);
+    /// free(state);
+    /// ```
+    /// Panics abort, so a thread that finished never returns an `Err`.
+    pub fn codegen_thread_join(
+        &mut self,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let t = instance.substs.type_at(0);
+        let state_typ = self.codegen_thread_state_typ(t);
+        let arg = fargs.remove(0);
+        let handle = self.gen_temp_variable(arg.typ().clone(), loc).to_expr();
+        let state = handle.clone().reinterpret_cast(state_typ.to_pointer());
+        let finished = state.clone().dereference().member(FINISHED_FIELD_NAME, &self.symbol_table);
+        let mut stmts = vec![
+            Stmt::decl(handle, Some(arg), loc),
+            Stmt::assume(finished.cast_to(Type::bool()), loc),
+        ];
+
+        // `Ok` is the first variant of `Result`.
+        let ok = VariantIdx::from_u32(0);
+        if !self.layout_of(t).is_zst() {
+            let adt_def = self.place_ty(p).ty_adt_def().unwrap();
+            let ok_place = self.tcx.mk_place_downcast(*p, adt_def, ok);
+            let ok_field = self.tcx.mk_place_field(ok_place, Field::new(0), t);
+            let result = state.clone().dereference().member(RESULT_FIELD_NAME, &self.symbol_table);
+            stmts.push(self.codegen_place(&ok_field).goto_expr.assign(result, loc));
+        }
+        stmts.push(self.codegen_set_discriminant(p, ok));
+        stmts.push(
+            BuiltinFn::Free.call(vec![state.cast_to(Type::void_pointer())], loc).as_stmt(loc),
+        );
+        stmts.push(Stmt::goto(self.current_fn().find_label(&target), loc));
+        Stmt::block(stmts, loc)
+    }
+
+    /// Dropping a `JoinHandle<T>` through `drop_in_place(handle)` detaches its thread:
+    /// ```c
+    /// struct ThreadState<T> *state = *(struct ThreadState<T> **)handle;
+    /// bool finished;
+    /// __CPROVER_atomic_begin();
+    /// state->detached = true;
+    /// finished = state->finished;
+    /// __CPROVER_atomic_end();
+    /// if (finished) { drop_in_place(&state->result); free(state); }
+    /// ```
+    /// If the thread is still running, it drops its result and frees the state when it finishes.
+    pub fn codegen_thread_detach(
+        &mut self,
+        instance: Instance<'tcx>,
+        mut fargs: Vec<Expr>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let handle_ty = match instance.def {
+            InstanceDef::DropGlue(_, Some(ty)) => ty,
+            _ => unreachable!("expected the drop glue of JoinHandle, got {:?}", instance),
+        };
+        let t = match handle_ty.kind() {
+            ty::Adt(_, substs) => substs.type_at(0),
+            _ => unreachable!("JoinHandle is a struct"),
+        };
+        let state_typ = self.codegen_thread_state_typ(t);
+        let handle = fargs.remove(0);
+        let state = self.gen_temp_variable(state_typ.clone().to_pointer(), loc).to_expr();
+        let state_ptr = handle.cast_to(state_typ.to_pointer().to_pointer()).dereference();
+        let finished = self.gen_temp_variable(Type::c_bool(), loc).to_expr();
+        let detach = Stmt::atomic_block(
+            vec![
+                state
+                    .clone()
+                    .dereference()
+                    .member(DETACHED_FIELD_NAME, &self.symbol_table)
+                    .assign(Expr::c_true(), loc),
+                finished.clone().assign(
+                    state.clone().dereference().member(FINISHED_FIELD_NAME, &self.symbol_table),
+                    loc,
+                ),
+            ],
+            loc,
+        );
+        let release = self.codegen_thread_state_release(t, state.clone(), loc);
+        Stmt::block(
+            vec![
+                Stmt::decl(state, Some(state_ptr), loc),
+                Stmt::decl(finished.clone(), None, loc),
+                detach,
+                finished.cast_to(Type::bool()).if_then_else(release, None, loc),
+                Stmt::goto(self.current_fn().find_label(&target), loc),
+            ],
+            loc,
+        )
+    }
+
+    /// Drops the result of a detached thread that finished, and frees its state:
+    /// ```c
+    /// drop_in_place(&state->result);
+    /// free(state);
+    /// ```
+    fn codegen_thread_state_release(&mut self, t: Ty<'tcx>, state: Expr, loc: Location) -> Stmt {
+        let mut stmts = vec![];
+        let drop_instance = Instance::resolve_drop_in_place(self.tcx, t);
+        if !matches!(drop_instance.def, InstanceDef::DropGlue(_, None)) {
+            let result = state.clone().dereference().member(RESULT_FIELD_NAME, &self.symbol_table);
+            let drop_fn = self.codegen_func_expr(drop_instance, None);
+            stmts.push(drop_fn.call(vec![result.address_of()]).as_stmt(loc));
+        }
+        stmts.push(
+            BuiltinFn::Free.call(vec![state.cast_to(Type::void_pointer())], loc).as_stmt(loc),
+        );
+        Stmt::block(stmts, loc)
+    }
+
+    /// `MovableMutex::new()` boxes a new `pthread_mutex_t`. We allocate it unlocked.
+    pub fn codegen_movable_mutex_new(
+        &mut self,
+        p: &Place<'tcx>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        // `MovableMutex` is a newtype around a `Box` of the platform mutex.
+        let boxed_ty = match self.place_ty(p).kind() {
+            ty::Adt(def, substs) => def.non_enum_variant().fields[0].ty(self.tcx, substs),
+            _ => unreachable!("MovableMutex is a struct"),
+        };
+        let size = self.layout_of(boxed_ty.boxed_ty()).size.bytes();
+        let alloc = BuiltinFn::Malloc.call(vec![Expr::int_constant(size, Type::size_t())], loc);
+        let mutex = self.gen_temp_variable(Type::void_pointer(), loc).to_expr();
+        let movable_mutex = self.codegen_place(p).goto_expr;
+        Stmt::block(
+            vec![
+                Stmt::decl(mutex.clone(), Some(alloc), loc),
+                Self::pthread_mutex_lock_word(mutex.clone())
+                    .assign(Type::unsigned_int(32).zero(), loc),
+                movable_mutex.reinterpret_cast(Type::void_pointer()).assign(mutex, loc),
+                Stmt::goto(self.current_fn().find_label(&target), loc),
+            ],
+            loc,
+        )
+    }
+
+    fn pthread_mutex_lock_word(mutex: Expr) -> Expr {
+        mutex.cast_to(Type::unsigned_int(32).to_pointer()).dereference()
+    }
+
+    /// `pthread_mutex_lock(mutex)`, `pthread_mutex_trylock(mutex)` and
+    /// `pthread_mutex_unlock(mutex)`. Each of them checks and updates the lock in a single
+    /// atomic step.
+    pub fn codegen_pthread_mutex_op(
+        &mut self,
+        name: &str,
+        mut fargs: Vec<Expr>,
+        p: &Place<'tcx>,
+        target: BasicBlock,
+        loc: Location,
+    ) -> Stmt {
+        let lock = Self::pthread_mutex_lock_word(fargs.remove(0));
+        let lock_typ = lock.typ().clone();
+        let ret_typ = self.codegen_ty(self.place_ty(p));
+        let ok = self.codegen_expr_to_place(p, ret_typ.zero());
+        let op = match name {
+            "pthread_mutex_lock" => Stmt::atomic_block(
+                vec![
+                    Stmt::assume(lock.clone().is_zero(), loc),
+                    lock.assign(lock_typ.one(), loc),
+                    ok,
+                ],
+                loc,
+            ),
+            "pthread_mutex_trylock" => {
+                let busy = Expr::int_constant(EBUSY, ret_typ);
+                Stmt::atomic_block(
+                    vec![lock.clone().is_zero().if_then_else(
+                        Stmt::block(vec![lock.assign(lock_typ.one(), loc), ok], loc),
+                        Some(self.codegen_expr_to_place(p, busy)),
+                        loc,
+                    )],
+                    loc,
+                )
+            }
+            "pthread_mutex_unlock" => {
+                Stmt::atomic_block(vec![lock.assign(lock_typ.zero(), loc), ok], loc)
+            }
+            _ => unreachable!("unexpected pthread mutex operation {}", name),
+        };
+        Stmt::block(vec![op, Stmt::goto(self.current_fn().find_label(&target), loc)], loc)
+    }
+}
@@ -6,6 +6,7 @@
 use cbmc::{btree_map, NO_PRETTY_NAME};
 use cbmc::{InternString, InternedString};
 use rustc_ast::ast::Mutability;
+use rustc_hir::{LangItem, Unsafety};
 use rustc_index::vec::IndexVec;
 use rustc_middle::mir::{HasLocalDecls, Local, Operand, Place, Rvalue};
 use rustc_middle::ty::layout::LayoutOf;
@@ -37,6 +38,10 @@
 const UNIT_TYPE_EMPTY_STRUCT_NAME: &str = "Unit";
 pub const FN_RETURN_VOID_VAR_NAME: &str = "VoidUnit";
 
+/// The name of the union member of a generator that holds its upvars and its tag.
+/// See `codegen_ty_generator` for more details.
+const GENERATOR_PREFIX_NAME: &str = "prefix";
+
 /// Map the never i.e. `!` type to an empty struct.
 /// The never type can appear as a function argument, e.g. in library/core/src/num/error.rs
 const NEVER_TYPE_EMPTY_STRUCT_NAME: &str = "Never";
@@ -197,6 +202,51 @@ fn closure_sig(
         self.sig_with_closure_untupled(sig)
     }
 
+    /// The signature of the function that resumes a generator, i.e.,
+    /// `fn(Pin<&mut G>, R) -> GeneratorState<Y, Ret>`.
+    /// This follows `fn_sig_for_fn_abi` in rustc_middle/src/ty/layout.rs.
+    fn generator_sig(
+        &self,
+        ty: Ty<'tcx>,
+        substs: ty::subst::SubstsRef<'tcx>,
+    ) -> ty::PolyFnSig<'tcx> {
+        let sig = substs.as_generator().poly_sig();
+
+        // Like for closures, the environment is passed by reference, so we need to add a region
+        // for it to the bound variables.
+        let bound_vars = self.tcx.mk_bound_variable_kinds(
+            sig.bound_vars().iter().chain(iter::once(ty::BoundVariableKind::Region(ty::BrEnv))),
+        );
+        let br = ty::BoundRegion {
+            var: ty::BoundVar::from_usize(bound_vars.len() - 1),
+            kind: ty::BoundRegionKind::BrEnv,
+        };
+        let env_region = ty::ReLateBound(ty::INNERMOST, br);
+        let env_ty = self.tcx.mk_mut_ref(self.tcx.mk_region(env_region), ty);
+
+        let pin_did = self.tcx.require_lang_item(LangItem::Pin, None);
+        let pin_adt_ref = self.tcx.adt_def(pin_did);
+        let pin_substs = self.tcx.intern_substs(&[env_ty.into()]);
+        let env_ty = self.tcx.mk_adt(pin_adt_ref, pin_substs);
+
+        let sig = sig.skip_binder();
+        let state_did = self.tcx.require_lang_item(LangItem::GeneratorState, None);
+        let state_adt_ref = self.tcx.adt_def(state_did);
+        let state_substs = self.tcx.intern_substs(&[sig.yield_ty.into(), sig.return_ty.into()]);
+        let ret_ty = self.tcx.mk_adt(state_adt_ref, state_substs);
+
+        ty::Binder::bind_with_vars(
+            self.tcx.mk_fn_sig(
+                [env_ty, sig.resume_ty].iter(),
+                &ret_ty,
+                false,
+                Unsafety::Normal,
+                Abi::Rust,
+            ),
+            bound_vars,
+        )
+    }
+
     pub fn fn_sig_of_instance(&self, instance: Instance<'tcx>) -> Option<ty::PolyFnSig<'tcx>> {
         let fntyp = instance.ty(self.tcx, ty::ParamEnv::reveal_all());
         self.monomorphize(match fntyp.kind() {
@@ -211,7 +261,7 @@ pub fn fn_sig_of_instance(&self, instance: Instance<'tcx>) -> Option<ty::PolyFnS
                 }
                 Some(sig)
             }
-            ty::Generator(_def_id, _substs, _movability) => None,
+            ty::Generator(_def_id, substs, _movability) => Some(self.generator_sig(fntyp, substs)),
             _ => unreachable!("Can't get function signature of type: {:?}", fntyp),
         })
     }
@@ -548,7 +598,7 @@ fn codegen_ty_inner(&mut self, ty: Ty<'tcx>) -> Type {
             }
             ty::FnPtr(sig) => self.codegen_function_sig(*sig).to_pointer(),
             ty::Closure(_, subst) => self.codegen_ty_closure(ty, subst),
-            ty::Generator(_, subst, _) => self.codegen_ty_generator(subst),
+            ty::Generator(..) => self.codegen_ty_generator(ty),
             ty::Never => {
                 self.ensure_struct(NEVER_TYPE_EMPTY_STRUCT_NAME, NO_PRETTY_NAME, |_, _| vec![])
             }
@@ -736,13 +786,67 @@ fn codegen_ty_closure(&mut self, t: Ty<'tcx>, substs: ty::subst::SubstsRef<'tcx>
         })
     }
 
-    /// Preliminary support for the Generator type kind. The core functionality remains
-    /// unimplemented, but this way we fail at verification time only if paths that
-    /// rely on Generator types are used.
-    fn codegen_ty_generator(&mut self, substs: ty::subst::SubstsRef<'tcx>) -> Type {
-        let tys = substs.as_generator().upvar_tys().map(|t| self.codegen_ty(t)).collect();
-        let output = self.codegen_ty(substs.as_generator().return_ty());
-        Type::code_with_unnamed_parameters(tys, output)
+    /// A generator is a state machine. Its layout is similar to the one of an enum with a direct
+    /// tag: a prefix holds the upvars and the tag, and each variant holds the locals that are live
+    /// across the corresponding suspension point. The difference is that the fields of a variant
+    /// do not necessarily follow the prefix: locals that are used by several variants are promoted
+    /// to the prefix, so they can be found at the same offset in each of them.
+    ///
+    /// To account for the overlap, we codegen a union of structs, where each struct places its
+    /// fields at the offsets given by the layout:
+    /// union Generator {
+    ///   struct Generator::prefix {
+    ///     T0 0; // upvars
+    ///     ...
+    ///     u8 case;
+    ///   } prefix;
+    ///   struct Generator::Unresumed { ... } Unresumed;
+    ///   ...
+    ///   struct Generator::Suspend0 {
+    ///     T0 0; // saved locals of the variant
+    ///     ...
+    ///   } Suspend0;
+    /// }
+    fn codegen_ty_generator(&mut self, t: Ty<'tcx>) -> Type {
+        let (def_id, substs) = match t.kind() {
+            ty::Generator(def_id, substs, _) => (*def_id, *substs),
+            _ => unreachable!("expected a generator: {:?}", t),
+        };
+        self.ensure_union(self.ty_mangled_name(t), Some(self.ty_pretty_name(t)), |ctx, name| {
+            let layout = ctx.layout_of(t).layout;
+            let variants = match &layout.variants {
+                Variants::Multiple { variants, .. } => variants,
+                _ => unreachable!("a generator always has multiple variants"),
+            };
+            let prefix_flds = substs
+                .as_generator()
+                .upvar_tys()
+                .enumerate()
+                .map(|(i, t)| (GotocCtx::tuple_fld_name(i), t))
+                .chain(iter::once(("case".to_string(), ctx.codegen_enum_discr_typ(t))))
+                .collect();
+            let prefix_name = format!("{}::{}", name, GENERATOR_PREFIX_NAME);
+            let prefix = ctx.ensure_struct(&prefix_name, NO_PRETTY_NAME, |ctx, _| {
+                ctx.codegen_struct_fields(prefix_flds, layout, 0)
+            });
+            let mut fields = vec![Type::datatype_component(GENERATOR_PREFIX_NAME, prefix)];
+            for (idx, tys) in substs.as_generator().state_tys(def_id, ctx.tcx).enumerate() {
+                let idx = VariantIdx::from_usize(idx);
+                let variant_name = GotocCtx::generator_variant_name(idx);
+                let flds = tys.enumerate().map(|(i, t)| (GotocCtx::tuple_fld_name(i), t)).collect();
+                let struct_name = format!("{}::{}", name, variant_name);
+                let variant = ctx.ensure_struct(&struct_name, NO_PRETTY_NAME, |ctx, _| {
+                    ctx.codegen_struct_fields(flds, &variants[idx], 0)
+                });
+                fields.push(Type::datatype_component(&variant_name, variant));
+            }
+            fields
+        })
+    }
+
+    /// The prefix of a generator, i.e., the struct that holds its upvars and its tag.
+    pub fn codegen_generator_prefix(&self, generator: Expr) -> Expr {
+        generator.member(GENERATOR_PREFIX_NAME, &self.symbol_table)
     }
 
     pub fn codegen_fat_ptr(&mut self, mir_type: Ty<'tcx>) -> Type {
@@ -808,6 +912,7 @@ pub fn codegen_ty_ref(&mut self, pointee_type: Ty<'tcx>) -> Type {
             | ty::Closure(..)
             | ty::Float(_)
             | ty::Foreign(_)
+            | ty::Generator(..)
             | ty::Int(_)
             | ty::RawPtr(_)
             | ty::Ref(..)
@@ -827,7 +932,6 @@ pub fn codegen_ty_ref(&mut self, pointee_type: Ty<'tcx>) -> Type {
             // For soundess, hold off on generating them till we have test-cases.
             ty::Bound(_, _) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
             ty::Error(_) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
-            ty::Generator(_, _, _) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
             ty::GeneratorWitness(_) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
             ty::Infer(_) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
             ty::Param(_) => todo!("{:?} {:?}", pointee_type, pointee_type.kind()),
@@ -864,6 +968,31 @@ pub fn codegen_dynamic_function_sig(&mut self, sig: PolyFnSig<'tcx>) -> Type {
         Type::code_with_unnamed_parameters(params, self.codegen_ty(sig.output()))
     }
 
+    /// The type of the implicit argument of `#[track_caller]` functions,
+    /// i.e. `&'static core::panic::Location<'static>`.
+    pub fn codegen_caller_location_ty(&mut self) -> Type {
+        let caller_location_ty = self.tcx.caller_location_ty();
+        self.codegen_ty(caller_location_ty)
+    }
+
+    /// The function type of `instance`, including the implicit caller location parameter
+    /// that rustc adds at the end of the argument list of `#[track_caller]` functions.
+    pub fn codegen_instance_fn_sig(&mut self, instance: Instance<'tcx>) -> Type {
+        let fn_typ = self.codegen_function_sig(self.fn_sig_of_instance(instance).unwrap());
+        if !instance.def.requires_caller_location(self.tcx) {
+            return fn_typ;
+        }
+        let caller_location_param = self.codegen_caller_location_ty().as_parameter(None, None);
+        match fn_typ {
+            Type::Code { parameters, return_type } => {
+                let mut parameters = parameters.to_vec();
+                parameters.push(caller_location_param);
+                Type::code(parameters, return_type.as_ref().clone())
+            }
+            _ => unreachable!("#[track_caller] functions cannot be variadic: {:?}", fn_typ),
+        }
+    }
+
     /// one can only apply this function to a monomorphized signature
     pub fn codegen_function_sig(&mut self, sig: PolyFnSig<'tcx>) -> Type {
         let sig = self.monomorphize(sig);
@@ -1223,6 +1352,16 @@ pub fn fn_typ(&mut self) -> Type {
             })
             .collect();
 
+        // `#[track_caller]` functions take the location of their caller as an implicit last
+        // argument. See `codegen_caller_location` in `span.rs` for how it is passed.
+        if self.current_fn().instance().def.requires_caller_location(self.tcx) {
+            let (name, _) = self.codegen_caller_location_param_name();
+            params.push(
+                self.codegen_caller_location_ty()
+                    .as_parameter(Some(name.clone().into()), Some(name.into())),
+            );
+        }
+
         // For vtable shims, we need to modify fn(self, ...) to fn(self: *mut Self, ...),
         // since the vtable functions expect a pointer as the first argument. See the comment
         // and similar code in compiler/rustc_mir/src/shim.rs.
@@ -3,10 +3,11 @@
 
 //! This file contains the code necessary to interface with the compiler backend
 
+use crate::codegen::assign_cbmc_loop_ids;
 use crate::context::metadata::RmcMetadata;
 use crate::GotocCtx;
 use bitflags::_core::any::Any;
-use cbmc::goto_program::symtab_transformer;
+use cbmc::goto_program::symtab_transformer::{read_pipeline_file, PassContext, PassRegistry};
 use cbmc::InternedString;
 use rmc_queries::{QueryDb, UserInput};
 use rustc_codegen_ssa::traits::CodegenBackend;
@@ -25,7 +26,7 @@
 use std::collections::BTreeMap;
 use std::io::BufWriter;
 use std::iter::FromIterator;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use tracing::{debug, warn};
 
@@ -45,7 +46,9 @@ fn metadata_loader(&self) -> Box<MetadataLoaderDyn> {
         Box::new(rustc_codegen_ssa::back::metadata::DefaultMetadataLoader)
     }
 
-    fn provide(&self, _providers: &mut Providers) {}
+    fn provide(&self, providers: &mut Providers) {
+        crate::overrides::provide(providers);
+    }
 
     fn provide_extern(&self, _providers: &mut ty::query::ExternProviders) {}
 
@@ -57,7 +60,7 @@ fn codegen_crate<'tcx>(
     ) -> Box<dyn Any> {
         super::utils::init();
 
-        check_options(&tcx.sess, need_metadata_module);
+        check_options(&tcx.sess, need_metadata_module, &self.queries);
 
         let codegen_units: &'tcx [CodegenUnit<'_>] = tcx.collect_and_partition_mono_items(()).1;
         let mut c = GotocCtx::new(tcx, self.queries.clone());
@@ -119,31 +122,57 @@ fn codegen_crate<'tcx>(
             }
         }
 
+        // leak checks can only be generated once all allocation sites are known
+        c.codegen_leak_check_fn();
+
         // perform post-processing symbol table passes
-        let passes = self.queries.get_symbol_table_passes();
-        let symtab = symtab_transformer::do_passes(c.symbol_table, &passes);
+        let registry = PassRegistry::default();
+        // the passes run in the order given: those of the file, then those of the flag
+        let mut passes = match self.queries.get_symbol_table_passes_file() {
+            Some(file) => {
+                read_pipeline_file(Path::new(&file)).unwrap_or_else(|e| tcx.sess.fatal(&e))
+            }
+            None => vec![],
+        };
+        passes.extend(self.queries.get_symbol_table_passes());
+        let pipeline = registry
+            .parse_pipeline(&passes, &self.queries.get_dump_after())
+            .unwrap_or_else(|e| tcx.sess.fatal(&e));
+        let outputs = tcx.output_filenames(());
+        let base_filename = outputs.output_path(OutputType::Object);
+        let entry_points: Vec<InternedString> = std::iter::once("main".to_string())
+            .chain(c.proof_harnesses.iter().map(|harness| harness.mangled_name.clone()))
+            .chain(self.queries.get_entry_points())
+            .map(InternedString::from)
+            .collect();
+        let context = PassContext { entry_points: &entry_points };
+        let symtab = pipeline.run(c.symbol_table, &context, |pass, symtab| {
+            write_file(&base_filename, &format!("{}.symtab.json", pass), symtab)
+        });
 
         // Map MIR types to GotoC types
         let type_map: BTreeMap<InternedString, InternedString> =
             BTreeMap::from_iter(c.type_map.into_iter().map(|(k, v)| (k, v.to_string().into())));
 
         // Get the vtable function pointer restrictions if requested
-        let vtable_restrictions = if c.vtable_ctx.emit_vtable_restrictions {
+        let vtable_restrictions = if c.vtable_ctx.emits_restrictions() {
             Some(c.vtable_ctx.get_virtual_function_restrictions())
         } else {
             None
         };
 
-        let metadata = RmcMetadata { proof_harnesses: c.proof_harnesses };
+        let metadata = RmcMetadata {
+            proof_harnesses: c.proof_harnesses,
+            inline_asm_warnings: c.inline_asm_warnings,
+            loops: assign_cbmc_loop_ids(c.loops, &symtab),
+        };
 
         // No output should be generated if user selected no_codegen.
         if !tcx.sess.opts.debugging_opts.no_codegen && tcx.sess.opts.output_types.should_codegen() {
-            let outputs = tcx.output_filenames(());
-            let base_filename = outputs.output_path(OutputType::Object);
             write_file(&base_filename, "symtab.json", &symtab);
             write_file(&base_filename, "type_map.json", &type_map);
             write_file(&base_filename, "rmc-metadata.json", &metadata);
-            // If they exist, write out vtable virtual call function pointer restrictions
+            // If they exist, write out the function pointer restrictions of virtual and other calls
             if let Some(restrictions) = vtable_restrictions {
                 write_file(&base_filename, "restrictions.json", &restrictions);
             }
@@ -183,16 +212,19 @@ fn link(
     }
 }
 
-fn check_options(session: &Session, need_metadata_module: bool) {
+fn check_options(session: &Session, need_metadata_module: bool, queries: &QueryDb) {
     if !session.overflow_checks() {
         session.err("RMC requires overflow checks in order to provide a sound analysis.");
     }
 
-    if session.panic_strategy() != PanicStrategy::Abort {
-        session.err(
-            "RMC can only handle abort panic strategy (-C panic=abort). See for more details \
-        https://github.com/model-checking/rmc/issues/692",
-        );
+    let expected_strategy =
+        if queries.get_panic_unwind() { PanicStrategy::Unwind } else { PanicStrategy::Abort };
+    if session.panic_strategy() != expected_strategy {
+        session.err(&format!(
+            "RMC expected the {0} panic strategy (-C panic={0}). Use --panic-unwind to model \
+            unwinding panics. See for more details https://github.com/model-checking/rmc/issues/692",
+            expected_strategy.desc()
+        ));
     }
 
     if need_metadata_module {
@@ -14,8 +14,9 @@
 //! Any MIR specific functionality (e.g. codegen etc) should live in specialized files that use
 //! this structure as input.
 use super::current_fn::CurrentFnCtx;
-use super::metadata::HarnessMetadata;
+use super::metadata::{HarnessMetadata, InlineAsmWarning, LoopMetadata};
 use super::vtable_ctx::VtableCtx;
+use crate::codegen::has_leak_checked_harness;
 use crate::overrides::{fn_hooks, GotocHooks};
 use crate::utils::full_crate_name;
 use cbmc::goto_program::{DatatypeComponent, Expr, Location, Stmt, Symbol, SymbolTable, Type};
@@ -32,11 +33,11 @@
 use rustc_middle::ty::layout::{HasParamEnv, HasTyCtxt, LayoutError, LayoutOfHelpers, TyAndLayout};
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
 use rustc_session::cstore::MetadataLoader;
-use rustc_session::Session;
 use rustc_span::source_map::Span;
 use rustc_target::abi::Endian;
 use rustc_target::abi::{HasDataLayout, TargetDataLayout};
 use rustc_target::spec::Target;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::rc::Rc;
 
@@ -59,15 +60,24 @@ pub struct GotocCtx<'tcx> {
     pub current_fn: Option<CurrentFnCtx<'tcx>>,
     pub type_map: FxHashMap<InternedString, Ty<'tcx>>,
     pub proof_harnesses: Vec<HarnessMetadata>,
+    pub inline_asm_warnings: Vec<InlineAsmWarning>,
+    /// the loops of each function, whose CBMC ids are only known after the symbol table passes
+    pub loops: BTreeMap<String, Vec<LoopMetadata>>,
+    /// whether some harness checks for memory leaks, in which case we track heap allocations
+    pub track_leaks: bool,
+    /// the function and location of each tracked allocation site
+    pub alloc_sites: Vec<(String, Location)>,
 }
 
 /// Constructor
 impl<'tcx> GotocCtx<'tcx> {
     pub fn new(tcx: TyCtxt<'tcx>, queries: Rc<QueryDb>) -> GotocCtx<'tcx> {
-        let fhks = fn_hooks();
-        let mm = machine_model_from_session(tcx.sess);
+        let fhks = fn_hooks(&queries);
+        let mm = machine_model_from_target(tcx);
         let symbol_table = SymbolTable::new(mm);
         let emit_vtable_restrictions = queries.get_emit_vtable_restrictions();
+        let emit_fn_ptr_restrictions = queries.get_emit_fn_ptr_restrictions();
+        let track_leaks = has_leak_checked_harness(tcx, &queries);
         GotocCtx {
             tcx,
             queries,
@@ -76,10 +86,14 @@ pub fn new(tcx: TyCtxt<'tcx>, queries: Rc<QueryDb>) -> GotocCtx<'tcx> {
             full_crate_name: full_crate_name(tcx),
             global_var_count: 0,
             alloc_map: FxHashMap::default(),
-            vtable_ctx: VtableCtx::new(emit_vtable_restrictions),
+            vtable_ctx: VtableCtx::new(emit_vtable_restrictions, emit_fn_ptr_restrictions),
             current_fn: None,
             type_map: FxHashMap::default(),
             proof_harnesses: vec![],
+            inline_asm_warnings: vec![],
+            loops: BTreeMap::new(),
+            track_leaks,
+            alloc_sites: vec![],
         }
     }
 }
@@ -324,32 +338,53 @@ fn get_dylib_metadata(&self, target: &Target, filename: &Path) -> Result<Metadat
     }
 }
 
-fn machine_model_from_session(sess: &Session) -> MachineModel {
-    // TODO: Hardcoded values from from the ones currently used in env.rs
-    // We may wish to get more of them from the session.
-    let alignment = sess.target.options.min_global_align.unwrap_or(1);
-    let architecture = &sess.target.arch;
+/// The machine model of the compilation target, i.e. the layout of the C types on the target
+/// (which `rmc_lib.c` and the CBMC library use), taken from the rustc target specification.
+/// The C type widths follow the C ABI of the target, like `core::ffi` and the `libc` crate.
+fn machine_model_from_target(tcx: TyCtxt<'_>) -> MachineModel {
+    let target = &tcx.sess.target;
+    let data_layout = tcx.data_layout();
+    let architecture = target.arch.as_str();
+    let is_apple = target.is_like_osx || target.vendor == "apple";
+
+    // `min_global_align` is in bits, CBMC expects bytes.
+    let alignment = target.min_global_align.map_or(1, |bits| (bits / 8).max(1));
     let bool_width = 8;
-    let char_is_unsigned = false;
+    let char_is_unsigned = match architecture {
+        "aarch64" => !is_apple && !target.is_like_windows,
+        "arm" => !is_apple,
+        "hexagon" | "msp430" | "powerpc" | "powerpc64" | "riscv32" | "riscv64" | "s390x" => true,
+        _ => false,
+    };
     let char_width = 8;
-    let double_width = 64;
+    let double_width = if architecture == "avr" { 32 } else { 64 };
     let float_width = 32;
-    let int_width = 32;
-    let is_big_endian = match sess.target.options.endian {
+    let int_width = target.c_int_width.parse().unwrap();
+    let is_big_endian = match data_layout.endian {
         Endian::Little => false,
         Endian::Big => true,
     };
-    let long_double_width = 128;
-    let long_int_width = 64;
+    let pointer_width = data_layout.pointer_size.bits();
+    let long_double_width = match architecture {
+        _ if target.is_like_windows => 64,
+        "x86" => 96,
+        "x86_64" | "powerpc64" | "riscv64" | "s390x" => 128,
+        "aarch64" if !is_apple => 128,
+        _ => 64,
+    };
+    // LP64 on 64-bit targets other than Windows, and ILP32 (or 16-bit `int`) otherwise.
+    let long_int_width = if pointer_width == 64 && !target.is_like_windows { 64 } else { 32 };
     let long_long_int_width = 64;
-    let memory_operand_size = 4;
+    let memory_operand_size = int_width / 8;
     let null_is_zero = true;
-    let pointer_width = sess.target.pointer_width.into();
     let short_int_width = 16;
     let single_width = 32;
-    let wchar_t_is_unsigned = false;
-    let wchar_t_width = 32;
-    let word_size = 32;
+    let (wchar_t_is_unsigned, wchar_t_width) = match architecture {
+        _ if target.is_like_windows => (true, 16),
+        "aarch64" | "arm" if !is_apple => (true, 32),
+        _ => (false, 32),
+    };
+    let word_size = int_width;
     let rounding_mode = RoundingMode::ToNearest;
 
     MachineModel::new(
@@ -5,6 +5,7 @@
 //! but leaving it here for now...
 
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// We emit this structure for each annotated proof harness we find
 #[derive(Serialize)]
@@ -19,8 +20,41 @@ pub struct HarnessMetadata {
     pub original_line: String,
 }
 
+/// We emit this structure for each inline assembly block that was modeled instead of verified
+#[derive(Serialize)]
+pub struct InlineAsmWarning {
+    /// The name of the function that contains the inline assembly
+    pub function: String,
+    /// The (currently full-) path to the file that contains the inline assembly
+    pub original_file: String,
+    /// The line in that file where the inline assembly begins
+    pub original_line: String,
+    /// The function that models the inline assembly, if its outputs are not simply havocked
+    pub model: Option<String>,
+}
+
+/// We emit this structure for each loop of a function, so that tools can relate the loop ids
+/// of CBMC, e.g. in unwinding failures and `--unwindset`, to the loops of the Rust code
+#[derive(Serialize)]
+pub struct LoopMetadata {
+    /// The label of the block of the loop header in the goto function, e.g. `bb3`, or `None`
+    /// for a loop that CBMC finds but that is not a loop of the Rust code
+    pub label: Option<String>,
+    /// The (currently full-) path to the file that contains the loop
+    pub original_file: String,
+    /// The line in that file where the loop header begins, or where the backward jump of a
+    /// loop without a label is
+    pub original_line: String,
+    /// The ids of the loop in CBMC, e.g. `main.0`. CBMC has a loop for each backward jump to
+    /// the header, so there may be several, or none if the symbol table passes removed them.
+    pub cbmc_loop_ids: Vec<String>,
+}
+
 /// The structure of `.rmc-metadata.json` files, which are emitted for each crate
 #[derive(Serialize)]
 pub struct RmcMetadata {
     pub proof_harnesses: Vec<HarnessMetadata>,
+    pub inline_asm_warnings: Vec<InlineAsmWarning>,
+    /// The loops of each function with loops, by the name of the function in the symbol table
+    pub loops: BTreeMap<String, Vec<LoopMetadata>>,
 }
@@ -13,6 +13,9 @@
 /// performance compared to heuristics that consider a wider set of possible
 /// function pointer targets.
 ///
+/// With `--restrict-fn-ptrs`, calls through plain function pointers are
+/// restricted the same way, see `codegen/fn_ptr.rs`.
+///
 /// For the current CBMC implementation of function restrictions, see:
 ///     http://cprover.diffblue.com/md__home_travis_build_diffblue_cbmc_doc_architectural_restrict-function-pointer.html
 use crate::GotocCtx;
@@ -30,6 +33,9 @@ pub struct VtableCtx {
     // Option to actually enable restrictions
     pub emit_vtable_restrictions: bool,
 
+    // Option to also restrict calls through plain function pointers
+    pub emit_fn_ptr_restrictions: bool,
+
     // Map: (normalized trait name, method index) -> possible implementations
     possible_methods: FxHashMap<TraitDefinedMethod, Vec<InternedString>>,
 
@@ -42,10 +48,12 @@ pub struct VtableCtx {
 
 /// Constructor
 impl VtableCtx {
-    pub fn new(emit_vtable_restrictions: bool) -> Self {
+    pub fn new(emit_vtable_restrictions: bool, emit_fn_ptr_restrictions: bool) -> Self {
         debug!("Restricting vtable function pointers? {:?}", emit_vtable_restrictions);
+        debug!("Restricting other function pointers? {:?}", emit_fn_ptr_restrictions);
         Self {
             emit_vtable_restrictions,
+            emit_fn_ptr_restrictions,
             possible_methods: FxHashMap::default(),
             call_sites: Vec::new(),
             call_site_global_idx: 0,
@@ -55,6 +63,11 @@ pub fn new(emit_vtable_restrictions: bool) -> Self {
 
 /// Interface for codegen to add possible methods
 impl VtableCtx {
+    /// Whether we restrict any function pointer calls, and thus write out restrictions.
+    pub fn emits_restrictions(&self) -> bool {
+        self.emit_vtable_restrictions || self.emit_fn_ptr_restrictions
+    }
+
     /// Add a possible implementation for a virtual method call.
     pub fn add_possible_method(
         &mut self,
@@ -62,11 +75,13 @@ pub fn add_possible_method(
         method: usize,
         imp: InternedString,
     ) {
-        assert!(self.emit_vtable_restrictions);
+        assert!(self.emits_restrictions());
         let key = TraitDefinedMethod { trait_name, vtable_idx: method };
 
         if let Some(possibilities) = self.possible_methods.get_mut(&key) {
-            possibilities.push(imp);
+            if !possibilities.contains(&imp) {
+                possibilities.push(imp);
+            }
         } else {
             self.possible_methods.insert(key, vec![imp]);
         }
@@ -81,7 +96,7 @@ pub fn drop_index() -> usize {
 /// Internal tracking helpers
 impl VtableCtx {
     fn get_call_site_global_idx(&mut self) -> usize {
-        assert!(self.emit_vtable_restrictions);
+        assert!(self.emits_restrictions());
         self.call_site_global_idx += 1;
         self.call_site_global_idx
     }
@@ -93,7 +108,7 @@ fn add_call_site(
         method: usize,
         function_name: InternedString,
     ) {
-        assert!(self.emit_vtable_restrictions);
+        assert!(self.emits_restrictions());
         let site = CallSite {
             trait_method: TraitDefinedMethod { trait_name, vtable_idx: method },
             function_name: function_name,
@@ -125,20 +140,37 @@ pub fn virtual_call_with_restricted_fn_ptr(
     ) -> Expr {
         assert!(self.vtable_ctx.emit_vtable_restrictions);
 
+        // We only have the Gotoc type, we need to normalize to match the MIR type.
+        assert!(trait_ty.is_struct_tag());
+        let mir_name =
+            self.normalized_trait_name(self.type_map.get(&trait_ty.tag().unwrap()).unwrap());
+        let wrapper_name = self.restricted_call_wrapper_name();
+        self.call_with_restricted_fn_ptr(wrapper_name, mir_name.into(), vtable_idx, fn_ptr, args)
+    }
+
+    /// A fresh name for the wrapper of a restricted call.
+    pub fn restricted_call_wrapper_name(&mut self) -> InternedString {
         // Crate-based naming scheme for wrappers
         let full_crate_name = self.full_crate_name().to_string().replace("::", "_");
-        let wrapper_name: InternedString = format!(
+        format!(
             "restricted_call_{}_{}",
             full_crate_name,
             self.vtable_ctx.get_call_site_global_idx()
         )
-        .into();
+        .into()
+    }
 
-        // We only have the Gotoc type, we need to normalize to match the MIR type.
-        assert!(trait_ty.is_struct_tag());
-        let mir_name =
-            self.normalized_trait_name(self.type_map.get(&trait_ty.tag().unwrap()).unwrap());
-        self.vtable_ctx.add_call_site(mir_name.into(), vtable_idx, wrapper_name);
+    /// Call `fn_ptr` through the wrapper `wrapper_name`, whose single call is
+    /// restricted to the possible methods of `trait_name` and `vtable_idx`.
+    pub fn call_with_restricted_fn_ptr(
+        &mut self,
+        wrapper_name: InternedString,
+        trait_name: InternedString,
+        vtable_idx: usize,
+        fn_ptr: Expr,
+        args: Vec<Expr>,
+    ) -> Expr {
+        self.vtable_ctx.add_call_site(trait_name, vtable_idx, wrapper_name);
 
         // Declare the wrapper's parameters
         let func_exp: Expr = fn_ptr.dereference();
@@ -192,14 +224,18 @@ pub fn virtual_call_with_restricted_fn_ptr(
 impl VtableCtx {
     /// Write out (1) all call sites and (2) possible concrete methods to JSON.
     pub fn get_virtual_function_restrictions(&mut self) -> VtableCtxResults {
-        assert!(self.emit_vtable_restrictions);
+        assert!(self.emits_restrictions());
 
         VtableCtxResults {
             call_sites: self.call_sites.clone(),
             possible_methods: self
                 .possible_methods
                 .drain()
-                .map(|(k, v)| PossibleMethodEntry { trait_method: k, possibilities: v })
+                .map(|(k, mut v)| {
+                    // Sort the methods by name, so that they do not depend on the order of codegen
+                    v.sort_by_cached_key(|imp| imp.to_string());
+                    PossibleMethodEntry { trait_method: k, possibilities: v }
+                })
                 .collect(),
         }
     }
@@ -8,10 +8,12 @@
 //! It would be too nasty if we spread around these sort of undocumented hooks in place, so
 //! this module addresses this issue.
 
-use crate::utils::instance_name_starts_with;
+use crate::utils::{dynamic_fat_ptr, instance_name_starts_with};
 use crate::GotocCtx;
-use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Symbol, Type};
+use cbmc::goto_program::{Expr, Location, Stmt, Symbol, Type};
 use cbmc::NO_PRETTY_NAME;
+use rmc_queries::{QueryDb, UserInput};
+use rustc_ast::expand::allocator::AllocatorKind;
 use rustc_middle::mir::{BasicBlock, Place};
 use rustc_middle::ty::layout::LayoutOf;
 use rustc_middle::ty::print::with_no_trimmed_paths;
@@ -212,6 +214,67 @@ fn handle(
     }
 }
 
+/// `__rust_panic_cleanup` takes the exception object of a caught panic and returns its payload.
+/// We do not model panic payloads, so we return a `()` payload through a dangling pointer.
+struct PanicCleanup;
+
+impl<'tcx> GotocHook<'tcx> for PanicCleanup {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = tcx.symbol_name(instance).name.to_string();
+        name == "__rust_panic_cleanup"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        let place_ty = tcx.place_ty(&p);
+        let trait_ty = place_ty.builtin_deref(true).unwrap().ty;
+        let vtable = tcx.codegen_vtable(tcx.tcx.mk_unit(), trait_ty).address_of();
+        let data = Expr::pointer_constant(1, Type::void_pointer());
+        let payload = dynamic_fat_ptr(tcx.codegen_ty(place_ty), data, vtable, &tcx.symbol_table);
+        Stmt::block(
+            vec![
+                tcx.codegen_place(&p).goto_expr.assign(payload, loc.clone()),
+                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
+            ],
+            loc,
+        )
+    }
+}
+
+/// The panic count is only updated by the standard library's panic machinery, which we replace
+/// with our own model of panics. Decrementing it after a caught panic would therefore underflow.
+struct PanicCountDecrease;
+
+impl<'tcx> GotocHook<'tcx> for PanicCountDecrease {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::panicking::panic_count::decrease"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        _assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        Stmt::goto(tcx.current_fn().find_label(&target.unwrap()), loc)
+    }
+}
+
 struct Nevers;
 
 impl<'tcx> GotocHook<'tcx> for Nevers {
@@ -453,53 +516,80 @@ fn handle(
     }
 }
 
-struct RustAlloc;
+/// `handle_alloc_error` aborts the program, which we report as a failure.
+struct HandleAllocError;
+
+impl<'tcx> GotocHook<'tcx> for HandleAllocError {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "alloc::alloc::handle_alloc_error"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        _instance: Instance<'tcx>,
+        _fargs: Vec<Expr>,
+        _assign_to: Option<Place<'tcx>>,
+        _target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        tcx.codegen_fatal_error("memory allocation failed", span)
+    }
+}
+
+/// The allocator shims `__rust_alloc`, `__rust_alloc_zeroed`, `__rust_realloc` and
+/// `__rust_dealloc` (see `codegen/alloc.rs`).
+///
+/// `exchange_malloc`, which allocates boxes, is modeled as `__rust_alloc` as well, unless
+/// allocations may fail or go through a `#[global_allocator]`. Since it must not return null, we
+/// then codegen its body, which calls `__rust_alloc` and `handle_alloc_error`.
+struct RustAlloc {
+    model_exchange_malloc: bool,
+}
 
 impl<'tcx> GotocHook<'tcx> for RustAlloc {
     fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
         let name = tcx.symbol_name(instance).name.to_string();
+        if matches!(
+            name.as_str(),
+            "__rust_alloc" | "__rust_alloc_zeroed" | "__rust_realloc" | "__rust_dealloc"
+        ) {
+            return true;
+        }
         let full_name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
-        name == "__rust_alloc" || full_name == "alloc::alloc::exchange_malloc"
+        self.model_exchange_malloc
+            && full_name == "alloc::alloc::exchange_malloc"
+            && tcx.allocator_kind(()) != Some(AllocatorKind::Global)
     }
 
     fn handle(
         &self,
         tcx: &mut GotocCtx<'tcx>,
         instance: Instance<'tcx>,
-        mut fargs: Vec<Expr>,
+        fargs: Vec<Expr>,
         assign_to: Option<Place<'tcx>>,
         target: Option<BasicBlock>,
         span: Option<Span>,
     ) -> Stmt {
-        debug!(?instance, "Replace allocation");
+        debug!(?instance, "Replace allocator shim");
         let loc = tcx.codegen_span_option(span);
-        match (assign_to, target) {
-            (Some(p), Some(target)) => {
-                let size = fargs.remove(0);
-                Stmt::block(
-                    vec![
-                        tcx.codegen_place(&p).goto_expr.assign(
-                            BuiltinFn::Malloc
-                                .call(vec![size], loc.clone())
-                                .cast_to(Type::unsigned_int(8).to_pointer()),
-                            loc,
-                        ),
-                        Stmt::goto(tcx.current_fn().find_label(&target), Location::none()),
-                    ],
-                    Location::none(),
-                )
-            }
-            _ => unreachable!(),
-        }
+        let name = tcx.symbol_name(instance);
+        // `exchange_malloc(size, align)` has the same signature as `__rust_alloc`.
+        let name = if name.starts_with("__rust_") { name.as_str() } else { "__rust_alloc" };
+        tcx.codegen_allocator_shim(name, fargs, assign_to.as_ref(), target.unwrap(), loc)
     }
 }
 
-struct RustDealloc;
+struct SliceFromRawPart;
 
-impl<'tcx> GotocHook<'tcx> for RustDealloc {
+impl<'tcx> GotocHook<'tcx> for SliceFromRawPart {
     fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
-        let name = tcx.symbol_name(instance).name.to_string();
-        name == "__rust_dealloc"
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "core::ptr::slice_from_raw_parts"
+            || name == "std::ptr::slice_from_raw_parts"
+            || name == "core::ptr::slice_from_raw_parts_mut"
+            || name == "std::ptr::slice_from_raw_parts_mut"
     }
 
     fn handle(
@@ -507,164 +597,269 @@ fn handle(
         tcx: &mut GotocCtx<'tcx>,
         _instance: Instance<'tcx>,
         mut fargs: Vec<Expr>,
-        _assign_to: Option<Place<'tcx>>,
+        assign_to: Option<Place<'tcx>>,
         target: Option<BasicBlock>,
         span: Option<Span>,
     ) -> Stmt {
         let loc = tcx.codegen_span_option(span);
-        match target {
-            Some(target) => {
-                let ptr = fargs.remove(0);
-                Stmt::block(
-                    vec![
-                        BuiltinFn::Free
-                            .call(vec![ptr.cast_to(Type::void_pointer())], loc.clone())
-                            .as_stmt(loc.clone()),
-                        Stmt::goto(tcx.current_fn().find_label(&target), Location::none()),
-                    ],
-                    loc,
-                )
-            }
-            _ => unreachable!(),
+        let p = assign_to.unwrap();
+        let target = target.unwrap();
+        let pt = tcx.codegen_ty(tcx.place_ty(&p));
+        let data = fargs.remove(0);
+        let len = fargs.remove(0);
+        let code = tcx
+            .codegen_place(&p)
+            .goto_expr
+            .assign(
+                Expr::struct_expr_from_values(pt, vec![data, len], &tcx.symbol_table),
+                loc.clone(),
+            )
+            .with_location(loc.clone());
+        Stmt::block(vec![code, Stmt::goto(tcx.current_fn().find_label(&target), loc.clone())], loc)
+    }
+}
+
+/// `std::thread::spawn` runs its closure in a new thread (see `codegen/thread.rs`).
+struct ThreadSpawn;
+
+impl<'tcx> GotocHook<'tcx> for ThreadSpawn {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::thread::spawn"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        tcx.codegen_thread_spawn(instance, fargs, &assign_to.unwrap(), target.unwrap(), loc)
+    }
+}
+
+/// `JoinHandle::join` waits for a thread created by `std::thread::spawn` to finish.
+struct ThreadJoin;
+
+impl<'tcx> GotocHook<'tcx> for ThreadJoin {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::thread::JoinHandle::<T>::join"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        tcx.codegen_thread_join(instance, fargs, &assign_to.unwrap(), target.unwrap(), loc)
+    }
+}
+
+/// Dropping a `JoinHandle` detaches its thread, which keeps running (see `codegen/thread.rs`).
+struct JoinHandleDrop;
+
+impl<'tcx> GotocHook<'tcx> for JoinHandleDrop {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        match instance.def {
+            InstanceDef::DropGlue(_, Some(ty)) => match ty.kind() {
+                ty::Adt(def, _) => {
+                    let name = with_no_trimmed_paths(|| tcx.def_path_str(def.did));
+                    name == "std::thread::JoinHandle"
+                }
+                _ => false,
+            },
+            _ => false,
         }
     }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        fargs: Vec<Expr>,
+        _assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        tcx.codegen_thread_detach(instance, fargs, target.unwrap(), loc)
+    }
 }
 
-struct RustRealloc;
+/// `MovableMutex::new` is not generic, so we would not have its body otherwise.
+/// It allocates the `pthread_mutex_t` of a `std::sync::Mutex`.
+struct MovableMutexNew;
 
-impl<'tcx> GotocHook<'tcx> for RustRealloc {
+impl<'tcx> GotocHook<'tcx> for MovableMutexNew {
     fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
-        let name = tcx.symbol_name(instance).name.to_string();
-        name == "__rust_realloc"
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "std::sys_common::mutex::MovableMutex::new"
     }
 
     fn handle(
         &self,
         tcx: &mut GotocCtx<'tcx>,
         _instance: Instance<'tcx>,
-        mut fargs: Vec<Expr>,
+        _fargs: Vec<Expr>,
         assign_to: Option<Place<'tcx>>,
         target: Option<BasicBlock>,
         span: Option<Span>,
     ) -> Stmt {
         let loc = tcx.codegen_span_option(span);
-        let p = assign_to.unwrap();
-        let target = target.unwrap();
-        let ptr = fargs.remove(0).cast_to(Type::void_pointer());
-        fargs.remove(0); // old_size
-        fargs.remove(0); // align
-        let size = fargs.remove(0);
-        Stmt::block(
-            vec![
-                tcx.codegen_place(&p).goto_expr.assign(
-                    BuiltinFn::Realloc
-                        .call(vec![ptr, size], loc.clone())
-                        .cast_to(Type::unsigned_int(8).to_pointer()),
-                    loc.clone(),
-                ),
-                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
-            ],
-            loc,
-        )
+        tcx.codegen_movable_mutex_new(&assign_to.unwrap(), target.unwrap(), loc)
     }
 }
 
-struct RustAllocZeroed;
+/// Locking and unlocking a `pthread_mutex_t`, which the standard library uses to implement
+/// `std::sync::Mutex`.
+struct PthreadMutex;
 
-impl<'tcx> GotocHook<'tcx> for RustAllocZeroed {
+impl<'tcx> GotocHook<'tcx> for PthreadMutex {
     fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
-        let name = tcx.symbol_name(instance).name.to_string();
-        name == "__rust_alloc_zeroed"
+        let name = tcx.symbol_name(instance).name;
+        name == "pthread_mutex_lock"
+            || name == "pthread_mutex_trylock"
+            || name == "pthread_mutex_unlock"
     }
 
     fn handle(
         &self,
         tcx: &mut GotocCtx<'tcx>,
-        _instance: Instance<'tcx>,
-        mut fargs: Vec<Expr>,
+        instance: Instance<'tcx>,
+        fargs: Vec<Expr>,
         assign_to: Option<Place<'tcx>>,
         target: Option<BasicBlock>,
         span: Option<Span>,
     ) -> Stmt {
         let loc = tcx.codegen_span_option(span);
-        let p = assign_to.unwrap();
-        let target = target.unwrap();
-        let size = fargs.remove(0);
-        Stmt::block(
-            vec![
-                tcx.codegen_place(&p).goto_expr.assign(
-                    BuiltinFn::Calloc
-                        .call(vec![Type::size_t().one(), size], loc.clone())
-                        .cast_to(Type::unsigned_int(8).to_pointer()),
-                    loc.clone(),
-                ),
-                Stmt::goto(tcx.current_fn().find_label(&target), loc.clone()),
-            ],
-            loc,
-        )
+        let name = tcx.tcx.symbol_name(instance).name;
+        tcx.codegen_pthread_mutex_op(name, fargs, &assign_to.unwrap(), target.unwrap(), loc)
     }
 }
 
-struct SliceFromRawPart;
+/// The LLVM intrinsics that `core::arch` uses to implement the x86 vendor intrinsics, e.g.
+/// `llvm.x86.sse2.pmovmskb.128` (see `codegen/llvm_intrinsic.rs`).
+struct LlvmX86Intrinsic;
 
-impl<'tcx> GotocHook<'tcx> for SliceFromRawPart {
+impl<'tcx> GotocHook<'tcx> for LlvmX86Intrinsic {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        tcx.is_foreign_item(instance.def_id())
+            && tcx.symbol_name(instance).name.starts_with("llvm.x86.")
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let name = tcx.tcx.symbol_name(instance).name;
+        tcx.codegen_llvm_intrinsic(name, fargs, &assign_to.unwrap(), target.unwrap(), loc)
+    }
+}
+
+/// With `--simple-hashing`, `RandomState::new` returns arbitrary keys instead of reading them
+/// from the OS (see `codegen/hash.rs`).
+struct RandomStateNew;
+
+impl<'tcx> GotocHook<'tcx> for RandomStateNew {
     fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
         let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
-        name == "core::ptr::slice_from_raw_parts"
-            || name == "std::ptr::slice_from_raw_parts"
-            || name == "core::ptr::slice_from_raw_parts_mut"
-            || name == "std::ptr::slice_from_raw_parts_mut"
+        name == "std::collections::hash_map::RandomState::new"
     }
 
     fn handle(
         &self,
         tcx: &mut GotocCtx<'tcx>,
         _instance: Instance<'tcx>,
-        mut fargs: Vec<Expr>,
+        _fargs: Vec<Expr>,
         assign_to: Option<Place<'tcx>>,
         target: Option<BasicBlock>,
         span: Option<Span>,
     ) -> Stmt {
         let loc = tcx.codegen_span_option(span);
-        let p = assign_to.unwrap();
-        let target = target.unwrap();
-        let pt = tcx.codegen_ty(tcx.place_ty(&p));
-        let data = fargs.remove(0);
-        let len = fargs.remove(0);
-        let code = tcx
-            .codegen_place(&p)
-            .goto_expr
-            .assign(
-                Expr::struct_expr_from_values(pt, vec![data, len], &tcx.symbol_table),
-                loc.clone(),
-            )
-            .with_location(loc.clone());
-        Stmt::block(vec![code, Stmt::goto(tcx.current_fn().find_label(&target), loc.clone())], loc)
+        tcx.codegen_random_state_new(&assign_to.unwrap(), target.unwrap(), loc)
     }
 }
 
-pub fn fn_hooks<'tcx>() -> GotocHooks<'tcx> {
-    GotocHooks {
-        hooks: vec![
-            Rc::new(Panic), //Must go first, so it overrides Nevers
-            Rc::new(Assume),
-            Rc::new(ExpectFail),
-            Rc::new(Intrinsic),
-            Rc::new(MemReplace),
-            Rc::new(MemSwap),
-            Rc::new(Nevers),
-            Rc::new(Nondet),
-            Rc::new(PtrRead),
-            Rc::new(PtrWrite),
-            Rc::new(RustAlloc),
-            Rc::new(RustAllocZeroed),
-            Rc::new(RustDealloc),
-            Rc::new(RustRealloc),
-            Rc::new(SliceFromRawPart),
-        ],
+/// With `--simple-hashing`, `DefaultHasher` replaces SipHash by a cheap hash function (see
+/// `codegen/hash.rs`).
+struct DefaultHasher;
+
+impl<'tcx> GotocHook<'tcx> for DefaultHasher {
+    fn hook_applies(&self, tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+        let name = with_no_trimmed_paths(|| tcx.def_path_str(instance.def_id()));
+        name == "<std::collections::hash_map::DefaultHasher as std::hash::Hasher>::write"
+            || name == "<std::collections::hash_map::DefaultHasher as std::hash::Hasher>::finish"
+    }
+
+    fn handle(
+        &self,
+        tcx: &mut GotocCtx<'tcx>,
+        instance: Instance<'tcx>,
+        fargs: Vec<Expr>,
+        assign_to: Option<Place<'tcx>>,
+        target: Option<BasicBlock>,
+        span: Option<Span>,
+    ) -> Stmt {
+        let loc = tcx.codegen_span_option(span);
+        let target = target.unwrap();
+        if tcx.tcx.item_name(instance.def_id()).as_str() == "write" {
+            tcx.codegen_default_hasher_write(fargs, target, loc)
+        } else {
+            tcx.codegen_default_hasher_finish(fargs, &assign_to.unwrap(), target, loc)
+        }
     }
 }
 
+pub fn fn_hooks<'tcx>(queries: &QueryDb) -> GotocHooks<'tcx> {
+    let mut hooks: Vec<Rc<dyn GotocHook<'tcx> + 'tcx>> = vec![
+        Rc::new(Panic), //Must go first, so it overrides Nevers
+        Rc::new(Assume),
+        Rc::new(ExpectFail),
+        Rc::new(HandleAllocError),
+        Rc::new(Intrinsic),
+        Rc::new(JoinHandleDrop),
+        Rc::new(LlvmX86Intrinsic),
+        Rc::new(MemReplace),
+        Rc::new(MemSwap),
+        Rc::new(MovableMutexNew),
+        Rc::new(Nevers),
+        Rc::new(Nondet),
+        Rc::new(PanicCleanup),
+        Rc::new(PanicCountDecrease),
+        Rc::new(PthreadMutex),
+        Rc::new(PtrRead),
+        Rc::new(PtrWrite),
+        Rc::new(RustAlloc {
+            model_exchange_malloc: !queries.get_alloc_may_fail()
+                && queries.get_heap_bound().is_none(),
+        }),
+        Rc::new(SliceFromRawPart),
+        Rc::new(ThreadJoin),
+        Rc::new(ThreadSpawn),
+    ];
+    if queries.get_simple_hashing() {
+        hooks.push(Rc::new(DefaultHasher));
+        hooks.push(Rc::new(RandomStateNew));
+    }
+    GotocHooks { hooks }
+}
+
 pub struct GotocHooks<'tcx> {
     hooks: Vec<Rc<dyn GotocHook<'tcx> + 'tcx>>,
 }
@@ -5,7 +5,11 @@
 //! For example, we the RMC provides pseudo-functions, such as rmc::assume().
 //! These functions should not be codegenned as MIR.
 //! Instead, we use a "hook" to generate the correct CBMC intrinsic.
+//! Similarly, we replace the standard library functions that interact with the operating system
+//! by their models in `rmc::std_models`.
 
 mod hooks;
+mod std_models;
 
 pub use hooks::{fn_hooks, GotocHooks};
+pub use std_models::provide;
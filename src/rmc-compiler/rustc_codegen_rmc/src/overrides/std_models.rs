@@ -0,0 +1,83 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module replaces the standard library functions that interact with the operating system
+//! (e.g. `std::env::var` or `std::time::Instant::now`) by their models in `rmc::std_models`.
+//!
+//! These functions are not generic, so their MIR is not available and we would otherwise codegen
+//! calls to missing functions. Instead, we override the `resolve_instance` query, so that both
+//! the collection of mono items and the codegen see the model wherever the program calls one of
+//! them. The models are `#[inline]`, so they are codegenned in the crate under verification.
+
+use rustc_errors::ErrorReported;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::query::Providers;
+use rustc_middle::ty::subst::SubstsRef;
+use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_span::symbol::{sym, Symbol};
+use std::lazy::SyncOnceCell;
+
+/// The functions of the standard library that we model, and the diagnostic items of their
+/// models.
+const STD_MODELS: &[(&str, &str)] = &[
+    ("<std::env::Args as std::iter::ExactSizeIterator>::len", "RmcModelArgsLen"),
+    ("<std::env::Args as std::iter::Iterator>::next", "RmcModelArgsNext"),
+    ("<std::env::Args as std::iter::Iterator>::size_hint", "RmcModelArgsSizeHint"),
+    ("<std::env::ArgsOs as std::iter::ExactSizeIterator>::len", "RmcModelArgsOsLen"),
+    ("<std::env::ArgsOs as std::iter::Iterator>::next", "RmcModelArgsOsNext"),
+    ("<std::env::ArgsOs as std::iter::Iterator>::size_hint", "RmcModelArgsOsSizeHint"),
+    ("<std::fs::File as std::io::Read>::read", "RmcModelFileRead"),
+    ("<std::fs::File as std::io::Read>::read_to_end", "RmcModelFileReadToEnd"),
+    ("<std::fs::File as std::io::Read>::read_to_string", "RmcModelFileReadToString"),
+    ("std::env::_var", "RmcModelEnvVar"),
+    ("std::env::_var_os", "RmcModelEnvVarOs"),
+    ("std::env::args", "RmcModelEnvArgs"),
+    ("std::env::args_os", "RmcModelEnvArgsOs"),
+    ("std::fs::OpenOptions::_open", "RmcModelFsOpen"),
+    ("std::fs::read::inner", "RmcModelFsRead"),
+    ("std::fs::read_to_string::inner", "RmcModelFsReadToString"),
+    ("std::time::Instant::duration_since", "RmcModelInstantDurationSince"),
+    ("std::time::Instant::elapsed", "RmcModelInstantElapsed"),
+    ("std::time::Instant::now", "RmcModelInstantNow"),
+    ("std::time::SystemTime::duration_since", "RmcModelSystemTimeDurationSince"),
+    ("std::time::SystemTime::now", "RmcModelSystemTimeNow"),
+];
+
+type ResolveInstance = for<'tcx> fn(
+    TyCtxt<'tcx>,
+    ty::ParamEnvAnd<'tcx, (DefId, SubstsRef<'tcx>)>,
+) -> Result<Option<Instance<'tcx>>, ErrorReported>;
+
+/// The `resolve_instance` provider of rustc, which we wrap.
+static DEFAULT_RESOLVE_INSTANCE: SyncOnceCell<ResolveInstance> = SyncOnceCell::new();
+
+pub fn provide(providers: &mut Providers) {
+    DEFAULT_RESOLVE_INSTANCE.get_or_init(|| providers.resolve_instance);
+    providers.resolve_instance = resolve_instance;
+}
+
+fn resolve_instance<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    key: ty::ParamEnvAnd<'tcx, (DefId, SubstsRef<'tcx>)>,
+) -> Result<Option<Instance<'tcx>>, ErrorReported> {
+    let instance = DEFAULT_RESOLVE_INSTANCE.get().unwrap()(tcx, key)?;
+    Ok(instance.map(|instance| std_model(tcx, instance).unwrap_or(instance)))
+}
+
+/// The model of `instance`, if it is a function of the standard library that we model and the
+/// `rmc` crate is available.
+fn std_model<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> Option<Instance<'tcx>> {
+    let def_id = match instance.def {
+        InstanceDef::Item(def) => def.did,
+        _ => return None,
+    };
+    if tcx.crate_name(def_id.krate) != sym::std {
+        return None;
+    }
+    let name = with_no_trimmed_paths(|| tcx.def_path_str(def_id));
+    let (_, model) = STD_MODELS.iter().find(|(function, _)| *function == name)?;
+    let model = tcx.get_diagnostic_item(Symbol::intern(model))?;
+    // None of the modeled functions is generic.
+    Some(Instance::new(model, ty::InternalSubsts::empty()))
+}
@@ -5,9 +5,12 @@
 
 use crate::GotocCtx;
 use cbmc::goto_program::Location;
+use rmc_queries::UserInput;
+use rustc_middle::mir::pretty::write_mir_fn;
 use rustc_middle::mir::Body;
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::Instance;
+use rustc_session::config::OutputType;
 use rustc_span::def_id::DefId;
 use std::cell::RefCell;
 use std::lazy::SyncLazy;
@@ -109,4 +112,28 @@ pub fn print_instance(&self, instance: Instance<'_>, mir: &'tcx Body<'tcx>) {
             }
         }
     }
+
+    /// If the current function was requested with `--dump-goto`, writes its MIR and its goto
+    /// program to `<output>.<function>.goto`.
+    pub fn dump_goto(&self, mir: &'tcx Body<'tcx>) {
+        let readable_name = self.current_fn().readable_name();
+        let name = self.current_fn().name();
+        if !self.queries.get_dump_goto().iter().any(|f| f == readable_name || *f == name) {
+            return;
+        }
+        let mut dump = Vec::new();
+        write_mir_fn(self.tcx, mir, &mut |_, _| Ok(()), &mut dump).unwrap();
+        let goto = self.symbol_table.lookup(&name).unwrap();
+        dump.extend(format!("\n{}", goto).into_bytes());
+
+        let file_name: String =
+            readable_name.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect();
+        let outputs = self.tcx.output_filenames(());
+        let path =
+            outputs.output_path(OutputType::Object).with_extension(format!("{}.goto", file_name));
+        debug!("dumping the goto of {} to {:?}", readable_name, path);
+        if let Err(e) = std::fs::write(&path, dump) {
+            self.tcx.sess.warn(&format!("Cannot write {}: {}", path.display(), e));
+        }
+    }
 }
@@ -11,7 +11,8 @@
 use rustc_middle::mir::mono::CodegenUnitNameBuilder;
 use rustc_middle::mir::Local;
 use rustc_middle::ty::print::with_no_trimmed_paths;
-use rustc_middle::ty::{Instance, TyCtxt};
+use rustc_middle::ty::{GeneratorSubsts, Instance, TyCtxt};
+use rustc_target::abi::VariantIdx;
 use tracing::debug;
 
 impl<'tcx> GotocCtx<'tcx> {
@@ -50,6 +51,15 @@ pub fn codegen_spread_arg_name(&self, l: &Local) -> (String, String) {
         (name, base_name)
     }
 
+    /// The name of the implicit `&'static core::panic::Location<'static>` parameter that
+    /// `#[track_caller]` functions receive, see `fn_typ` in `typ.rs`.
+    pub fn codegen_caller_location_param_name(&self) -> (String, String) {
+        let fname = self.current_fn().name();
+        let base_name = "caller_location".to_string();
+        let name = format!("{}::1::{}", fname, base_name);
+        (name, base_name)
+    }
+
     pub fn initializer_fn_name(var_name: &str) -> String {
         format!("{}_init", var_name)
     }
@@ -93,11 +103,21 @@ pub fn symbol_name(&self, instance: Instance<'tcx>) -> String {
         }
     }
 
+    /// The name of the shim that a function pointer to the closure `instance` points to
+    pub fn closure_fn_ptr_shim_name(&self, instance: Instance<'tcx>) -> String {
+        format!("gen-fn-ptr-shim<{}>", self.symbol_name(instance))
+    }
+
     /// The name for a tuple field
     pub fn tuple_fld_name(n: usize) -> String {
         format!("{}", n)
     }
 
+    /// The name for the union member that holds the fields of a generator variant, e.g. `Suspend0`
+    pub fn generator_variant_name(idx: VariantIdx) -> String {
+        GeneratorSubsts::variant_name(idx).to_string()
+    }
+
     /// The name for the struct field on a vtable for a given function. Because generic
     /// functions can share the same name, we need to use the index of the entry in the
     /// vtable. This is the same index that will be passed in virtual function calls as
@@ -23,15 +23,18 @@
 use std::rc::Rc;
 
 /// This function generates all rustc configurations required by our goto-c codegen.
-fn rustc_gotoc_flags(lib_path: &str) -> Vec<String> {
+/// By default, panics abort. If `panic_unwind` is set, we keep the unwind strategy so the MIR
+/// contains the cleanup edges we use to model unwinding.
+fn rustc_gotoc_flags(lib_path: &str, panic_unwind: bool) -> Vec<String> {
     let rmc_deps = lib_path.clone().to_owned() + "/deps";
+    let panic_args = if panic_unwind {
+        vec!["-C", "panic=unwind"]
+    } else {
+        vec!["-C", "panic=abort", "-Z", "panic_abort_tests=yes"]
+    };
     let args = vec![
         "-C",
         "overflow-checks=on",
-        "-C",
-        "panic=abort",
-        "-Z",
-        "panic_abort_tests=yes",
         "-Z",
         "trim-diagnostic-paths=no",
         "-Z",
@@ -48,7 +51,7 @@ fn rustc_gotoc_flags(lib_path: &str) -> Vec<String> {
         "-L",
         rmc_deps.as_str(),
     ];
-    args.iter().map(|s| s.to_string()).collect()
+    panic_args.iter().chain(args.iter()).map(|s| s.to_string()).collect()
 }
 
 fn parser<'a, 'b>() -> App<'a, 'b> {
@@ -77,11 +80,91 @@ fn parser<'a, 'b>() -> App<'a, 'b> {
                 .use_delimiter(true)
                 .multiple(true),
         )
+        .arg(
+            Arg::with_name("symbol-table-passes-file")
+                .long("--symbol-table-passes-file")
+                .value_name("FILE")
+                .help("File listing transformations to perform before --symbol-table-passes.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("dump-after")
+                .long("--dump-after")
+                .value_name("PASS")
+                .help("Write the symbol table after each run of the transformation PASS.")
+                .takes_value(true)
+                .use_delimiter(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name("dump-goto")
+                .long("--dump-goto")
+                .value_name("FUNCTION")
+                .help("Write the MIR and the goto program of FUNCTION to <output>.<FUNCTION>.goto.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("entry-point")
+                .long("--entry-point")
+                .value_name("FUNCTION")
+                .help("Additional function, besides main and the harnesses, to keep when pruning.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
         .arg(
             Arg::with_name("restrict-vtable-fn-ptrs")
                 .long("--restrict-vtable-fn-ptrs")
                 .help("Restrict the targets of virtual table function pointer calls."),
         )
+        .arg(
+            Arg::with_name("restrict-fn-ptrs")
+                .long("--restrict-fn-ptrs")
+                .help("Restrict the targets of calls through function pointers."),
+        )
+        .arg(
+            Arg::with_name("panic-unwind")
+                .long("--panic-unwind")
+                .help("Model panics as unwinding instead of aborting the execution."),
+        )
+        .arg(
+            Arg::with_name("havoc-inline-asm")
+                .long("--havoc-inline-asm")
+                .help("Model inline assembly as assigning arbitrary values to its outputs."),
+        )
+        .arg(
+            Arg::with_name("inline-asm-model")
+                .long("--inline-asm-model")
+                .value_name("FUNCTION=MODEL")
+                .help("Replace the inline assembly in FUNCTION by a call to the function MODEL.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("simple-hashing")
+                .long("--simple-hashing")
+                .help("Replace the SipHash hasher of HashMap and HashSet by a cheap hasher."),
+        )
+        .arg(
+            Arg::with_name("alloc-may-fail")
+                .long("--alloc-may-fail")
+                .help("Model heap allocations as nondeterministically failing."),
+        )
+        .arg(
+            Arg::with_name("heap-bound")
+                .long("--heap-bound")
+                .value_name("BYTES")
+                .help("Make heap allocations fail once BYTES bytes are allocated.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("check-leaks")
+                .long("--check-leaks")
+                .help("Check that harnesses free all the heap memory they allocate."),
+        )
         .arg(
             Arg::with_name("sysroot")
                 .long("--sysroot")
@@ -121,7 +204,38 @@ fn main() -> Result<(), &'static str> {
     if let Some(symbol_table_passes) = matches.values_of_os("symbol-table-passes") {
         queries.set_symbol_table_passes(symbol_table_passes.map(convert_arg).collect::<Vec<_>>());
     }
+    queries.set_symbol_table_passes_file(
+        matches.value_of("symbol-table-passes-file").map(String::from),
+    );
+    if let Some(dump_after) = matches.values_of("dump-after") {
+        queries.set_dump_after(dump_after.map(String::from).collect());
+    }
+    if let Some(functions) = matches.values_of("dump-goto") {
+        queries.set_dump_goto(functions.map(String::from).collect());
+    }
+    if let Some(entry_points) = matches.values_of("entry-point") {
+        queries.set_entry_points(entry_points.map(String::from).collect());
+    }
     queries.set_emit_vtable_restrictions(matches.is_present("restrict-vtable-fn-ptrs"));
+    queries.set_emit_fn_ptr_restrictions(matches.is_present("restrict-fn-ptrs"));
+    queries.set_panic_unwind(matches.is_present("panic-unwind"));
+    queries.set_havoc_inline_asm(matches.is_present("havoc-inline-asm"));
+    if let Some(models) = matches.values_of("inline-asm-model") {
+        let models = models
+            .map(|model| match model.split_once('=') {
+                Some((function, model)) => Ok((function.to_string(), model.to_string())),
+                None => Err("Expected FUNCTION=MODEL for --inline-asm-model"),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        queries.set_inline_asm_models(models);
+    }
+    queries.set_simple_hashing(matches.is_present("simple-hashing"));
+    queries.set_alloc_may_fail(matches.is_present("alloc-may-fail"));
+    if let Some(bound) = matches.value_of("heap-bound") {
+        let bound = bound.parse().map_err(|_| "Expected a number of bytes for --heap-bound")?;
+        queries.set_heap_bound(Some(bound));
+    }
+    queries.set_check_leaks(matches.is_present("check-leaks"));
 
     // Configure and run compiler.
     let mut callbacks = RmcCallbacks {};
@@ -143,8 +257,10 @@ impl Callbacks for RmcCallbacks {}
 
 /// Generate the arguments to pass to rustc_driver.
 fn generate_rustc_args(args: &ArgMatches) -> Vec<String> {
-    let mut gotoc_args =
-        rustc_gotoc_flags(&args.value_of("rmc-lib").unwrap_or(std::env!("RMC_LIB_PATH")));
+    let mut gotoc_args = rustc_gotoc_flags(
+        &args.value_of("rmc-lib").unwrap_or(std::env!("RMC_LIB_PATH")),
+        args.is_present("panic-unwind"),
+    );
     let mut rustc_args = vec![String::from("rustc")];
     if args.is_present("goto-c") {
         rustc_args.append(&mut gotoc_args);
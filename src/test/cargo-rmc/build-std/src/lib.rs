@@ -0,0 +1,13 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `str::from_utf8` is neither generic nor inline, so this crate only declares it. With
+//! `--build-std`, its body comes from the symbol table of `core`; otherwise, the undefined
+//! function check fails.
+use std::str;
+
+#[no_mangle]
+pub fn check_from_utf8() {
+    let bytes = [b'a'];
+    assert!(str::from_utf8(&bytes).is_ok());
+}
@@ -0,0 +1,38 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Test the restrictions of calls through plain function pointers. The test only
+// checks the restrictions that RMC writes, since CBMC does not apply them yet
+// (see the vtable_restrictions_fixme.rs tests).
+
+// rmc-flags: --restrict-fn-ptrs
+
+struct Handlers {
+    on_event: fn(i32) -> i32,
+}
+
+fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+fn dec(x: i32) -> i32 {
+    x - 1
+}
+
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+pub fn main() {
+    // The call through `f` may only call `inc` or `dec`
+    let f: fn(i32) -> i32 = if rmc::nondet() { inc } else { dec };
+    let r = f(5);
+    assert!(r == 6 || r == 4);
+
+    // The call through the field may call any `fn(i32) -> i32` whose address is taken
+    let handlers = Handlers { on_event: double };
+    assert!((handlers.on_event)(3) == 6);
+
+    let closure: fn(i32) -> i32 = |x| x + 10;
+    assert!(closure(1) == 11);
+}
@@ -0,0 +1,30 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --check-leaks
+
+// Check that the leaks of memory allocated by the standard library are reported at the call
+// that allocated it, rather than inside the standard library.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct Node {
+    next: RefCell<Option<Rc<Node>>>,
+}
+
+fn leak_vec() {
+    let mut v = Vec::with_capacity(2);
+    v.push(1u8);
+    std::mem::forget(v);
+}
+
+fn leak_cycle() {
+    let a = Rc::new(Node { next: RefCell::new(None) });
+    let b = Rc::new(Node { next: RefCell::new(Some(a.clone())) });
+    *a.next.borrow_mut() = Some(b);
+}
+
+fn main() {
+    leak_vec();
+    leak_cycle();
+}
@@ -0,0 +1,37 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check the loop table of the `.rmc-metadata.json` file for a `while` loop, a
+// `for` loop and nested loops.
+
+fn count_while() -> u32 {
+    let mut i = 0;
+    while i < 3 {
+        i += 1;
+    }
+    i
+}
+
+fn count_for() -> u32 {
+    let mut sum = 0;
+    for i in 0..3 {
+        sum += i;
+    }
+    sum
+}
+
+fn count_nested() -> u32 {
+    let mut n = 0;
+    for _ in 0..2 {
+        for _ in 0..2 {
+            n += 1;
+        }
+    }
+    n
+}
+
+fn main() {
+    assert!(count_while() == 3);
+    assert!(count_for() == 3);
+    assert!(count_nested() == 4);
+}
@@ -0,0 +1,35 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --build-target thumbv7em-none-eabihf --function harness
+
+// Check that arithmetic that fits in a 64-bit usize overflows on a 32-bit target.
+
+#![no_std]
+#![no_main]
+#![feature(rustc_attrs)]
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+/// The `rmc` crate is only built for the host, so we declare the functions that RMC replaces.
+mod rmc {
+    #[rustc_diagnostic_item = "RmcAnyRaw"]
+    #[inline(never)]
+    pub unsafe fn any_raw<T>() -> T {
+        loop {}
+    }
+
+    #[rustc_diagnostic_item = "RmcAssume"]
+    #[inline(never)]
+    pub fn assume(_cond: bool) {}
+}
+
+#[no_mangle]
+pub fn harness() {
+    let x: usize = unsafe { rmc::any_raw() };
+    rmc::assume(x == 1 << 31);
+    let y = x * 2;
+    assert!(y == 0);
+}
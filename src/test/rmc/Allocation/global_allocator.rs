@@ -0,0 +1,38 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that the code of a `#[global_allocator]` is verified.
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::ptr;
+
+const ARENA_SIZE: usize = 64;
+
+/// A bump allocator that never frees memory.
+struct BumpAllocator;
+
+static mut ARENA: [u8; ARENA_SIZE] = [0; ARENA_SIZE];
+static mut NEXT: usize = 0;
+
+unsafe impl GlobalAlloc for BumpAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let start = (NEXT + layout.align() - 1) & !(layout.align() - 1);
+        if start + layout.size() > ARENA_SIZE {
+            return ptr::null_mut();
+        }
+        NEXT = start + layout.size();
+        (ptr::addr_of_mut!(ARENA) as *mut u8).add(start)
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+}
+
+#[global_allocator]
+static ALLOCATOR: BumpAllocator = BumpAllocator;
+
+fn main() {
+    let a = Box::new(1u32);
+    let b = Box::new(2u32);
+    assert!(*a + *b == 3);
+    assert!(unsafe { NEXT } == 8);
+}
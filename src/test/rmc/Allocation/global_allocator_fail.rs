@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Check that the result of a `#[global_allocator]` is used: this allocator runs out of memory.
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::ptr;
+
+struct NoMemory;
+
+unsafe impl GlobalAlloc for NoMemory {
+    unsafe fn alloc(&self, _layout: Layout) -> *mut u8 {
+        ptr::null_mut()
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+}
+
+#[global_allocator]
+static ALLOCATOR: NoMemory = NoMemory;
+
+fn main() {
+    let b = Box::new(1u8);
+    assert!(*b == 1);
+}
@@ -0,0 +1,18 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --heap-bound 64
+
+// Check that allocations fail once the heap bound is reached, and succeed again once memory is
+// freed.
+
+fn main() {
+    let mut v: Vec<u8> = Vec::with_capacity(32);
+    v.push(1);
+    let mut w: Vec<u8> = Vec::new();
+    assert!(w.try_reserve_exact(48).is_err());
+    assert!(w.try_reserve_exact(32).is_ok());
+    drop(v);
+    let mut x: Vec<u8> = Vec::new();
+    assert!(x.try_reserve_exact(32).is_ok());
+}
@@ -0,0 +1,12 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --heap-bound 16
+// rmc-verify-fail
+
+// Check that exceeding the heap bound is reported through `handle_alloc_error`.
+
+fn main() {
+    let v: Vec<u64> = Vec::with_capacity(4);
+    assert!(v.capacity() == 4);
+}
@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --alloc-may-fail
+
+// Check that `try_reserve` reports allocation failures when allocations may fail.
+
+fn main() {
+    let mut v: Vec<u8> = Vec::new();
+    match v.try_reserve(16) {
+        Ok(()) => assert!(v.capacity() >= 16),
+        Err(_) => assert!(v.capacity() == 0),
+    }
+    let mut w: Vec<u8> = Vec::new();
+    rmc::expect_fail(w.try_reserve(16).is_ok(), "The allocation may fail");
+}
@@ -0,0 +1,12 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --alloc-may-fail
+// rmc-verify-fail
+
+// Check that a failing allocation is reported through `handle_alloc_error`.
+
+fn main() {
+    let b = Box::new(42u32);
+    assert!(*b == 42);
+}
@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --havoc-inline-asm
+
+// Check that inline assembly assigns arbitrary values to its outputs, and leaves its inputs
+// untouched.
+#![feature(asm)]
+
+fn read_timestamp(input: u32) -> (u32, u64) {
+    let lo: u32;
+    let hi: u32;
+    unsafe {
+        asm!("rdtsc", out("eax") lo, out("edx") hi, in("ecx") input);
+    }
+    (input, ((hi as u64) << 32) | lo as u64)
+}
+
+fn main() {
+    let (input, timestamp) = read_timestamp(42);
+    assert!(input == 42);
+    rmc::expect_fail(timestamp == 0, "The timestamp may have any value");
+    rmc::expect_fail(timestamp != 0, "The timestamp may have any value");
+}
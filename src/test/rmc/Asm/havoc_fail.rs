@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --havoc-inline-asm
+// rmc-verify-fail
+
+// Check that the outputs of inline assembly are not assumed to keep their value.
+#![feature(asm)]
+
+fn main() {
+    let mut x: u64 = 5;
+    unsafe {
+        asm!("add {0}, 1", inout(reg) x);
+    }
+    assert!(x == 6);
+}
@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-flags: --inline-asm-model=add_one=add_one_model
+
+// Check that the inline assembly in a function can be replaced by a model.
+#![feature(asm)]
+
+#[no_mangle]
+fn add_one_model(input: u64, output: &mut u64) {
+    *output = input + 1;
+}
+
+fn add_one(x: u64) -> u64 {
+    let y: u64;
+    unsafe {
+        asm!("lea {1}, [{0} + 1]", in(reg) x, out(reg) y);
+    }
+    y
+}
+
+fn main() {
+    let x: u64 = rmc::any();
+    if x < 100 {
+        assert!(add_one(x) == x + 1);
+    }
+}
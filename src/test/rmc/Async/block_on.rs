@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// edition:2018
+
+// Check that we can verify async functions by running them with `rmc::block_on`.
+
+async fn double(x: u32) -> u32 {
+    x * 2
+}
+
+async fn add_doubles(x: u32, y: u32) -> u32 {
+    let a = double(x).await;
+    let b = double(y).await;
+    a + b
+}
+
+fn main() {
+    let x: u32 = rmc::any();
+    let y: u32 = rmc::any();
+    rmc::assume(x < 1000 && y < 1000);
+    let sum = rmc::block_on(add_doubles(x, y));
+    assert!(sum == 2 * (x + y));
+}
@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// edition:2018
+// rmc-verify-fail
+
+// Check that a failing assertion inside an async block is reported.
+
+fn main() {
+    let x: u8 = rmc::any();
+    rmc::block_on(async move {
+        let y = async { x / 2 }.await;
+        assert!(y < 100);
+    });
+}
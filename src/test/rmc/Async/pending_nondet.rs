@@ -0,0 +1,39 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// edition:2018
+// cbmc-flags: --unwind 3 --unwinding-assertions
+
+// Check that a protocol state machine keeps its invariant no matter how many times it is
+// suspended while waiting for its peer.
+
+use rmc::futures::pending_nondet;
+
+#[derive(PartialEq)]
+enum State {
+    Idle,
+    Connecting,
+    Connected,
+}
+
+struct Connection {
+    state: State,
+    retries: u8,
+}
+
+impl Connection {
+    async fn connect(&mut self) {
+        self.state = State::Connecting;
+        // Wait for the handshake. The peer may or may not answer right away.
+        pending_nondet().await;
+        self.retries += 1;
+        self.state = State::Connected;
+    }
+}
+
+fn main() {
+    let mut conn = Connection { state: State::Idle, retries: 0 };
+    rmc::block_on(conn.connect());
+    assert!(conn.state == State::Connected);
+    assert!(conn.retries == 1);
+}
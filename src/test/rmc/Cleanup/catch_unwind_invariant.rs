@@ -0,0 +1,44 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// Check that destructors run while unwinding, so a guard can restore an invariant that
+// holds again once the panic has been caught.
+// rmc-flags: --panic-unwind
+use std::panic;
+
+struct Counter {
+    value: u32,
+    in_use: bool,
+}
+
+struct Guard<'a> {
+    counter: &'a mut Counter,
+}
+
+impl<'a> Drop for Guard<'a> {
+    fn drop(&mut self) {
+        self.counter.in_use = false;
+    }
+}
+
+fn increment(counter: &mut Counter, amount: u32) {
+    counter.in_use = true;
+    let guard = Guard { counter };
+    if amount > 100 {
+        panic!("amount is too large");
+    }
+    guard.counter.value += amount;
+}
+
+fn main() {
+    let amount: u32 = rmc::nondet();
+    let mut counter = Counter { value: 0, in_use: false };
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| increment(&mut counter, amount)));
+    assert!(!counter.in_use);
+    assert!(result.is_err() == (amount > 100));
+    if result.is_ok() {
+        assert!(counter.value == amount);
+    } else {
+        assert!(counter.value == 0);
+    }
+}
@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// A panic that is not caught must still be reported as a failure when panics unwind.
+// rmc-flags: --panic-unwind
+// rmc-verify-fail
+
+fn main() {
+    let x: u8 = rmc::nondet();
+    let _ = std::panic::catch_unwind(|| {
+        assert!(x != 1);
+    });
+    assert!(x != 2, "This panic is not caught");
+}
@@ -1,17 +1,11 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
-// We currently do not support stack unwinding panic strategy. Once we do, this testcase should
-// fail during the verification with both the panic and the assertion failing.
-// https://github.com/model-checking/rmc/issues/692
+// With the unwind panic strategy, this testcase should fail during the verification with both
+// the panic and the assertion in the destructor failing.
 //
-// To run manually, execute:
-// ```
-// RUSTFLAGS="--C panic=unwind --crate-type lib" rmc unwind_fixme.rs  --function create
-// ```
-//
-// compile-flags: --C panic=unwind --crate-type lib
-// rmc-flags: --function create
+// compile-flags: --crate-type lib
+// rmc-flags: --panic-unwind --function create
 // rmc-verify-fail
 
 pub struct DummyResource {
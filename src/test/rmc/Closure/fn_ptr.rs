@@ -0,0 +1,20 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that closures that capture nothing can be coerced to function pointers.
+
+fn apply(f: fn(u8, u8) -> u8, a: u8, b: u8) -> u8 {
+    f(a, b)
+}
+
+fn main() {
+    let table: [fn(u8) -> bool; 2] = [|x| x % 2 == 0, |x| x > 100];
+    let x: u8 = rmc::any();
+    assert!(table[0](x) == (x % 2 == 0));
+    assert!(table[1](x) == (x > 100));
+
+    assert!(apply(|a, b| a.wrapping_add(b), 200, 100) == 44);
+
+    let no_args: fn() -> u32 = || 42;
+    assert!(no_args() == 42);
+}
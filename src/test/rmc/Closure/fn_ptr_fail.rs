@@ -0,0 +1,11 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Check that an assertion inside a closure called through a function pointer is reported.
+
+fn main() {
+    let check: fn(u8) = |x| assert!(x < 100);
+    check(rmc::any());
+}
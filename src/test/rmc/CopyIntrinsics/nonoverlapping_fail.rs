@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// `copy_nonoverlapping` requires the source and destination regions not to overlap.
+use std::ptr;
+
+fn main() {
+    let mut arr = [1, 2, 3, 4];
+    let src: *const i32 = arr.as_ptr();
+    unsafe {
+        let dst: *mut i32 = arr.as_mut_ptr().add(1);
+        ptr::copy_nonoverlapping(src, dst, 2);
+    }
+}
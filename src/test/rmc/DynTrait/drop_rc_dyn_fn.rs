@@ -0,0 +1,34 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that dropping the last `Rc` to a closure drops its captured values.
+
+use std::rc::Rc;
+
+static mut DROPPED: bool = false;
+
+struct Captured(u32);
+
+impl Drop for Captured {
+    fn drop(&mut self) {
+        unsafe {
+            DROPPED = true;
+        }
+    }
+}
+
+fn main() {
+    let captured = Captured(5);
+    let f: Rc<dyn Fn() -> u32> = Rc::new(move || captured.0);
+    let g = f.clone();
+    assert!(f() == 5);
+    drop(f);
+    unsafe {
+        assert!(!DROPPED);
+    }
+    assert!(g() == 5);
+    drop(g);
+    unsafe {
+        assert!(DROPPED);
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Check that the drop of a closure behind an `Rc` is not skipped.
+
+use std::rc::Rc;
+
+static mut DROPPED: bool = false;
+
+struct Captured(u32);
+
+impl Drop for Captured {
+    fn drop(&mut self) {
+        unsafe {
+            DROPPED = true;
+        }
+    }
+}
+
+fn main() {
+    let captured = Captured(5);
+    let f: Rc<dyn Fn() -> u32> = Rc::new(move || captured.0);
+    assert!(f() == 5);
+    drop(f);
+    unsafe {
+        rmc::expect_fail(!DROPPED, "the closure was dropped");
+    }
+}
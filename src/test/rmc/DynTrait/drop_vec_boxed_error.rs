@@ -0,0 +1,40 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// cbmc-flags: --unwind 3 --unwinding-assertions
+
+// Check that dropping a vector of boxed trait objects drops every element.
+
+use std::error::Error;
+use std::fmt;
+
+static mut DROPPED: u32 = 0;
+
+#[derive(Debug)]
+struct MyError;
+
+impl fmt::Display for MyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "my error")
+    }
+}
+
+impl Error for MyError {}
+
+impl Drop for MyError {
+    fn drop(&mut self) {
+        unsafe {
+            DROPPED += 1;
+        }
+    }
+}
+
+fn main() {
+    {
+        let errors: Vec<Box<dyn Error>> = vec![Box::new(MyError), Box::new(MyError)];
+        assert!(errors.len() == 2);
+    }
+    unsafe {
+        assert!(DROPPED == 2);
+    }
+}
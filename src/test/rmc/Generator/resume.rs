@@ -0,0 +1,26 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that a generator keeps its upvars and its saved locals across suspension points.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+use std::pin::Pin;
+
+fn main() {
+    let x: u32 = rmc::any();
+    rmc::assume(x < 100);
+    let mut generator = move |step: u32| {
+        // `sum` is live across both suspension points, `tmp` only across the first one.
+        let mut sum = x;
+        let tmp = sum + step;
+        let step = yield sum;
+        sum = tmp + step;
+        yield sum;
+        sum * 2
+    };
+    assert!(Pin::new(&mut generator).resume(1) == GeneratorState::Yielded(x));
+    assert!(Pin::new(&mut generator).resume(2) == GeneratorState::Yielded(x + 3));
+    assert!(Pin::new(&mut generator).resume(3) == GeneratorState::Complete(2 * x + 6));
+}
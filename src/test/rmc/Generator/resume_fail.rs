@@ -0,0 +1,26 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Check that the state of a generator is not mixed up between its variants.
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+use std::pin::Pin;
+
+fn main() {
+    let mut generator = || {
+        let first: u8 = rmc::any();
+        yield first;
+        let second: u8 = rmc::any();
+        yield second;
+        first == second
+    };
+    let first = match Pin::new(&mut generator).resume(()) {
+        GeneratorState::Yielded(v) => v,
+        GeneratorState::Complete(_) => unreachable!(),
+    };
+    assert!(Pin::new(&mut generator).resume(()) == GeneratorState::Yielded(first));
+}
@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --simple-hashing
+
+// Check that hashes are consistent for a given `RandomState`, but otherwise arbitrary.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash, Hasher};
+
+fn hash<T: Hash>(state: &RandomState, value: T) -> u64 {
+    let mut hasher = state.build_hasher();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn main() {
+    let state = RandomState::new();
+    let x: u64 = rmc::any();
+    let y: u64 = rmc::any();
+    assert!(hash(&state, x) == hash(&state, x));
+    if x != y {
+        // Distinct integers have distinct hashes.
+        assert!(hash(&state, x) != hash(&state, y));
+    }
+    let other = RandomState::new();
+    rmc::expect_fail(hash(&state, x) == hash(&other, x), "Keys are arbitrary");
+}
@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --simple-hashing
+// cbmc-flags: --unwind 17 --unwinding-assertions
+
+use std::collections::HashMap;
+
+fn main() {
+    let k1: u32 = rmc::any();
+    let k2: u32 = rmc::any();
+    let mut map = HashMap::new();
+    map.insert(k1, 1);
+    map.insert(k2, 2);
+    if k1 == k2 {
+        assert!(map.len() == 1);
+        assert!(map[&k1] == 2);
+    } else {
+        assert!(map.len() == 2);
+        assert!(map[&k1] == 1);
+        assert!(map[&k2] == 2);
+    }
+    assert!(map.remove(&k2) == Some(2));
+    assert!(!map.contains_key(&k2));
+}
@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --simple-hashing
+// cbmc-flags: --unwind 17 --unwinding-assertions
+
+use std::collections::HashSet;
+
+fn main() {
+    let mut set = HashSet::new();
+    assert!(set.insert("foo"));
+    assert!(set.insert("bar"));
+    assert!(!set.insert("foo"));
+    assert!(set.len() == 2);
+    assert!(set.contains("bar"));
+    assert!(!set.contains("baz"));
+}
@@ -0,0 +1,13 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// `i8::MIN / -1` overflows, which is undefined behavior for `unchecked_div`.
+#![feature(core_intrinsics)]
+use std::intrinsics::unchecked_div;
+
+fn main() {
+    let divisor: i8 = rmc::any();
+    rmc::assume(divisor != 0);
+    let _ = unsafe { unchecked_div(i8::MIN, divisor) };
+}
@@ -0,0 +1,20 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Checks that the UB checks for unchecked intrinsics hold on well-defined inputs.
+#![feature(core_intrinsics)]
+use std::intrinsics::{unchecked_div, unchecked_rem, unchecked_shl, unchecked_shr};
+
+fn main() {
+    let a: i32 = rmc::any();
+    let b: i32 = rmc::any();
+    rmc::assume(b != 0 && !(a == i32::MIN && b == -1));
+    let shift: u32 = rmc::any();
+    rmc::assume(shift < 32);
+    unsafe {
+        assert!(unchecked_div(a, b) == a / b);
+        assert!(unchecked_rem(a, b) == a % b);
+        assert!(unchecked_shl(1u32, shift) == 1u32 << shift);
+        assert!(unchecked_shr(u32::MAX, shift) == u32::MAX >> shift);
+    }
+}
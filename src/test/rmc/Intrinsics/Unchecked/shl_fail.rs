@@ -0,0 +1,13 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// Shifting by the bit width of the type is undefined behavior.
+#![feature(core_intrinsics)]
+use std::intrinsics::unchecked_shl;
+
+fn main() {
+    let shift: u32 = rmc::any();
+    rmc::assume(shift <= 32);
+    let _ = unsafe { unchecked_shl(1u32, shift) };
+}
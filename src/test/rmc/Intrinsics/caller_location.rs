@@ -0,0 +1,29 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that `Location::caller()` reports the call site of `#[track_caller]` functions,
+// including when the location is forwarded through several of them.
+use std::panic::Location;
+
+#[track_caller]
+fn get_caller() -> &'static Location<'static> {
+    Location::caller()
+}
+
+#[track_caller]
+fn forward_caller() -> &'static Location<'static> {
+    get_caller()
+}
+
+fn main() {
+    let loc = get_caller();
+    assert!(loc.line() == 19);
+    assert!(loc.column() == 15);
+
+    let forwarded = forward_caller();
+    assert!(forwarded.line() == 23);
+
+    // Function pointers to `#[track_caller]` functions go through a reify shim.
+    let fn_ptr: fn() -> &'static Location<'static> = get_caller;
+    let _ = fn_ptr();
+}
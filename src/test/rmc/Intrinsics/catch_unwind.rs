@@ -3,6 +3,7 @@
 
 // https://doc.rust-lang.org/std/panic/fn.catch_unwind.html
 // Stable way of calling the `try` intrinsic.
+// rmc-flags: --panic-unwind
 use std::panic;
 
 fn main() {
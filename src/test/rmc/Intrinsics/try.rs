@@ -0,0 +1,36 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that the `try` intrinsic catches panics and calls the catch function.
+// See discussion on https://github.com/model-checking/rmc/issues/267
+// rmc-flags: --panic-unwind
+#![feature(core_intrinsics)]
+use std::intrinsics::r#try;
+
+fn may_panic(data: *mut u8) {
+    unsafe {
+        if *data > 10 {
+            panic!("too large");
+        }
+        *data += 1;
+    }
+}
+
+fn catch(data: *mut u8, _payload: *mut u8) {
+    unsafe {
+        *data = 0;
+    }
+}
+
+fn main() {
+    let input: u8 = rmc::nondet();
+    let mut data = input;
+    let result = unsafe { r#try(may_panic, &mut data as *mut u8, catch) };
+    if input > 10 {
+        assert!(result == 1);
+        assert!(data == 0);
+    } else {
+        assert!(result == 0);
+        assert!(data == input + 1);
+    }
+}
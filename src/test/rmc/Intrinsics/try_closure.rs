@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 // See discussion on https://github.com/model-checking/rmc/issues/267
+// rmc-flags: --panic-unwind
 #![feature(core_intrinsics)]
 use std::intrinsics::r#try;
 
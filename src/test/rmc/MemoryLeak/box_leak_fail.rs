@@ -0,0 +1,11 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// Check that `#[rmc::check_leaks]` reports a leaked box.
+
+#[rmc::check_leaks]
+fn main() {
+    let x: &'static mut u32 = Box::leak(Box::new(1));
+    *x += 1;
+}
@@ -0,0 +1,11 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --check-leaks
+// rmc-verify-fail
+
+// Check that `mem::forget` leaks the allocation of a box.
+
+fn main() {
+    let b = Box::new(1u32);
+    std::mem::forget(b);
+}
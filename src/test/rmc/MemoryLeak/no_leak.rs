@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --check-leaks
+// cbmc-flags: --unwind 3 --unwinding-assertions
+
+// Check that values dropped before the harness returns are not reported as leaked.
+
+use std::rc::Rc;
+
+fn main() {
+    let b = Box::new(1u32);
+    let mut v = vec![*b, 2];
+    v.push(3);
+    let rc = Rc::new(v);
+    let other = rc.clone();
+    assert!(other.len() == 3);
+}
@@ -0,0 +1,21 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --check-leaks
+
+// Check that breaking a cycle with a `Weak` reference frees both nodes.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+struct Node {
+    parent: RefCell<Weak<Node>>,
+    child: RefCell<Option<Rc<Node>>>,
+}
+
+fn main() {
+    let parent = Rc::new(Node { parent: RefCell::new(Weak::new()), child: RefCell::new(None) });
+    let child = Rc::new(Node { parent: RefCell::new(Weak::new()), child: RefCell::new(None) });
+    *child.parent.borrow_mut() = Rc::downgrade(&parent);
+    *parent.child.borrow_mut() = Some(child.clone());
+    assert!(child.parent.borrow().upgrade().is_some());
+}
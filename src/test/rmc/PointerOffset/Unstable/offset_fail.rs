@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-verify-fail
+
+// `offset` must stay within the allocation (one past the end is allowed).
+#![feature(core_intrinsics)]
+use std::intrinsics::offset;
+
+fn main() {
+    let a = [0; 5];
+    let ptr: *const i32 = &a[0];
+    unsafe {
+        let _end = offset(ptr, 5);
+        let _past_end = offset(ptr, 6);
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --prune-unreachable
+
+// Check that pruning unreachable symbols keeps the methods called through a vtable.
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+struct Rectangle(u32, u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> u32 {
+        self.0 * self.1
+    }
+}
+
+fn unused() -> u32 {
+    Rectangle(1, 2).area()
+}
+
+fn main() {
+    let shapes: [Box<dyn Shape>; 2] = [Box::new(Square(3)), Box::new(Rectangle(2, 5))];
+    assert!(shapes[0].area() == 9);
+    assert!(shapes[1].area() == 10);
+}
@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --prune-unreachable
+// rmc-verify-fail
+
+// Check that pruning unreachable symbols keeps the functions whose address is stored in a
+// static, so that the failing assertion is still found.
+
+fn ok(x: u8) -> bool {
+    x < 10
+}
+
+fn fails(x: u8) -> bool {
+    assert!(x > 10);
+    true
+}
+
+static CHECKS: [fn(u8) -> bool; 2] = [ok, fails];
+
+fn main() {
+    let i: usize = rmc::nondet();
+    rmc::assume(i < CHECKS.len());
+    assert!(CHECKS[i](5));
+}
@@ -0,0 +1,15 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --prune-unreachable --function check
+
+// Check that pruning unreachable symbols keeps the function selected with `--function`.
+
+#[no_mangle]
+pub fn check() {
+    let v = vec![1, 2, 3];
+    assert!(v.iter().sum::<i32>() == 6);
+}
+
+fn main() {
+    assert!(1 + 1 == 2);
+}
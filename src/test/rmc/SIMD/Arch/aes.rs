@@ -0,0 +1,40 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check the models of the AES-NI intrinsics against the test vectors of the Intel AES-NI white
+//! paper, and against the results of the hardware instructions.
+
+use std::arch::x86_64::*;
+
+fn as_u64x2(v: __m128i) -> (u64, u64) {
+    let [lo, hi]: [u64; 2] = unsafe { std::mem::transmute(v) };
+    (hi, lo)
+}
+
+fn main() {
+    unsafe {
+        let state = _mm_set_epi64x(0x7b5b546573745665, 0x63746f725d53475d);
+        let round_key = _mm_set_epi64x(0x4869285368617929, 0x5b477565726f6e5d);
+        assert!(
+            as_u64x2(_mm_aesenc_si128(state, round_key))
+                == (0xa8311c2f9fdba3c5, 0x8b104b58ded7e595)
+        );
+        assert!(
+            as_u64x2(_mm_aesenclast_si128(state, round_key))
+                == (0xc7fb881e938c5964, 0x177ec42553fdc611)
+        );
+        assert!(
+            as_u64x2(_mm_aesdec_si128(state, round_key))
+                == (0x138ac342faea2787, 0xb58eb95eb730392a)
+        );
+        assert!(
+            as_u64x2(_mm_aesdeclast_si128(state, round_key))
+                == (0xc5a391ef6b317f95, 0xd410637b72a593d0)
+        );
+        assert!(as_u64x2(_mm_aesimc_si128(state)) == (0x627a6f6644b109c8, 0x2b18330a81c3b3e5));
+        assert!(
+            as_u64x2(_mm_aeskeygenassist_si128::<0x1b>(state))
+                == (0x4d21393b2139204d, 0x40fb92b3fb92a840)
+        );
+    }
+}
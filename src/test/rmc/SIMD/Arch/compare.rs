@@ -0,0 +1,30 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check the model of the packed floating-point comparisons, which set each lane to all ones if
+//! the comparison holds, or to zero otherwise.
+
+use std::arch::x86_64::*;
+
+fn mask(v: __m128) -> [u32; 4] {
+    unsafe { std::mem::transmute(v) }
+}
+
+fn main() {
+    unsafe {
+        let a = _mm_setr_ps(1.0, 2.0, f32::NAN, 4.0);
+        let b = _mm_setr_ps(1.0, 1.0, 3.0, 5.0);
+        assert!(mask(_mm_cmpeq_ps(a, b)) == [u32::MAX, 0, 0, 0]);
+        assert!(mask(_mm_cmplt_ps(a, b)) == [0, 0, 0, u32::MAX]);
+        assert!(mask(_mm_cmpneq_ps(a, b)) == [0, u32::MAX, u32::MAX, u32::MAX]);
+        assert!(mask(_mm_cmpunord_ps(a, b)) == [0, 0, u32::MAX, 0]);
+        assert!(mask(_mm_cmp_ps::<_CMP_NEQ_OQ>(a, b)) == [0, u32::MAX, 0, u32::MAX]);
+        assert!(mask(_mm_cmp_ps::<_CMP_TRUE_UQ>(a, b)) == [u32::MAX; 4]);
+
+        // A lane is ordered with itself if and only if it is not NaN.
+        let x: f64 = rmc::any();
+        let ordered: [u64; 4] =
+            std::mem::transmute(_mm256_cmp_pd::<_CMP_ORD_Q>(_mm256_set1_pd(x), _mm256_set1_pd(x)));
+        assert!(ordered.iter().all(|lane| (*lane != 0) == !x.is_nan()));
+    }
+}
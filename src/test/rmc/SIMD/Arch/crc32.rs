@@ -0,0 +1,22 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check the model of the SSE4.2 `crc32` instructions, which compute the CRC-32C checksum.
+
+use std::arch::x86_64::*;
+
+fn main() {
+    unsafe {
+        assert!(_mm_crc32_u8(0, b'a') == 0x93ad1061);
+        assert!(_mm_crc32_u16(7, 0xbeef) == 0xf826d1a9);
+        assert!(_mm_crc32_u32(0xffffffff, 0x12345678) == 0x4dece20c);
+        assert!(_mm_crc32_u64(0xffffffff, 0x123456789abcdef0) == 0xd95b664b);
+
+        // Accumulating the checksum one byte at a time is the same as one word at a time.
+        let data: u32 = rmc::any();
+        let crc: u32 = rmc::any();
+        let bytes = data.to_le_bytes();
+        let by_byte = bytes.iter().fold(crc, |crc, b| _mm_crc32_u8(crc, *b));
+        assert!(by_byte == _mm_crc32_u32(crc, data));
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check the models of the movemask intrinsics, which gather the sign bit of each lane.
+
+use std::arch::x86_64::*;
+
+fn main() {
+    unsafe {
+        let bytes = _mm_setr_epi8(-1, 0, 1, -128, 127, 0, 0, 0, 0, 0, 0, 0, 0, 0, -5, 0);
+        assert!(_mm_movemask_epi8(bytes) == 0b0100_0000_0000_1001);
+
+        let wide = _mm256_set1_epi8(-1);
+        assert!(_mm256_movemask_epi8(wide) == -1);
+
+        let floats = _mm_setr_ps(-1.0, 2.0, -0.0, f32::NAN);
+        assert!(_mm_movemask_ps(floats) == 0b0101);
+        let doubles = _mm256_setr_pd(1.0, -2.0, f64::NEG_INFINITY, 0.0);
+        assert!(_mm256_movemask_pd(doubles) == 0b0110);
+
+        // The mask of a byte is set if and only if the byte is negative.
+        let x: i8 = rmc::any();
+        assert!((_mm_movemask_epi8(_mm_set1_epi8(x)) != 0) == (x < 0));
+    }
+}
@@ -0,0 +1,17 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check the model of `rdrand`, which may fail to produce a random value.
+
+use std::arch::x86_64::*;
+
+fn main() {
+    let mut value = 42;
+    let success = unsafe { _rdrand32_step(&mut value) };
+    assert!(success == 0 || success == 1);
+    if success == 0 {
+        assert!(value == 0);
+    }
+    rmc::expect_fail(success == 1, "rdrand may fail");
+    rmc::expect_fail(value == 42, "rdrand returns an arbitrary value");
+}
@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check the models of the byte shuffle and lane permutation intrinsics.
+
+use std::arch::x86_64::*;
+
+fn main() {
+    unsafe {
+        let a = _mm_setr_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+        // Reverse the bytes, but zero the bytes whose control byte has its sign bit set.
+        let control = _mm_setr_epi8(15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, -1, 0x10);
+        let r: [u8; 16] = std::mem::transmute(_mm_shuffle_epi8(a, control));
+        assert!(r == [15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 0, 0]);
+
+        // `_mm256_shuffle_epi8` shuffles each 128-bit lane separately.
+        let a = _mm256_setr_epi64x(0, 0, 0x0101010101010101, 0x0101010101010101);
+        let r: [u8; 32] = std::mem::transmute(_mm256_shuffle_epi8(a, _mm256_setzero_si256()));
+        assert!(r[0..16].iter().all(|b| *b == 0));
+        assert!(r[16..32].iter().all(|b| *b == 1));
+
+        let a = _mm256_setr_epi32(10, 11, 12, 13, 14, 15, 16, 17);
+        let idx = _mm256_setr_epi32(7, 6, 5, 4, 3, 2, 1, 8);
+        let r: [i32; 8] = std::mem::transmute(_mm256_permutevar8x32_epi32(a, idx));
+        assert!(r == [17, 16, 15, 14, 13, 12, 11, 10]);
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// cbmc-flags: --unwind 10 --unwinding-assertions
+
+//! Check that command line arguments and environment variables are arbitrary but bounded.
+
+use std::env;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    assert!(!args.is_empty());
+    assert!(args.len() <= rmc::std_models::MAX_ARGS);
+    assert!(env::args().len() == args.len());
+    match env::var("HOME") {
+        Ok(home) => assert!(home.len() <= rmc::std_models::MAX_STRING_LEN),
+        Err(env::VarError::NotPresent) => {}
+        Err(env::VarError::NotUnicode(_)) => unreachable!(),
+    }
+    let verbose = env::var_os("VERBOSE").is_some();
+    rmc::expect_fail(verbose, "The variable may be set");
+}
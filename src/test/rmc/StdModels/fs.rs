@@ -0,0 +1,20 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// cbmc-flags: --unwind 20 --unwinding-assertions
+
+//! Check that opening a file may fail, and that its contents are arbitrary but bounded.
+
+use std::fs::{self, File};
+use std::io::Read;
+
+fn main() {
+    if let Ok(mut file) = File::open("input.txt") {
+        let mut buf = [0u8; 4];
+        let n = file.read(&mut buf).unwrap_or(0);
+        assert!(n <= buf.len());
+    }
+    if let Ok(contents) = fs::read_to_string("config.toml") {
+        assert!(contents.len() <= rmc::std_models::MAX_FILE_LEN);
+    }
+    rmc::expect_fail(fs::read("data.bin").is_ok(), "Reading a file may fail");
+}
@@ -0,0 +1,16 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// cbmc-flags: --unwind 9 --unwinding-assertions
+
+//! Check that `getrandom` fills the buffer with arbitrary bytes.
+
+extern "C" {
+    fn getrandom(buf: *mut u8, buflen: usize, flags: u32) -> isize;
+}
+
+fn main() {
+    let mut buf = [0u8; 8];
+    let n = unsafe { getrandom(buf.as_mut_ptr(), buf.len(), 0) };
+    assert!(n == 8);
+    rmc::expect_fail(buf[7] == 0, "The bytes are arbitrary");
+}
@@ -0,0 +1,19 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that instants are monotonic, while the system time is arbitrary.
+
+use std::time::{Duration, Instant, SystemTime};
+
+fn main() {
+    let start = Instant::now();
+    let end = Instant::now();
+    assert!(end >= start);
+    let elapsed = end.duration_since(start);
+    assert!(start.elapsed() >= elapsed);
+
+    let now = SystemTime::now();
+    let later = SystemTime::now();
+    rmc::expect_fail(later.duration_since(now).is_ok(), "The system time may go backwards");
+    assert!(now.duration_since(now).unwrap() == Duration::ZERO);
+}
@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --build-target armebv7r-none-eabi --function harness
+
+// Check byte order dependent code on a 32-bit big-endian target.
+
+#![no_std]
+#![no_main]
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[no_mangle]
+pub fn harness() {
+    let x: u32 = 0x0102_0304;
+    let bytes: [u8; 4] = unsafe { core::mem::transmute(x) };
+    assert!(bytes == [1, 2, 3, 4]);
+    assert!(x.to_ne_bytes() == x.to_be_bytes());
+    assert!(u32::from_ne_bytes([0, 0, 0, 1]) == 1);
+    assert!(cfg!(target_endian = "big"));
+
+    let y: usize = 0x0a0b_0c0d;
+    let first = unsafe { *(&y as *const usize as *const u8) };
+    assert!(first == 0x0a);
+}
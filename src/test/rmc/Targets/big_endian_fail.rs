@@ -0,0 +1,21 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --build-target armebv7r-none-eabi --function harness
+// rmc-verify-fail
+
+// Check that code assuming a little-endian byte order fails on a big-endian target.
+
+#![no_std]
+#![no_main]
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[no_mangle]
+pub fn harness() {
+    let x: u16 = 1;
+    let bytes: [u8; 2] = unsafe { core::mem::transmute(x) };
+    assert!(bytes[0] == 1);
+}
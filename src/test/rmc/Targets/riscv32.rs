@@ -0,0 +1,31 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --build-target riscv32imac-unknown-none-elf --function harness
+
+// Check pointer-width-dependent code on a 32-bit RISC-V target.
+
+#![no_std]
+#![no_main]
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[repr(C)]
+struct Header {
+    tag: u8,
+    len: usize,
+    data: *const u8,
+}
+
+#[no_mangle]
+pub fn harness() {
+    assert!(core::mem::size_of::<Header>() == 12);
+    assert!(core::mem::align_of::<Header>() == 4);
+    let header = Header { tag: 1, len: 3, data: core::ptr::null() };
+    assert!(header.tag as usize + header.len == 4);
+    assert!(header.data.is_null());
+    assert!(isize::MIN as i64 == i32::MIN as i64);
+    assert!((-1isize) as usize == u32::MAX as usize);
+}
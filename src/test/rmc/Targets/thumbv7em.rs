@@ -0,0 +1,26 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// rmc-flags: --build-target thumbv7em-none-eabihf --function harness
+
+// Check that usize and pointers are 32 bits wide on a 32-bit ARM microcontroller.
+
+#![no_std]
+#![no_main]
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[no_mangle]
+pub fn harness() {
+    assert!(core::mem::size_of::<usize>() == 4);
+    assert!(core::mem::size_of::<*const u8>() == 4);
+    assert!(core::mem::size_of::<&[u8]>() == 8);
+    assert!(usize::MAX == u32::MAX as usize);
+
+    let x: usize = 0xffff_ffff;
+    assert!(x.checked_add(1).is_none());
+    assert!(x.wrapping_add(1) == 0);
+    assert!((x as u64) < u64::MAX);
+}
@@ -0,0 +1,29 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that thread-local variables are initialized and keep their value between accesses.
+
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static COUNTER: Cell<u32> = Cell::new(10);
+    static NAMES: RefCell<[u8; 3]> = RefCell::new([1, 2, 3]);
+}
+
+fn bump() -> u32 {
+    COUNTER.with(|c| {
+        c.set(c.get() + 1);
+        c.get()
+    })
+}
+
+fn main() {
+    assert!(COUNTER.with(|c| c.get()) == 10);
+    assert!(bump() == 11);
+    assert!(bump() == 12);
+
+    let idx: usize = rmc::any();
+    rmc::assume(idx < 3);
+    NAMES.with(|n| n.borrow_mut()[idx] = 0);
+    assert!(NAMES.with(|n| n.borrow()[idx]) == 0);
+}
@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that each thread has its own copy of a thread-local variable: the writes of a spawned
+// thread do not change the copy of the main thread.
+
+use std::cell::Cell;
+use std::thread;
+
+thread_local! {
+    static COUNTER: Cell<u32> = Cell::new(10);
+}
+
+fn main() {
+    let handle = thread::spawn(|| {
+        // The spawned thread starts with its own initialized copy
+        assert!(COUNTER.with(|c| c.get()) == 10);
+        COUNTER.with(|c| c.set(42));
+        COUNTER.with(|c| c.get())
+    });
+    let theirs = handle.join().unwrap();
+    assert!(theirs == 42);
+    assert!(COUNTER.with(|c| c.get()) == 10);
+}
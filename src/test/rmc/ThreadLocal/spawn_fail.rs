@@ -0,0 +1,20 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Check that the write of a spawned thread to its copy of a thread-local variable is not seen
+// by the main thread.
+
+use std::cell::Cell;
+use std::thread;
+
+thread_local! {
+    static COUNTER: Cell<u32> = Cell::new(10);
+}
+
+fn main() {
+    let handle = thread::spawn(|| COUNTER.with(|c| c.set(42)));
+    handle.join().unwrap();
+    assert!(COUNTER.with(|c| c.get()) == 42);
+}
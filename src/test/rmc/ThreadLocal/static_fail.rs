@@ -0,0 +1,19 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Check that writes to a `#[thread_local]` static are visible to later reads.
+
+#![feature(thread_local)]
+
+#[thread_local]
+static mut VALUE: u8 = 0;
+
+fn main() {
+    let v: u8 = rmc::any();
+    unsafe {
+        VALUE = v;
+        assert!(VALUE == 0);
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that exactly one of two threads racing on `compare_exchange` succeeds.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+fn main() {
+    let flag = Arc::new(AtomicBool::new(false));
+    let other = flag.clone();
+    let handle = thread::spawn(move || {
+        other.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+    });
+    let mine = flag.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok();
+    let theirs = handle.join().unwrap();
+    assert!(mine != theirs);
+    assert!(flag.load(Ordering::SeqCst));
+}
@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that the result of a detached thread is dropped at most once, whether the thread
+// finishes before or after its `JoinHandle` is dropped.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+struct Token(u8);
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    let handle = thread::spawn(|| Token(1));
+    drop(handle);
+    assert!(DROPS.load(Ordering::SeqCst) <= 1);
+}
@@ -0,0 +1,25 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Check that the result of a detached thread is dropped once the thread finishes.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+struct Token(u8);
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        DROPS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    let handle = thread::spawn(|| Token(1));
+    drop(handle);
+    assert!(DROPS.load(Ordering::SeqCst) == 0);
+}
@@ -0,0 +1,18 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// `fetch_add` is a single atomic step, so no update can be lost.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+fn main() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let other = counter.clone();
+    let handle = thread::spawn(move || other.fetch_add(1, Ordering::SeqCst));
+    let mine = counter.fetch_add(1, Ordering::SeqCst);
+    let theirs = handle.join().unwrap();
+    assert!(mine != theirs);
+    assert!(counter.load(Ordering::SeqCst) == 2);
+}
@@ -0,0 +1,25 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// rmc-verify-fail
+
+// Incrementing a counter with a separate load and store is not atomic, so one of the updates
+// can be lost when the other thread runs in between.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+fn increment(counter: &AtomicUsize) {
+    let value = counter.load(Ordering::SeqCst);
+    counter.store(value + 1, Ordering::SeqCst);
+}
+
+fn main() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let other = counter.clone();
+    let handle = thread::spawn(move || increment(&other));
+    increment(&counter);
+    handle.join().unwrap();
+    assert!(counter.load(Ordering::SeqCst) == 2);
+}
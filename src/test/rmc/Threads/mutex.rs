@@ -0,0 +1,22 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that a `Mutex` protects a read-modify-write of the value it guards.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+fn increment(counter: &Mutex<u32>) {
+    let mut guard = counter.lock().unwrap();
+    let value = *guard;
+    *guard = value + 1;
+}
+
+fn main() {
+    let counter = Arc::new(Mutex::new(0));
+    let other = counter.clone();
+    let handle = thread::spawn(move || increment(&other));
+    increment(&counter);
+    handle.join().unwrap();
+    assert!(*counter.lock().unwrap() == 2);
+}
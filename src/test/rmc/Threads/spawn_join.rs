@@ -0,0 +1,14 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that `join` returns the value computed by the spawned thread.
+
+use std::thread;
+
+fn main() {
+    let x: u32 = rmc::any();
+    rmc::assume(x < 100);
+    let handle = thread::spawn(move || x + 1);
+    let y = handle.join().unwrap();
+    assert!(y == x + 1);
+}
@@ -339,6 +339,11 @@ fn run_cargo_rmc_test(&self) {
 
     /// Common method used to run RMC on a single file test.
     fn run_rmc(&self) -> ProcRes {
+        self.run_rmc_with(&self.testpaths.file, &[])
+    }
+
+    /// Runs RMC on `input` with `extra_args` in addition to the flags of the test.
+    fn run_rmc_with(&self, input: &PathBuf, extra_args: &[&str]) -> ProcRes {
         // Other modes call self.compile_test(...). However, we cannot call it here for two reasons:
         // 1. It calls rustc instead of RMC
         // 2. It may pass some options that do not make sense for RMC
@@ -353,8 +358,9 @@ fn run_rmc(&self) -> ProcRes {
         }
         // Pass the test path along with RMC and CBMC flags parsed from comments at the top of the test file.
         rmc.args(&self.props.rmc_flags)
+            .args(extra_args)
             .arg("--input")
-            .arg(&self.testpaths.file)
+            .arg(input)
             .arg("--cbmc-args")
             .args(&self.props.cbmc_flags);
         self.add_rmc_dir_to_path(&mut rmc);
@@ -364,11 +370,58 @@ fn run_rmc(&self) -> ProcRes {
     /// Runs RMC on the test file specified by `self.testpaths.file`. An error
     /// message is printed to stdout if verification output does not contain
     /// the expected output in `expected` file.
+    ///
+    /// For each `expected.<suffix>` file next to the test file, e.g.
+    /// `expected.restrictions.json`, RMC also keeps the artifact `<stem>.<suffix>`.
+    /// Every non-empty line of the `expected.<suffix>` file is a regular
+    /// expression that must match somewhere in that artifact. Tests that check
+    /// artifacts may omit the `expected` file.
     fn run_expected_test(&self) {
-        let proc_res = self.run_rmc();
-        let expected =
-            fs::read_to_string(self.testpaths.file.parent().unwrap().join("expected")).unwrap();
-        self.verify_output(&proc_res, &expected);
+        let test_dir = self.testpaths.file.parent().unwrap();
+        let mut artifacts = vec![];
+        for entry in fs::read_dir(test_dir).unwrap() {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_str().unwrap();
+            if let Some(suffix) = name.strip_prefix("expected.") {
+                artifacts.push((suffix.to_string(), path));
+            }
+        }
+        artifacts.sort();
+
+        // RMC writes its artifacts next to the input, so we run it on a copy of the test file
+        // in the output directory if we check them
+        let (input, extra_args): (PathBuf, &[&str]) = if artifacts.is_empty() {
+            (self.testpaths.file.clone(), &[])
+        } else {
+            let output_dir = self.output_base_dir();
+            create_dir_all(&output_dir).unwrap();
+            let input = output_dir.join(self.testpaths.file.file_name().unwrap());
+            fs::copy(&self.testpaths.file, &input).unwrap();
+            (input, &["--keep-temps"])
+        };
+        let proc_res = self.run_rmc_with(&input, extra_args);
+        let expected_file = test_dir.join("expected");
+        if artifacts.is_empty() || expected_file.exists() {
+            self.verify_output(&proc_res, &fs::read_to_string(expected_file).unwrap());
+        }
+        for (suffix, expected_file) in artifacts {
+            let artifact = input.with_extension(&suffix);
+            let contents = fs::read_to_string(&artifact).unwrap_or_else(|_| {
+                self.fatal_proc_rec(
+                    &format!("test failed: missing artifact {:?}", artifact),
+                    &proc_res,
+                )
+            });
+            let patterns = fs::read_to_string(&expected_file).unwrap();
+            for pattern in patterns.lines().filter(|line| !line.is_empty()) {
+                if !Regex::new(pattern).unwrap().is_match(&contents) {
+                    self.fatal_proc_rec(
+                        &format!("test failed: expected {:?} to match: {}", artifact, pattern),
+                        &proc_res,
+                    );
+                }
+            }
+        }
     }
 
     /// Runs RMC with stub implementations of various data structures.
@@ -0,0 +1,87 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Links the symbol tables that RMC generates for each crate (`*.symtab.json`), e.g. those of a
+//! crate, of its dependencies and of the standard library built together with them
+//! (`-Z build-std`), into a single symbol table. `cargo rmc --link-goto` uses it instead of
+//! `goto-cc`; this is opt-in and still experimental.
+//!
+//! Usage: `rmc-link-goto OUTPUT INPUT...`, where each input is either a symbol table or a
+//! directory, whose symbol tables are all linked.
+
+use cbmc::irep::link::link;
+use cbmc::irep::SymbolTable;
+use serde::Deserialize;
+use std::env;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process;
+
+const SYMTAB_SUFFIX: &str = ".symtab.json";
+
+/// Symbol tables are deeply nested, so we parse them without recursion limit, on a thread with
+/// a large stack.
+const STACK_SIZE: usize = 1 << 30;
+
+/// The symbol tables in `path`, in a deterministic order.
+fn symbol_table_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if path.is_dir() {
+        let entries =
+            path.read_dir().map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let mut files = vec![];
+        for entry in entries {
+            let file = entry.map_err(|e| format!("Cannot read {}: {}", path.display(), e))?.path();
+            if file.to_string_lossy().ends_with(SYMTAB_SUFFIX) {
+                files.push(file);
+            }
+        }
+        files.sort();
+        Ok(files)
+    } else if path.is_file() {
+        Ok(vec![path.to_path_buf()])
+    } else {
+        Err(format!("{} is neither a symbol table nor a directory", path.display()))
+    }
+}
+
+fn read_symbol_table(path: &Path) -> Result<SymbolTable, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+    deserializer.disable_recursion_limit();
+    SymbolTable::deserialize(&mut deserializer)
+        .map_err(|e| format!("Cannot parse {}: {}", path.display(), e))
+}
+
+fn run(output: &Path, inputs: &[PathBuf]) -> Result<(), String> {
+    let mut tables = vec![];
+    for input in inputs {
+        for file in symbol_table_files(input)? {
+            let table = read_symbol_table(&file)?;
+            tables.push((file.display().to_string(), table));
+        }
+    }
+    let linked = link(tables).map_err(|errors| errors.join("\n"))?;
+    let file =
+        File::create(output).map_err(|e| format!("Cannot create {}: {}", output.display(), e))?;
+    serde_json::to_writer(BufWriter::new(file), &linked)
+        .map_err(|e| format!("Cannot write {}: {}", output.display(), e))
+}
+
+pub fn main() {
+    let args: Vec<PathBuf> = env::args_os().skip(1).map(PathBuf::from).collect();
+    if args.len() < 2 {
+        eprintln!("Usage: rmc-link-goto OUTPUT INPUT...");
+        process::exit(2);
+    }
+    let result = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || run(&args[0], &args[1..]))
+        .unwrap()
+        .join()
+        .unwrap();
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
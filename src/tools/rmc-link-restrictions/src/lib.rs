@@ -0,0 +1,204 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Links the function pointer restrictions that RMC writes for each crate
+//! (`*.restrictions.json`) into the restrictions that CBMC expects, which map each call site
+//! to its possible targets.
+//!
+//! A call site may call any possible method of its trait-defined method that some crate
+//! found, e.g. an implementation of a trait in a dependency.
+
+use cbmc::InternedString;
+use rmc_restrictions::{TraitDefinedMethod, VtableCtxResults};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+const RESTRICTIONS_SUFFIX: &str = ".restrictions.json";
+
+/// The linked restrictions, by CBMC call site name.
+pub type LinkedRestrictions = BTreeMap<String, Vec<InternedString>>;
+
+/// The result of linking: the restrictions, and warnings about them.
+#[derive(Debug)]
+pub struct LinkResult {
+    pub restrictions: LinkedRestrictions,
+    pub warnings: Vec<String>,
+}
+
+/// Reads the restrictions in `path`, either a restrictions file or a directory, whose
+/// restrictions files are all read in a deterministic order. Each is given with its file,
+/// for the error messages of `link_function_pointer_restrictions`.
+pub fn read_restrictions(path: &Path) -> Result<Vec<(String, VtableCtxResults)>, String> {
+    let files = if path.is_dir() {
+        let entries =
+            path.read_dir().map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let mut files = vec![];
+        for entry in entries {
+            let file = entry.map_err(|e| format!("Cannot read {}: {}", path.display(), e))?.path();
+            if file.to_string_lossy().ends_with(RESTRICTIONS_SUFFIX) {
+                files.push(file);
+            }
+        }
+        files.sort();
+        files
+    } else if path.is_file() && path.to_string_lossy().ends_with(RESTRICTIONS_SUFFIX) {
+        vec![path.to_path_buf()]
+    } else {
+        return Err(format!(
+            "{} is neither a restrictions file nor a directory with restrictions files",
+            path.display()
+        ));
+    };
+    files
+        .iter()
+        .map(|file| Ok((file.display().to_string(), read_restrictions_file(file)?)))
+        .collect()
+}
+
+fn read_restrictions_file(path: &Path) -> Result<VtableCtxResults, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Cannot parse {}: {}", path.display(), e))
+}
+
+/// Links the restrictions of several crates, each given with a name for its origin (e.g. its
+/// file), which is used in the messages:
+///  - The possible methods of a trait-defined method are the union of those of all crates.
+///  - A method that has different vtable indices for the same trait in two crates is an error,
+///    since the crates disagree on the layout of the vtable. Returns all such errors.
+///  - A call site without any possible method gets a warning, since CBMC then cuts off every
+///    path through the call, which makes its verification vacuous.
+pub fn link_function_pointer_restrictions(
+    data_per_crate: Vec<(String, VtableCtxResults)>,
+) -> Result<LinkResult, Vec<String>> {
+    let mut combined_possible_methods: HashMap<TraitDefinedMethod, Vec<InternedString>> =
+        HashMap::new();
+    // Map: (trait name, method) -> (vtable index, origin)
+    let mut method_indices: HashMap<(InternedString, InternedString), (usize, &str)> =
+        HashMap::new();
+    let mut errors = vec![];
+
+    // Combine method possibilities
+    for (origin, crate_data) in &data_per_crate {
+        for entry in &crate_data.possible_methods {
+            let trait_method = &entry.trait_method;
+            let possibilities = combined_possible_methods.entry(trait_method.clone()).or_default();
+            for imp in &entry.possibilities {
+                let key = (trait_method.trait_name, *imp);
+                match method_indices.get(&key) {
+                    Some((idx, other)) if *idx != trait_method.vtable_idx && other != origin => {
+                        errors.push(format!(
+                            "Method {} of {} has vtable index {} in {} but {} in {}",
+                            imp,
+                            trait_method.trait_name,
+                            idx,
+                            other,
+                            trait_method.vtable_idx,
+                            origin
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        method_indices.insert(key, (trait_method.vtable_idx, origin));
+                    }
+                }
+                if !possibilities.contains(imp) {
+                    possibilities.push(*imp);
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // Iterate call sites
+    let mut restrictions = LinkedRestrictions::new();
+    let mut warnings = vec![];
+    for (origin, crate_data) in &data_per_crate {
+        for call_site in &crate_data.call_sites {
+            // CBMC index is 1-indexed:
+            // http://cprover.diffblue.com/md__home_travis_build_diffblue_cbmc_doc_architectural_restrict-function-pointer.html
+            let cbmc_call_site_name =
+                format!("{}.function_pointer_call.1", call_site.function_name);
+            let trait_def = &call_site.trait_method;
+
+            // Look up all possibilities, defaulting to the empty set
+            let possibilities =
+                combined_possible_methods.get(trait_def).cloned().unwrap_or_default();
+            if possibilities.is_empty() {
+                warnings.push(format!(
+                    "Call site {} in {} has no possible targets for method {} of {}, so the paths \
+                    through it are not verified",
+                    call_site.function_name, origin, trait_def.vtable_idx, trait_def.trait_name
+                ));
+            }
+            restrictions.insert(cbmc_call_site_name, possibilities);
+        }
+    }
+    Ok(LinkResult { restrictions, warnings })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rmc_restrictions::{CallSite, PossibleMethodEntry};
+
+    fn method(trait_name: &str, vtable_idx: usize) -> TraitDefinedMethod {
+        TraitDefinedMethod { trait_name: trait_name.into(), vtable_idx }
+    }
+
+    fn results(
+        call_sites: Vec<(TraitDefinedMethod, &str)>,
+        possible_methods: Vec<(TraitDefinedMethod, Vec<&str>)>,
+    ) -> VtableCtxResults {
+        VtableCtxResults {
+            call_sites: call_sites
+                .into_iter()
+                .map(|(trait_method, name)| CallSite { trait_method, function_name: name.into() })
+                .collect(),
+            possible_methods: possible_methods
+                .into_iter()
+                .map(|(trait_method, imps)| PossibleMethodEntry {
+                    trait_method,
+                    possibilities: imps.into_iter().map(InternedString::from).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_link_union() {
+        let first = results(
+            vec![(method("dyn T", 3), "call_1"), (method("dyn U", 3), "call_2")],
+            vec![(method("dyn T", 3), vec!["a", "b"])],
+        );
+        let second = results(vec![], vec![(method("dyn T", 3), vec!["b", "c"])]);
+        let linked =
+            link_function_pointer_restrictions(vec![("a".into(), first), ("b".into(), second)])
+                .unwrap();
+        let targets: Vec<_> = linked.restrictions["call_1.function_pointer_call.1"]
+            .iter()
+            .map(|imp| imp.to_string())
+            .collect();
+        assert_eq!(targets, vec!["a", "b", "c"]);
+        assert!(linked.restrictions["call_2.function_pointer_call.1"].is_empty());
+        assert_eq!(linked.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_link_inconsistent_index() {
+        let first = results(vec![], vec![(method("dyn T", 3), vec!["a"])]);
+        let second = results(vec![], vec![(method("dyn T", 4), vec!["a"])]);
+        let errors =
+            link_function_pointer_restrictions(vec![("a".into(), first), ("b".into(), second)])
+                .unwrap_err();
+        assert_eq!(
+            errors,
+            vec!["Method a of dyn T has vtable index 3 in a but 4 in b".to_string()]
+        );
+    }
+}